@@ -0,0 +1,91 @@
+#!/usr/bin/env bash
+# Starts two Nodes on this machine which join each other as a Cluster.
+#
+#   scripts/cluster-localhost.sh [node count, default 2]
+#
+# Every Node gets its own working path under target/cluster-localhost/node-N,
+# its own ports and the other Nodes as seeds. Both share one sqlite database.
+# Stop the Nodes with Ctrl+C.
+set -euo pipefail
+
+NODES="${1:-2}"
+SECRET="localhost-cluster-secret"
+ROOT="$(cd "$(dirname "$0")/.." && pwd)"
+BASE="$ROOT/target/cluster-localhost"
+
+cargo build --manifest-path "$ROOT/Cargo.toml" --no-default-features --features sqlite,rest-api
+BIN="$ROOT/target/debug/minecloud-node"
+
+node_port() { echo $((5050 + ($1 - 1) * 10)); }
+
+seeds_for() {
+    local self="$1" seeds="" i
+    for ((i = 1; i <= NODES; i++)); do
+        [[ "$i" == "$self" ]] && continue
+        seeds+="${seeds:+,}{\"ip\": \"127.0.0.1\", \"port\": $(node_port "$i")}"
+    done
+    echo "[$seeds]"
+}
+
+mkdir -p "$BASE"
+PIDS=()
+trap 'kill "${PIDS[@]}" 2>/dev/null || true' EXIT
+
+for ((n = 1; n <= NODES; n++)); do
+    dir="$BASE/node-$n"
+    port="$(node_port "$n")"
+    mkdir -p "$dir"
+    cat > "$dir/config.json" <<JSON
+{
+  "name": "Node-$n",
+  "prefix": "[Game Cloud]",
+  "language": "de",
+  "server_host": "127.0.0.1",
+  "max_ram": 2028,
+  "scheduler_interval": 10,
+  "resource_monitor_interval": 5,
+  "placement_policy": "least_loaded",
+  "database": {
+    "typ": "SQLITE",
+    "sqlite": { "file": "$BASE/database.db" },
+    "mysql": {
+      "host": { "ip": "127.0.0.1", "port": 3306 },
+      "username": "minecloud",
+      "password": "minecloud123",
+      "database": "minecloud"
+    }
+  },
+  "node_host": { "ip": "127.0.0.1", "port": $port },
+  "rest_api": { "ip": "127.0.0.1", "port": $((port + 1)) },
+  "rest_api_cors_origins": [],
+  "cluster": {
+    "seeds": $(seeds_for "$n"),
+    "secret": "$SECRET",
+    "heartbeat_interval": 5,
+    "node_timeout": 20
+  },
+  "path": {
+    "task_folder": "~task",
+    "template_folder": "~template",
+    "service_folder": {
+      "temp_folder": "~service/temp",
+      "static_folder": "~service/static"
+    },
+    "system_folder": {
+      "software_config": "~config",
+      "default_task": "~config",
+      "system_plugins_folder": "~config/system_plugins",
+      "software_files_folder": "~config/software_files"
+    }
+  }
+}
+JSON
+
+    # the terminal of the Node reads stdin, keep it open
+    tail -f /dev/null | "$BIN" "-working-path=$dir" > "$dir/node.log" 2>&1 &
+    PIDS+=("$!")
+    echo "Node-$n: node API 127.0.0.1:$port, REST API 127.0.0.1:$((port + 1)), log $dir/node.log"
+done
+
+echo "Cluster of $NODES Nodes started, Ctrl+C stops it"
+wait
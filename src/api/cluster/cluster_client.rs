@@ -1,8 +1,51 @@
 use async_trait::async_trait;
 
+use crate::types::{EntityId, Node, NodeResources, OnlinePlayer, Service};
 use crate::utils::error::CloudResult;
 
 #[async_trait]
 pub trait ClusterClient: Send + Sync {
+    /// contact all seeds and announce this Node
     async fn join_cluster(&self) -> CloudResult<()>;
+
+    /// tell all known Nodes that this Node goes offline
+    async fn leave_cluster(&self) -> CloudResult<()>;
+
+    /// send the heartbeat with the resources of this Node to all known Nodes
+    async fn heartbeat(&self, resources: Option<NodeResources>);
+
+    fn get_local_node(&self) -> &Node;
+
+    /// all known remote Nodes
+    async fn get_nodes(&self) -> Vec<Node>;
+
+    async fn get_node(&self, name: &str) -> Option<Node>;
+
+    /// Remote (Node called) -> a Node joined or sent a heartbeat
+    async fn on_node_seen(&self, node: Node);
+
+    /// Remote (Node called) -> a Node left the Cluster
+    async fn on_node_left(&self, name: &str) -> Option<Node>;
+
+    /// remove all Nodes without heartbeat in the node timeout and return them
+    async fn remove_dead_nodes(&self) -> Vec<Node>;
+
+    /// all local Services of the Node
+    async fn get_services(&self, node: &str) -> CloudResult<Vec<Service>>;
+
+    /// all Players online on the Proxies of the Node
+    async fn get_players(&self, node: &str) -> CloudResult<Vec<OnlinePlayer>>;
+
+    async fn start_service(&self, node: &str, task_name: &str) -> CloudResult<Service>;
+
+    async fn stop_service(&self, node: &str, id: &EntityId, msg: &str) -> CloudResult<()>;
+
+    /// the network maintenance the Node currently has
+    async fn get_maintenance(&self, node: &str) -> CloudResult<bool>;
+
+    async fn broadcast_maintenance(&self, enabled: bool);
+
+    async fn broadcast_service_registered(&self, service: &Service);
+
+    async fn broadcast_service_shutdown(&self, service: &Service);
 }
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::EntityId;
+
+pub const CLUSTER_SECRET_HEADER: &str = "X-Cluster-Secret";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StartServiceRequest {
+    task_name: String,
+}
+
+impl StartServiceRequest {
+    pub fn new(task_name: &str) -> StartServiceRequest {
+        StartServiceRequest {
+            task_name: task_name.to_string(),
+        }
+    }
+
+    pub fn get_task_name(&self) -> &str {
+        &self.task_name
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StopServiceRequest {
+    id: EntityId,
+    msg: String,
+}
+
+impl StopServiceRequest {
+    pub fn new(id: &EntityId, msg: &str) -> StopServiceRequest {
+        StopServiceRequest {
+            id: *id,
+            msg: msg.to_string(),
+        }
+    }
+
+    pub fn get_id(&self) -> &EntityId {
+        &self.id
+    }
+
+    pub fn get_msg(&self) -> &str {
+        &self.msg
+    }
+}
+
+/// Maintenance of the whole Network, sent to all Nodes when it is switched
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NetworkMaintenance {
+    enabled: bool,
+}
+
+impl NetworkMaintenance {
+    pub fn new(enabled: bool) -> NetworkMaintenance {
+        NetworkMaintenance { enabled }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+}
@@ -0,0 +1,288 @@
+use actix_web::{HttpRequest, HttpResponse, web};
+use serde_json::json;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::api::cluster::{
+    CLUSTER_SECRET_HEADER, NetworkMaintenance, StartServiceRequest, StopServiceRequest,
+};
+use crate::cloud::Cloud;
+use crate::log_error;
+use crate::manager::NodeManager;
+use crate::types::{Node, Service};
+use crate::utils::error::CloudError;
+use crate::utils::utils::Utils;
+
+/// Endpoints the other Nodes of the Cluster call, served by the internal API
+pub struct ClusterHandler;
+
+impl ClusterHandler {
+    pub fn configure(cfg: &mut web::ServiceConfig) {
+        cfg.service(
+            web::scope("/cluster")
+                .route("/join", web::post().to(Self::join))
+                .route("/leave", web::post().to(Self::leave))
+                .route("/heartbeat", web::post().to(Self::heartbeat))
+                .route("/services", web::post().to(Self::services))
+                .route("/players", web::post().to(Self::players))
+                .route("/service/start", web::post().to(Self::start_service))
+                .route("/service/stop", web::post().to(Self::stop_service))
+                .route(
+                    "/service/registered",
+                    web::post().to(Self::service_registered),
+                )
+                .route("/service/shutdown", web::post().to(Self::service_shutdown))
+                .route("/maintenance", web::post().to(Self::maintenance))
+                .route(
+                    "/maintenance/changed",
+                    web::post().to(Self::maintenance_changed),
+                ),
+        );
+    }
+
+    /// Returns all known Nodes including this Node
+    async fn join(
+        req: HttpRequest,
+        cloud: web::Data<Arc<RwLock<Cloud>>>,
+        node: web::Json<Node>,
+    ) -> HttpResponse {
+        let node_manager = match Self::authorize(&req, &cloud).await {
+            Ok(node_manager) => node_manager,
+            Err(response) => return response,
+        };
+
+        let cluster = node_manager.get_cluster();
+        cluster.on_node_seen(node.into_inner()).await;
+
+        let mut nodes = cluster.get_nodes().await;
+        nodes.push(cluster.get_local_node().clone());
+        HttpResponse::Ok().json(nodes)
+    }
+
+    async fn leave(
+        req: HttpRequest,
+        cloud: web::Data<Arc<RwLock<Cloud>>>,
+        node: web::Json<Node>,
+    ) -> HttpResponse {
+        let node_manager = match Self::authorize(&req, &cloud).await {
+            Ok(node_manager) => node_manager,
+            Err(response) => return response,
+        };
+
+        if let Some(node) = node_manager
+            .get_cluster()
+            .on_node_left(node.get_name())
+            .await
+        {
+            node_manager.on_remote_node_lost(&node).await;
+        }
+        Self::success()
+    }
+
+    async fn heartbeat(
+        req: HttpRequest,
+        cloud: web::Data<Arc<RwLock<Cloud>>>,
+        node: web::Json<Node>,
+    ) -> HttpResponse {
+        let node_manager = match Self::authorize(&req, &cloud).await {
+            Ok(node_manager) => node_manager,
+            Err(response) => return response,
+        };
+
+        node_manager
+            .get_cluster()
+            .on_node_seen(node.into_inner())
+            .await;
+        Self::success()
+    }
+
+    /// Returns all local Services of this Node
+    async fn services(req: HttpRequest, cloud: web::Data<Arc<RwLock<Cloud>>>) -> HttpResponse {
+        let node_manager = match Self::authorize(&req, &cloud).await {
+            Ok(node_manager) => node_manager,
+            Err(response) => return response,
+        };
+
+        HttpResponse::Ok().json(node_manager.get_local_services().await)
+    }
+
+    /// Returns all Players online on the Proxies of this Node
+    async fn players(req: HttpRequest, cloud: web::Data<Arc<RwLock<Cloud>>>) -> HttpResponse {
+        let node_manager = match Self::authorize(&req, &cloud).await {
+            Ok(node_manager) => node_manager,
+            Err(response) => return response,
+        };
+
+        HttpResponse::Ok().json(node_manager.get_local_players().await)
+    }
+
+    /// Returns the network maintenance of this Node, asked by a Node after joining
+    async fn maintenance(req: HttpRequest, cloud: web::Data<Arc<RwLock<Cloud>>>) -> HttpResponse {
+        if let Err(response) = Self::authorize(&req, &cloud).await {
+            return response;
+        }
+
+        let maintenance_manager = cloud.read().await.get_maintenance_manager();
+        HttpResponse::Ok().json(NetworkMaintenance::new(maintenance_manager.is_enabled()))
+    }
+
+    async fn maintenance_changed(
+        req: HttpRequest,
+        cloud: web::Data<Arc<RwLock<Cloud>>>,
+        body: web::Json<NetworkMaintenance>,
+    ) -> HttpResponse {
+        if let Err(response) = Self::authorize(&req, &cloud).await {
+            return response;
+        }
+
+        let maintenance_manager = cloud.read().await.get_maintenance_manager();
+        match maintenance_manager
+            .on_remote_maintenance(body.is_enabled())
+            .await
+        {
+            Ok(_) => Self::success(),
+            Err(e) => Self::error("maintenance_changed", e),
+        }
+    }
+
+    /// Start a Service from the Task on this Node and return it
+    async fn start_service(
+        req: HttpRequest,
+        cloud: web::Data<Arc<RwLock<Cloud>>>,
+        body: web::Json<StartServiceRequest>,
+    ) -> HttpResponse {
+        let node_manager = match Self::authorize(&req, &cloud).await {
+            Ok(node_manager) => node_manager,
+            Err(response) => return response,
+        };
+
+        match node_manager
+            .start_local_service_from_task(body.get_task_name())
+            .await
+        {
+            Ok(service) => HttpResponse::Ok().json(service),
+            Err(e) => Self::error("start_service", e),
+        }
+    }
+
+    /// Stop the Service in the background, the stop can take up to `time_shutdown_before_kill`.
+    /// The other Nodes get the `service/shutdown` broadcast when it is done.
+    async fn stop_service(
+        req: HttpRequest,
+        cloud: web::Data<Arc<RwLock<Cloud>>>,
+        body: web::Json<StopServiceRequest>,
+    ) -> HttpResponse {
+        let node_manager = match Self::authorize(&req, &cloud).await {
+            Ok(node_manager) => node_manager,
+            Err(response) => return response,
+        };
+
+        let body = body.into_inner();
+        tokio::spawn(async move {
+            node_manager
+                .stop_service(*body.get_id(), body.get_msg())
+                .await;
+        });
+        Self::success()
+    }
+
+    async fn service_registered(
+        req: HttpRequest,
+        cloud: web::Data<Arc<RwLock<Cloud>>>,
+        service: web::Json<Service>,
+    ) -> HttpResponse {
+        let node_manager = match Self::authorize(&req, &cloud).await {
+            Ok(node_manager) => node_manager,
+            Err(response) => return response,
+        };
+
+        match node_manager
+            .on_remote_service_registered(service.into_inner())
+            .await
+        {
+            Ok(_) => Self::success(),
+            Err(e) => Self::error("service_registered", e),
+        }
+    }
+
+    async fn service_shutdown(
+        req: HttpRequest,
+        cloud: web::Data<Arc<RwLock<Cloud>>>,
+        service: web::Json<Service>,
+    ) -> HttpResponse {
+        let node_manager = match Self::authorize(&req, &cloud).await {
+            Ok(node_manager) => node_manager,
+            Err(response) => return response,
+        };
+
+        match node_manager
+            .on_remote_service_shutdown(service.into_inner())
+            .await
+        {
+            Ok(_) => Self::success(),
+            Err(e) => Self::error("service_shutdown", e),
+        }
+    }
+
+    /// Check the Cluster secret and return the NodeManager
+    async fn authorize(
+        req: &HttpRequest,
+        cloud: &web::Data<Arc<RwLock<Cloud>>>,
+    ) -> Result<Arc<NodeManager>, HttpResponse> {
+        let cloud = cloud.read().await;
+        let header = req
+            .headers()
+            .get(CLUSTER_SECRET_HEADER)
+            .and_then(|h| h.to_str().ok());
+
+        if !Self::is_authorized(cloud.get_config().get_cluster().get_secret(), header) {
+            return Err(HttpResponse::Unauthorized().json(json!({
+                "error": "Invalid Cluster secret"
+            })));
+        }
+
+        Ok(cloud.get_node_manager())
+    }
+
+    /// Without a secret this Node is not part of a Cluster and accepts no Node
+    fn is_authorized(secret: &str, header: Option<&str>) -> bool {
+        match header {
+            Some(header) => !secret.is_empty() && Utils::constant_time_eq(secret, header),
+            None => false,
+        }
+    }
+
+    fn success() -> HttpResponse {
+        HttpResponse::Ok().json(json!({ "success": true }))
+    }
+
+    fn error(endpoint: &str, e: CloudError) -> HttpResponse {
+        log_error!(3, "[Cluster] [{}] Error: {}", endpoint, e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": e.to_string()
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ClusterHandler;
+
+    #[test]
+    fn accepts_the_configured_secret() {
+        assert!(ClusterHandler::is_authorized("s3cret", Some("s3cret")));
+    }
+
+    #[test]
+    fn rejects_a_wrong_or_missing_secret() {
+        assert!(!ClusterHandler::is_authorized("s3cret", Some("s3cre")));
+        assert!(!ClusterHandler::is_authorized("s3cret", Some("s3creT")));
+        assert!(!ClusterHandler::is_authorized("s3cret", None));
+    }
+
+    #[test]
+    fn rejects_everything_without_a_secret() {
+        assert!(!ClusterHandler::is_authorized("", Some("")));
+        assert!(!ClusterHandler::is_authorized("", None));
+    }
+}
@@ -1,5 +1,9 @@
 pub use cluster_client::ClusterClient;
+pub use dtos::*;
+pub use handlers::ClusterHandler;
 pub use rest_client::RestClusterClient;
 
 mod cluster_client;
+mod dtos;
+mod handlers;
 mod rest_client;
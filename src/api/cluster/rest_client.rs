@@ -1,28 +1,254 @@
 use async_trait::async_trait;
-use std::sync::{Arc, RwLock};
+use bx::network::address::Address;
+use futures_util::future::join_all;
+use reqwest::{Client, RequestBuilder};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use uuid::Uuid;
 
 use crate::api::cluster::cluster_client::ClusterClient;
+use crate::api::cluster::{
+    CLUSTER_SECRET_HEADER, NetworkMaintenance, StartServiceRequest, StopServiceRequest,
+};
 use crate::config::CloudConfig;
-use crate::types::Node;
-use crate::utils::error::CloudResult;
+use crate::types::{EntityId, Node, NodeResources, OnlinePlayer, Service};
+use crate::utils::error::*;
+use crate::{error, log_info, log_warning};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// the remote Node copies the templates and installs the software before it answers
+const START_SERVICE_TIMEOUT: Duration = Duration::from_secs(120);
 
 pub struct RestClusterClient {
-    nodes: RwLock<Vec<Node>>,
+    client: Client,
+    local_node: Node,
+    nodes: RwLock<HashMap<String, Node>>,
     cloud_config: Arc<CloudConfig>,
 }
 
 impl RestClusterClient {
     pub fn new(cloud_config: Arc<CloudConfig>) -> RestClusterClient {
         RestClusterClient {
-            nodes: RwLock::new(Vec::new()),
+            client: Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            local_node: Node::from_config(&cloud_config, Uuid::new_v4()),
+            nodes: RwLock::new(HashMap::new()),
             cloud_config,
         }
     }
+
+    fn post<T: Serialize + ?Sized>(&self, host: &Address, path: &str, body: &T) -> RequestBuilder {
+        self.client
+            .post(format!("http://{}/cluster/{}", host, path))
+            .header(
+                CLUSTER_SECRET_HEADER,
+                self.cloud_config.get_cluster().get_secret(),
+            )
+            .json(body)
+    }
+
+    async fn send<R: DeserializeOwned>(request: RequestBuilder) -> CloudResult<R> {
+        let response = request.send().await.map_err(|e| error!(CantReachNode, e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(error!(
+                ClusterRequestFailed,
+                format!("{}: {}", status, body)
+            ));
+        }
+
+        response
+            .json::<R>()
+            .await
+            .map_err(|e| error!(DeserializationError, e))
+    }
+
+    async fn get_host(&self, node: &str) -> CloudResult<Address> {
+        match self.nodes.read().await.get(node) {
+            Some(node) => Ok(node.get_host().clone()),
+            None => Err(error!(NodeNotFound, node)),
+        }
+    }
+
+    async fn broadcast<T: Serialize + Sync + ?Sized>(&self, path: &str, body: &T) {
+        let hosts: Vec<(String, Address)> = self
+            .nodes
+            .read()
+            .await
+            .values()
+            .map(|n| (n.get_name().to_string(), n.get_host().clone()))
+            .collect();
+
+        let requests = hosts.iter().map(|(name, host)| async move {
+            if let Err(e) = Self::send::<serde_json::Value>(self.post(host, path, body)).await {
+                log_warning!(4, "[Cluster] Cant send '{}' to Node {}: {}", path, name, e);
+            }
+        });
+        join_all(requests).await;
+    }
 }
 
 #[async_trait]
 impl ClusterClient for RestClusterClient {
     async fn join_cluster(&self) -> CloudResult<()> {
-        todo!()
+        // the same config is often shared by all Nodes, so this Node can be one of the seeds
+        let local_host = self.local_node.get_host().to_string();
+        let seeds: Vec<&Address> = self
+            .cloud_config
+            .get_cluster()
+            .get_seeds()
+            .iter()
+            .filter(|seed| seed.to_string() != local_host)
+            .collect();
+        if seeds.is_empty() {
+            log_info!(
+                3,
+                "[Cluster] No other seeds configured, running as single Node"
+            );
+            return Ok(());
+        }
+
+        let mut joined = false;
+        for seed in seeds {
+            let request = self.post(seed, "join", &self.local_node);
+            match Self::send::<Vec<Node>>(request).await {
+                Ok(nodes) => {
+                    for node in nodes {
+                        self.on_node_seen(node).await;
+                    }
+                    joined = true;
+                }
+                Err(e) => log_warning!(3, "[Cluster] Cant join over seed {}: {}", seed, e),
+            }
+        }
+
+        if !joined {
+            return Err(error!(CantReachNode, "No seed is reachable"));
+        }
+
+        log_info!(
+            3,
+            "[Cluster] Joined Cluster with {} other Node(s)",
+            self.nodes.read().await.len()
+        );
+        Ok(())
+    }
+
+    async fn leave_cluster(&self) -> CloudResult<()> {
+        self.broadcast("leave", &self.local_node).await;
+        self.nodes.write().await.clear();
+        Ok(())
+    }
+
+    async fn heartbeat(&self, resources: Option<NodeResources>) {
+        let mut node = self.local_node.clone();
+        node.set_resources(resources);
+        self.broadcast("heartbeat", &node).await;
+    }
+
+    fn get_local_node(&self) -> &Node {
+        &self.local_node
+    }
+
+    async fn get_nodes(&self) -> Vec<Node> {
+        self.nodes.read().await.values().cloned().collect()
+    }
+
+    async fn get_node(&self, name: &str) -> Option<Node> {
+        self.nodes.read().await.get(name).cloned()
+    }
+
+    async fn on_node_seen(&self, mut node: Node) {
+        if node.get_name() == self.local_node.get_name() {
+            return;
+        }
+
+        node.touch();
+        let mut nodes = self.nodes.write().await;
+        match nodes.insert(node.get_name().to_string(), node.clone()) {
+            Some(old) if old.get_uuid() == node.get_uuid() => (),
+            Some(_) => log_info!(3, "[Cluster] Node {} restarted", node.get_name()),
+            None => log_info!(3, "[Cluster] Node {} joined", node.get_name()),
+        }
+    }
+
+    async fn on_node_left(&self, name: &str) -> Option<Node> {
+        let node = self.nodes.write().await.remove(name);
+        if node.is_some() {
+            log_info!(3, "[Cluster] Node {} left", name);
+        }
+        node
+    }
+
+    async fn remove_dead_nodes(&self) -> Vec<Node> {
+        let timeout = self.cloud_config.get_cluster().get_node_timeout();
+        let mut nodes = self.nodes.write().await;
+
+        let dead: Vec<String> = nodes
+            .values()
+            .filter(|n| !n.is_alive(timeout))
+            .map(|n| n.get_name().to_string())
+            .collect();
+
+        dead.iter()
+            .filter_map(|name| {
+                log_warning!(2, "[Cluster] Node {} timed out", name);
+                nodes.remove(name)
+            })
+            .collect()
+    }
+
+    async fn get_services(&self, node: &str) -> CloudResult<Vec<Service>> {
+        let host = self.get_host(node).await?;
+        Self::send(self.post(&host, "services", &())).await
+    }
+
+    async fn get_players(&self, node: &str) -> CloudResult<Vec<OnlinePlayer>> {
+        let host = self.get_host(node).await?;
+        Self::send(self.post(&host, "players", &())).await
+    }
+
+    async fn start_service(&self, node: &str, task_name: &str) -> CloudResult<Service> {
+        let host = self.get_host(node).await?;
+        let request = self
+            .post(&host, "service/start", &StartServiceRequest::new(task_name))
+            .timeout(START_SERVICE_TIMEOUT);
+        Self::send(request).await
+    }
+
+    async fn stop_service(&self, node: &str, id: &EntityId, msg: &str) -> CloudResult<()> {
+        let host = self.get_host(node).await?;
+        let request = self.post(&host, "service/stop", &StopServiceRequest::new(id, msg));
+        Self::send::<serde_json::Value>(request).await?;
+        Ok(())
+    }
+
+    async fn get_maintenance(&self, node: &str) -> CloudResult<bool> {
+        let host = self.get_host(node).await?;
+        let maintenance: NetworkMaintenance =
+            Self::send(self.post(&host, "maintenance", &())).await?;
+        Ok(maintenance.is_enabled())
+    }
+
+    async fn broadcast_maintenance(&self, enabled: bool) {
+        self.broadcast("maintenance/changed", &NetworkMaintenance::new(enabled))
+            .await;
+    }
+
+    async fn broadcast_service_registered(&self, service: &Service) {
+        self.broadcast("service/registered", service).await;
+    }
+
+    async fn broadcast_service_shutdown(&self, service: &Service) {
+        self.broadcast("service/shutdown", service).await;
     }
 }
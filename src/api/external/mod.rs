@@ -1,3 +1,19 @@
-pub mod restapi_main;
-pub mod restapi_service;
-pub mod restapi_task;
\ No newline at end of file
+pub use restapi_auth::ApiAuth;
+pub use restapi_group::ApiGroup;
+pub use restapi_main::ApiMain;
+pub use restapi_maintenance::ApiMaintenance;
+pub use restapi_moderation::ApiModeration;
+pub use restapi_node::ApiNode;
+pub use restapi_player::ApiPlayer;
+pub use restapi_service::ApiService;
+pub use restapi_task::ApiTask;
+
+mod restapi_auth;
+mod restapi_group;
+mod restapi_main;
+mod restapi_maintenance;
+mod restapi_moderation;
+mod restapi_node;
+mod restapi_player;
+mod restapi_service;
+mod restapi_task;
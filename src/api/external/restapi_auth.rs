@@ -0,0 +1,84 @@
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::Method;
+use actix_web::http::header::AUTHORIZATION;
+use actix_web::middleware::Next;
+use actix_web::{Error, web};
+use std::sync::Arc;
+
+use crate::error;
+use crate::manager::ApiTokenManager;
+use crate::types::ApiScope;
+use crate::utils::error::*;
+
+pub struct ApiAuth;
+
+impl ApiAuth {
+    /// Middleware: check the Bearer Token and its scope, changing requests are audited
+    pub async fn check(
+        req: ServiceRequest,
+        next: Next<impl MessageBody>,
+    ) -> Result<ServiceResponse<impl MessageBody>, Error> {
+        // CORS preflight has no Authorization header
+        if req.method() == Method::OPTIONS {
+            return next.call(req).await;
+        }
+
+        let token_manager = match req.app_data::<web::Data<Arc<ApiTokenManager>>>() {
+            Some(token_manager) => token_manager.get_ref().clone(),
+            None => return Err(error!(Internal, "ApiTokenManager is missing").into()),
+        };
+
+        let bearer = req
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.strip_prefix("Bearer "))
+            .map(|t| t.trim().to_string());
+
+        let token = match bearer {
+            Some(bearer) => token_manager.authenticate(&bearer).await?,
+            None => None,
+        };
+        let token = token.ok_or(error!(ApiUnauthorized, "Missing or invalid Bearer Token"))?;
+
+        let method = req.method().clone();
+        let path = req.path().to_string();
+        let scope = Self::required_scope(&method, &path);
+        if !token.has_scope(scope) {
+            return Err(error!(
+                ApiForbidden,
+                format!("Token '{}' needs the scope '{}'", token.get_name(), scope)
+            )
+            .into());
+        }
+
+        // handlers read the Token to know who issued a change
+        req.extensions_mut().insert(token.clone());
+        let res = next.call(req).await?;
+
+        if method != Method::GET {
+            token_manager
+                .audit(&token, method.as_str(), &path, res.status().as_u16())
+                .await;
+        }
+        Ok(res)
+    }
+
+    fn required_scope(method: &Method, path: &str) -> ApiScope {
+        if path.starts_with("/services/") && path.ends_with("/console") {
+            return ApiScope::ServiceControl;
+        }
+        if method == Method::GET {
+            return ApiScope::ReadOnly;
+        }
+
+        if path.starts_with("/tasks") {
+            ApiScope::TaskAdmin
+        } else if path.starts_with("/players") || path.starts_with("/moderation") {
+            ApiScope::PlayerAdmin
+        } else {
+            ApiScope::ServiceControl
+        }
+    }
+}
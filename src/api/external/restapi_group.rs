@@ -0,0 +1,34 @@
+use actix_web::{HttpResponse, web};
+
+use crate::error;
+use crate::manager::GroupManagerRef;
+use crate::utils::error::*;
+
+pub struct ApiGroup;
+
+impl ApiGroup {
+    pub async fn get_all(group_manager: web::Data<GroupManagerRef>) -> CloudResult<HttpResponse> {
+        let group_refs = group_manager.read().await.get_all();
+
+        let mut groups = Vec::new();
+        for group_ref in group_refs {
+            groups.push(group_ref.read().await.clone());
+        }
+        groups.sort_by(|a, b| a.get_name().cmp(b.get_name()));
+
+        Ok(HttpResponse::Ok().json(groups))
+    }
+
+    pub async fn get(
+        group_manager: web::Data<GroupManagerRef>,
+        name: web::Path<String>,
+    ) -> CloudResult<HttpResponse> {
+        let group_ref = group_manager
+            .read()
+            .await
+            .get_from_name(&name)
+            .map_err(|_| error!(CantFindGroupFromName, name.as_str()))?;
+        let group = group_ref.read().await.clone();
+        Ok(HttpResponse::Ok().json(group))
+    }
+}
@@ -1,85 +1,772 @@
 use actix_cors::Cors;
+use actix_web::http::{Method, header};
+use actix_web::middleware::from_fn;
 use actix_web::{App, HttpServer, web};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+use crate::api::external::{
+    ApiAuth, ApiGroup, ApiMaintenance, ApiModeration, ApiNode, ApiPlayer, ApiService, ApiTask,
+};
 use crate::cloud::Cloud;
-use crate::api::external::restapi_service::ApiService;
-use crate::api::external::restapi_task::ApiTask;
-use crate::config::cloud_config::CloudConfig;
-use crate::utils::log::logger::Logger;
-use crate::{log_error, log_info, log_warning};
+use crate::utils::error::{CantBindAddress, CloudResult, IntoCloudError};
+use crate::{error, log_error, log_info};
 
 pub struct ApiMain;
 
 impl ApiMain {
-    #[actix_web::main]
-    pub async fn start(cloud: Arc<RwLock<Cloud>>) {
-        log_info!("Start the REST AIP Server");
-        let app_factory = move || {
-            App::new()
-                .app_data(web::Data::new(cloud.clone()))
-                .wrap(
-                    Cors::permissive()
-                        .allow_any_method()
-                        .supports_credentials()
-                        .allow_any_header(),
-                )
-                // Task
-                .service(web::resource("cloud/task/get_all").route(web::get().to(ApiTask::get_all)))
-                .service(web::resource("cloud/task/get").route(web::get().to(ApiTask::get)))
-                .service(web::resource("cloud/task/create").route(web::post().to(ApiTask::create)))
-                .service(web::resource("cloud/task/update").route(web::put().to(ApiTask::update)))
-                .service(
-                    web::resource("cloud/task/delete").route(web::delete().to(ApiTask::delete)),
-                )
-                // Service
-                .service(
-                    web::resource("cloud/service/all").route(web::get().to(ApiService::get_all)),
-                )
-                .service(
-                    web::resource("cloud/service/online")
-                        .route(web::get().to(ApiService::get_online)),
-                )
-                .service(
-                    web::resource("cloud/service/prepared")
-                        .route(web::get().to(ApiService::get_prepare)),
-                )
-                .service(
-                    web::resource("cloud/service/offline")
-                        .route(web::get().to(ApiService::get_offline)),
-                )
-                .service(
-                    web::resource("cloud/service/get/")
-                        .route(web::get().to(ApiService::get_from_id)),
-                )
-                .service(
-                    web::resource("cloud/service/create").route(web::post().to(ApiService::create)),
-                )
+    pub async fn start(cloud: Arc<RwLock<Cloud>>) -> CloudResult<()> {
+        log_info!(3, "Start the REST API Server");
+
+        let (
+            config,
+            task_manager,
+            node_manager,
+            group_manager,
+            player_manager,
+            token_manager,
+            moderation_manager,
+            maintenance_manager,
+        ) = {
+            let c = cloud.read().await;
+            (
+                c.get_config().clone(),
+                c.get_task_manager(),
+                c.get_node_manager(),
+                c.get_group_manager(),
+                c.get_player_manager(),
+                c.get_api_token_manager(),
+                c.get_moderation_manager(),
+                c.get_maintenance_manager(),
+            )
         };
+        let cors_origins = config.get_rest_api_cors_origins().clone();
+        let service_manager = node_manager.get_service_manager();
+
+        let bind_addr = config.get_rest_api().to_string();
+
+        let (tx, rx) = std::sync::mpsc::channel::<CloudResult<()>>();
+
+        std::thread::spawn(move || {
+            let system = actix_web::rt::System::new();
+            system.block_on(async move {
+                let app = move || {
+                    App::new()
+                        .app_data(web::Data::new(task_manager.clone()))
+                        .app_data(web::Data::new(node_manager.clone()))
+                        .app_data(web::Data::new(service_manager.clone()))
+                        .app_data(web::Data::new(group_manager.clone()))
+                        .app_data(web::Data::new(player_manager.clone()))
+                        .app_data(web::Data::new(token_manager.clone()))
+                        .app_data(web::Data::new(moderation_manager.clone()))
+                        .app_data(web::Data::new(maintenance_manager.clone()))
+                        .wrap(from_fn(ApiAuth::check))
+                        .wrap(Self::cors(&cors_origins))
+                        .configure(Self::routes)
+                };
+
+                let server = match HttpServer::new(app).bind(&bind_addr) {
+                    Ok(s) => {
+                        let _ = tx.send(Ok(()));
+                        s
+                    }
+                    Err(e) => {
+                        let _ = tx.send(Err(e).into_cloud_error(CantBindAddress));
+                        return;
+                    }
+                };
+
+                if let Err(e) = server.run().await {
+                    log_error!("REST API Server Error: {}", e);
+                }
+            });
+        });
+
+        rx.recv().unwrap_or(Err(error!(CantBindAddress)))?;
+
+        log_info!(
+            3,
+            "[REST API] Endpoint started at {}",
+            config.get_rest_api()
+        );
+        Ok(())
+    }
+
+    /// All resources of the REST API, the managers are taken from the app data
+    pub fn routes(cfg: &mut web::ServiceConfig) {
+        cfg
+            // Task
+            .service(
+                web::resource("/tasks")
+                    .route(web::get().to(ApiTask::get_all))
+                    .route(web::post().to(ApiTask::create)),
+            )
+            .service(
+                web::resource("/tasks/{name}")
+                    .route(web::get().to(ApiTask::get))
+                    .route(web::put().to(ApiTask::update))
+                    .route(web::delete().to(ApiTask::delete)),
+            )
+            .service(
+                web::resource("/tasks/{name}/maintenance")
+                    .route(web::put().to(ApiMaintenance::set_task)),
+            )
+            // Service
+            .service(
+                web::resource("/services")
+                    .route(web::get().to(ApiService::get_all))
+                    .route(web::post().to(ApiService::create)),
+            )
+            .service(
+                web::resource("/services/{id}")
+                    .route(web::get().to(ApiService::get))
+                    .route(web::delete().to(ApiService::stop)),
+            )
+            .service(
+                web::resource("/services/{id}/console")
+                    .route(web::get().to(ApiService::console))
+                    .route(web::post().to(ApiService::command)),
+            )
+            .service(
+                web::resource("/services/{id}/metrics").route(web::get().to(ApiService::metrics)),
+            )
+            .service(
+                web::resource("/services/{id}/events").route(web::get().to(ApiService::events)),
+            )
+            .service(web::resource("/events").route(web::get().to(ApiService::all_events)))
+            // Node
+            .service(web::resource("/nodes").route(web::get().to(ApiNode::get_all)))
+            // Group
+            .service(web::resource("/groups").route(web::get().to(ApiGroup::get_all)))
+            .service(web::resource("/groups/{name}").route(web::get().to(ApiGroup::get)))
+            // Player
+            .service(web::resource("/players").route(web::get().to(ApiPlayer::get_online)))
+            .service(web::resource("/players/{uuid}").route(web::get().to(ApiPlayer::get)))
+            .service(
+                web::resource("/players/{uuid}/history").route(web::get().to(ApiPlayer::history)),
+            )
+            .service(
+                web::resource("/players/{uuid}/punishments")
+                    .route(web::get().to(ApiModeration::get_punishments)),
+            )
+            .service(
+                web::resource("/statistics/players").route(web::get().to(ApiPlayer::statistics)),
+            )
+            // Moderation
+            .service(
+                web::resource("/moderation/bans")
+                    .route(web::get().to(ApiModeration::get_bans))
+                    .route(web::post().to(ApiModeration::ban)),
+            )
+            .service(
+                web::resource("/moderation/bans/{uuid}")
+                    .route(web::delete().to(ApiModeration::unban)),
+            )
+            .service(
+                web::resource("/moderation/mutes")
+                    .route(web::get().to(ApiModeration::get_mutes))
+                    .route(web::post().to(ApiModeration::mute)),
+            )
+            .service(
+                web::resource("/moderation/mutes/{uuid}")
+                    .route(web::delete().to(ApiModeration::unmute)),
+            )
+            .service(
+                web::resource("/moderation/whitelist")
+                    .route(web::get().to(ApiModeration::get_whitelist))
+                    .route(web::post().to(ApiModeration::add_to_whitelist))
+                    .route(web::put().to(ApiModeration::set_whitelist_state)),
+            )
+            .service(
+                web::resource("/moderation/whitelist/{uuid}")
+                    .route(web::delete().to(ApiModeration::remove_from_whitelist)),
+            )
+            // Maintenance
+            .service(
+                web::resource("/maintenance")
+                    .route(web::get().to(ApiMaintenance::get))
+                    .route(web::put().to(ApiMaintenance::set_enabled)),
+            );
+    }
+
+    /// Only the configured origins, without origins no cross origin request is allowed
+    fn cors(origins: &[String]) -> Cors {
+        let mut cors = Cors::default()
+            .allowed_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+            .allowed_headers([header::AUTHORIZATION, header::CONTENT_TYPE])
+            .max_age(3600);
+
+        for origin in origins {
+            cors = cors.allowed_origin(origin);
+        }
+        cors
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::test::{self, TestRequest};
+    use serde_json::{Value, json};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::config::{CloudConfig, SoftwareConfig, SoftwareConfigRef};
+    use crate::database::table::{TableApiAuditLog, TablePlayers};
+    use crate::database::test_database;
+    use crate::manager::{
+        ApiTokenManager, GroupManagerRef, MaintenanceManager, Manager, ModerationManager,
+        NodeManager, PlayerManagerRef, TaskManagerRef,
+    };
+    use crate::types::ApiScope;
+    use crate::utils::error::CloudErrorKind;
+    use database_manager::DatabaseManager;
+    use std::path::Path;
+
+    /// Software of the test Tasks, the "server" echoes its console
+    const SOFTWARE: &str = r#"{
+        "name": "echo",
+        "typ": "backend",
+        "version": "1.0",
+        "software_file": { "url": "", "file_name": "server.sh", "auto_update": false },
+        "environment": { "command": "sh", "process_args": ["%server_file%"] },
+        "max_ram": 128,
+        "ip_path": "server.sh",
+        "port_path": "server.sh",
+        "system_plugin": { "local": true, "download": "", "file_name": "plugin.jar", "path": "plugins" },
+        "software_lib": {}
+    }"#;
 
-        // bind the address
-        let http_server = match HttpServer::new(app_factory)
-            .bind(CloudConfig::get().get_rest_api().to_string())
-        {
-            Ok(http_server) => http_server,
-            Err(e) => {
-                log_warning!(1,
-                    "Can not bind the REST API Server at {}",
-                    CloudConfig::get().get_rest_api().to_string()
-                );
-                log_error!("{}", e.to_string());
-                return;
+    struct TestCloud {
+        db: Arc<DatabaseManager>,
+        task_manager: TaskManagerRef,
+        node_manager: Arc<NodeManager>,
+        group_manager: GroupManagerRef,
+        player_manager: PlayerManagerRef,
+        token_manager: Arc<ApiTokenManager>,
+        moderation_manager: Arc<ModerationManager>,
+        maintenance_manager: Arc<MaintenanceManager>,
+    }
+
+    impl TestCloud {
+        /// A Node without Tasks, Groups and Services in its own folder,
+        /// only the Software `backend/echo/1.0` is installed
+        async fn new() -> TestCloud {
+            let db = test_database().await;
+            let dir = std::env::temp_dir().join(format!("minecloud-api-{}", Uuid::new_v4()));
+            let folder = |name: &str| {
+                let path = dir.join(name);
+                std::fs::create_dir_all(&path).unwrap();
+                path.to_string_lossy().to_string()
+            };
+            let config: CloudConfig = serde_json::from_value(json!({
+                "name": "Node-1",
+                "prefix": "[Test]",
+                "language": "de",
+                "log_level": 0,
+                "server_host": "127.0.0.1",
+                "max_ram": 1024,
+                "node_host": { "ip": "127.0.0.1", "port": 5050 },
+                "rest_api": { "ip": "127.0.0.1", "port": 5051 },
+                "database": {
+                    "typ": "SQLITE",
+                    "sqlite": { "file": dir.join("database.db").to_string_lossy() },
+                    "mysql": {
+                        "host": { "ip": "127.0.0.1", "port": 3306 },
+                        "username": "minecloud",
+                        "password": "minecloud",
+                        "database": "minecloud"
+                    }
+                },
+                "path": {
+                    "task_folder": folder("task"),
+                    "template_folder": folder("template"),
+                    "group_folder": folder("group"),
+                    "service_folder": {
+                        "temp_folder": folder("service/temp"),
+                        "static_folder": folder("service/static")
+                    },
+                    "system_folder": {
+                        "default_task": folder("config"),
+                        "software_config_folder": folder("config"),
+                        "system_plugins_folder": folder("config/system_plugins"),
+                        "software_files_folder": folder("config/software_files"),
+                        "software_lib_folder": folder("config/software_lib")
+                    }
+                }
+            }))
+            .unwrap();
+            let config = Arc::new(config);
+            install_software(&dir.join("config"));
+            let software_config = SoftwareConfigRef::new(SoftwareConfig::load(config.clone()));
+
+            let (
+                player_manager,
+                task_manager,
+                node_manager,
+                group_manager,
+                moderation_manager,
+                maintenance_manager,
+            ) = Manager::create_all(db.clone(), config, software_config)
+                .await
+                .unwrap();
+            TestCloud {
+                db: db.clone(),
+                task_manager,
+                node_manager,
+                group_manager,
+                player_manager,
+                token_manager: Arc::new(ApiTokenManager::new(db)),
+                moderation_manager,
+                maintenance_manager,
             }
-        };
+        }
+
+        async fn token(&self, scopes: &[ApiScope]) -> String {
+            self.token_manager
+                .create_token("test", scopes)
+                .await
+                .unwrap()
+        }
 
-        // start the server
-        match http_server.run().await {
-            Ok(_) => log_info!("Rest Api Server successfully start"),
-            Err(e) => {
-                log_error!("{}", e.to_string());
-                return;
+        /// Status and JSON body of the request, `Null` for an empty body
+        async fn call(&self, req: TestRequest) -> (StatusCode, Value) {
+            let app = test::init_service(
+                App::new()
+                    .app_data(web::Data::new(self.task_manager.clone()))
+                    .app_data(web::Data::new(self.node_manager.clone()))
+                    .app_data(web::Data::new(self.node_manager.get_service_manager()))
+                    .app_data(web::Data::new(self.group_manager.clone()))
+                    .app_data(web::Data::new(self.player_manager.clone()))
+                    .app_data(web::Data::new(self.token_manager.clone()))
+                    .app_data(web::Data::new(self.moderation_manager.clone()))
+                    .app_data(web::Data::new(self.maintenance_manager.clone()))
+                    .wrap(from_fn(ApiAuth::check))
+                    .configure(ApiMain::routes),
+            )
+            .await;
+
+            let (status, body) = match test::try_call_service(&app, req.to_request()).await {
+                Ok(res) => (res.status(), test::read_body(res).await),
+                // the server turns errors of the middleware into the response
+                Err(e) => {
+                    let res = e.error_response();
+                    let status = res.status();
+                    let body = actix_web::body::to_bytes(res.into_body()).await;
+                    (status, body.unwrap_or_default())
+                }
+            };
+            (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+        }
+
+        async fn get(&self, path: &str, token: &str) -> (StatusCode, Value) {
+            self.call(
+                TestRequest::get()
+                    .uri(path)
+                    .insert_header((header::AUTHORIZATION, format!("Bearer {}", token))),
+            )
+            .await
+        }
+
+        async fn send(
+            &self,
+            req: TestRequest,
+            path: &str,
+            token: &str,
+            body: Option<Value>,
+        ) -> (StatusCode, Value) {
+            let req = req
+                .uri(path)
+                .insert_header((header::AUTHORIZATION, format!("Bearer {}", token)));
+            match body {
+                Some(body) => self.call(req.set_json(body)).await,
+                None => self.call(req).await,
             }
         }
+
+        /// Create the Task `Lobby` of the test Software and start one Service from it
+        async fn start_service(&self, token: &str) -> Uuid {
+            let software = json!({ "typ": "backend", "name": "echo", "version": "1.0" });
+            let (status, mut task) = self
+                .send(
+                    TestRequest::post(),
+                    "/tasks",
+                    token,
+                    Some(json!({ "name": "Lobby", "software": software })),
+                )
+                .await;
+            assert_eq!(status, StatusCode::CREATED);
+
+            // the default template is not installed, stopped Services are killed right away
+            task["templates"] = json!([]);
+            task["time_shutdown_before_kill"] = json!(0);
+            let (status, _) = self
+                .send(TestRequest::put(), "/tasks/Lobby", token, Some(task))
+                .await;
+            assert_eq!(status, StatusCode::OK);
+
+            let (status, _) = self
+                .send(
+                    TestRequest::post(),
+                    "/services",
+                    token,
+                    Some(json!({ "task_name": "Lobby" })),
+                )
+                .await;
+            assert_eq!(status, StatusCode::ACCEPTED);
+
+            let (_, services) = self.get("/services?task=Lobby", token).await;
+            Uuid::parse_str(services[0]["id"].as_str().unwrap()).unwrap()
+        }
+
+        /// A Player which joined the Network before
+        async fn player(&self) -> Uuid {
+            let uuid = Uuid::new_v4();
+            TablePlayers::new(&uuid, "Steve")
+                .unwrap()
+                .create(self.db.as_ref())
+                .await
+                .unwrap();
+            uuid
+        }
+    }
+
+    fn install_software(config_folder: &Path) {
+        let folder = config_folder.join("backend").join("echo").join("1.0");
+        std::fs::create_dir_all(folder.join("plugin")).unwrap();
+        std::fs::create_dir_all(folder.join("lib")).unwrap();
+        std::fs::write(folder.join("software.json"), SOFTWARE).unwrap();
+        std::fs::write(folder.join("server.sh"), "# %ip%:%port%\nexec cat\n").unwrap();
+        std::fs::write(folder.join("plugin").join("plugin.jar"), "").unwrap();
+    }
+
+    fn assert_error(body: &Value, kind: CloudErrorKind) {
+        assert_eq!(body["code"], json!(kind.code()));
+        assert_eq!(body["error"], json!(format!("{:?}", kind)));
+        assert!(body["message"].is_string());
+    }
+
+    #[actix_web::test]
+    async fn rejects_requests_without_a_valid_token() {
+        let cloud = TestCloud::new().await;
+
+        let (status, body) = cloud.call(TestRequest::get().uri("/tasks")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_error(&body, CloudErrorKind::ApiUnauthorized);
+
+        let (status, body) = cloud.get("/tasks", "wrong").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_error(&body, CloudErrorKind::ApiUnauthorized);
+    }
+
+    #[actix_web::test]
+    async fn read_only_token_can_not_change_anything() {
+        let cloud = TestCloud::new().await;
+        let token = cloud.token(&[ApiScope::ReadOnly]).await;
+
+        let (status, body) = cloud
+            .call(
+                TestRequest::post()
+                    .uri("/services")
+                    .insert_header((header::AUTHORIZATION, format!("Bearer {}", token)))
+                    .set_json(json!({ "task_name": "Lobby" })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_error(&body, CloudErrorKind::ApiForbidden);
+    }
+
+    #[actix_web::test]
+    async fn tasks() {
+        let cloud = TestCloud::new().await;
+        let token = cloud.token(&ApiScope::all()).await;
+
+        let (status, body) = cloud.get("/tasks", &token).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, json!([]));
+
+        let (status, body) = cloud.get("/tasks/Lobby", &token).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_error(&body, CloudErrorKind::TaskNotFound);
+    }
+
+    #[actix_web::test]
+    async fn services() {
+        let cloud = TestCloud::new().await;
+        let token = cloud.token(&ApiScope::all()).await;
+
+        let (status, body) = cloud.get("/services", &token).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, json!([]));
+
+        let (status, body) = cloud
+            .get(&format!("/services/{}", Uuid::new_v4()), &token)
+            .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_error(&body, CloudErrorKind::ServiceNotFound);
+
+        let (status, body) = cloud
+            .call(
+                TestRequest::post()
+                    .uri("/services")
+                    .insert_header((header::AUTHORIZATION, format!("Bearer {}", token)))
+                    .set_json(json!({ "task_name": "Lobby" })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_error(&body, CloudErrorKind::TaskNotFound);
+    }
+
+    #[actix_web::test]
+    async fn groups() {
+        let cloud = TestCloud::new().await;
+        let token = cloud.token(&[ApiScope::ReadOnly]).await;
+
+        let (status, body) = cloud.get("/groups", &token).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, json!([]));
+
+        let (status, body) = cloud.get("/groups/Lobby", &token).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_error(&body, CloudErrorKind::CantFindGroupFromName);
+    }
+
+    #[actix_web::test]
+    async fn players() {
+        let cloud = TestCloud::new().await;
+        let token = cloud.token(&[ApiScope::ReadOnly]).await;
+
+        let (status, body) = cloud.get("/players", &token).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, json!([]));
+
+        let (status, body) = cloud
+            .get(&format!("/players/{}", Uuid::new_v4()), &token)
+            .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_error(&body, CloudErrorKind::PlayerNotFound);
+
+        let (status, body) = cloud.get("/players/not-a-uuid", &token).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_error(&body, CloudErrorKind::InvalidRequest);
+    }
+
+    #[actix_web::test]
+    async fn service_lifecycle() {
+        let cloud = TestCloud::new().await;
+        let token = cloud.token(&ApiScope::all()).await;
+        let id = cloud.start_service(&token).await;
+
+        let (status, body) = cloud.get(&format!("/services/{}", id), &token).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["task_name"], json!("Lobby"));
+
+        let (status, body) = cloud
+            .get(&format!("/services/{}/console", id), &token)
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["lines"].is_array());
+
+        let (status, _) = cloud
+            .send(
+                TestRequest::post(),
+                &format!("/services/{}/console", id),
+                &token,
+                Some(json!({ "command": "say hello" })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let service_ref = cloud
+            .node_manager
+            .get_service_manager()
+            .read()
+            .await
+            .find_from_id(&id)
+            .unwrap();
+        let stdin_file = service_ref.read().await.get_path_stdin_file();
+        assert!(
+            std::fs::read_to_string(stdin_file)
+                .unwrap()
+                .contains("say hello")
+        );
+
+        let (status, _) = cloud
+            .send(
+                TestRequest::delete(),
+                &format!("/services/{}", id),
+                &token,
+                None,
+            )
+            .await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+
+        let (status, body) = cloud.get(&format!("/services/{}", id), &token).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_error(&body, CloudErrorKind::ServiceNotFound);
+    }
+
+    #[actix_web::test]
+    async fn bans_and_mutes() {
+        let cloud = TestCloud::new().await;
+        let token = cloud.token(&ApiScope::all()).await;
+        let player = cloud.player().await;
+
+        let (status, body) = cloud
+            .send(
+                TestRequest::post(),
+                "/moderation/bans",
+                &token,
+                Some(json!({ "player": player, "reason": "spam", "duration": "12h" })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body["reason"], json!("spam"));
+        assert_eq!(body["issuer"], json!("api:test"));
+
+        let (status, body) = cloud.get("/moderation/bans", &token).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.as_array().unwrap().len(), 1);
+
+        let (status, _) = cloud
+            .send(
+                TestRequest::delete(),
+                &format!("/moderation/bans/{}", player),
+                &token,
+                None,
+            )
+            .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (_, body) = cloud.get("/moderation/bans", &token).await;
+        assert_eq!(body, json!([]));
+
+        let (status, _) = cloud
+            .send(
+                TestRequest::post(),
+                "/moderation/mutes",
+                &token,
+                Some(json!({ "player": player, "reason": "caps" })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (status, _) = cloud
+            .send(
+                TestRequest::delete(),
+                &format!("/moderation/mutes/{}", player),
+                &token,
+                None,
+            )
+            .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+
+    #[actix_web::test]
+    async fn whitelist() {
+        let cloud = TestCloud::new().await;
+        let token = cloud.token(&ApiScope::all()).await;
+        let player = cloud.player().await;
+
+        let (status, _) = cloud
+            .send(
+                TestRequest::post(),
+                "/moderation/whitelist",
+                &token,
+                Some(json!({ "player": player })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (status, _) = cloud
+            .send(
+                TestRequest::put(),
+                "/moderation/whitelist",
+                &token,
+                Some(json!({ "enabled": true })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        let (status, body) = cloud.get("/moderation/whitelist", &token).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["enabled"], json!(true));
+        assert_eq!(body["players"].as_array().unwrap().len(), 1);
+
+        let (status, _) = cloud
+            .send(
+                TestRequest::delete(),
+                &format!("/moderation/whitelist/{}", player),
+                &token,
+                None,
+            )
+            .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+
+    #[actix_web::test]
+    async fn maintenance() {
+        let cloud = TestCloud::new().await;
+        let token = cloud.token(&ApiScope::all()).await;
+
+        let (status, body) = cloud
+            .send(
+                TestRequest::put(),
+                "/maintenance",
+                &token,
+                Some(json!({ "enabled": true })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["enabled"], json!(true));
+
+        let software = json!({ "typ": "backend", "name": "echo", "version": "1.0" });
+        let (status, _) = cloud
+            .send(
+                TestRequest::post(),
+                "/tasks",
+                &token,
+                Some(json!({ "name": "Lobby", "software": software })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (status, body) = cloud
+            .send(
+                TestRequest::put(),
+                "/tasks/Lobby/maintenance",
+                &token,
+                Some(json!({ "enabled": true })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["tasks"], json!(["Lobby"]));
+
+        let (status, _) = cloud
+            .send(TestRequest::delete(), "/tasks/Lobby", &token, None)
+            .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+
+    #[actix_web::test]
+    async fn changing_requests_are_audited() {
+        let cloud = TestCloud::new().await;
+        let token = cloud.token(&ApiScope::all()).await;
+
+        let (status, _) = cloud
+            .send(
+                TestRequest::put(),
+                "/maintenance",
+                &token,
+                Some(json!({ "enabled": true })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        cloud.get("/maintenance", &token).await;
+
+        let entries = TableApiAuditLog::find_all(cloud.db.as_ref()).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].get_token_name(), "test");
+        assert_eq!(entries[0].get_method(), "PUT");
+        assert_eq!(entries[0].get_path(), "/maintenance");
+        assert_eq!(entries[0].get_status(), 200);
     }
 }
@@ -0,0 +1,42 @@
+use actix_web::{HttpResponse, web};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::manager::MaintenanceManager;
+use crate::utils::error::*;
+
+pub struct ApiMaintenance;
+
+#[derive(Deserialize)]
+pub struct MaintenanceStateRequest {
+    enabled: bool,
+}
+
+impl ApiMaintenance {
+    pub async fn get(
+        maintenance_manager: web::Data<Arc<MaintenanceManager>>,
+    ) -> CloudResult<HttpResponse> {
+        Ok(HttpResponse::Ok().json(maintenance_manager.get_state().await))
+    }
+
+    /// Switch the network maintenance on or off until the next restart
+    pub async fn set_enabled(
+        maintenance_manager: web::Data<Arc<MaintenanceManager>>,
+        body: web::Json<MaintenanceStateRequest>,
+    ) -> CloudResult<HttpResponse> {
+        maintenance_manager.set_enabled(body.enabled).await?;
+        Ok(HttpResponse::Ok().json(maintenance_manager.get_state().await))
+    }
+
+    /// Saved in the Task, Services of the Task get no new Players while it is enabled
+    pub async fn set_task(
+        maintenance_manager: web::Data<Arc<MaintenanceManager>>,
+        name: web::Path<String>,
+        body: web::Json<MaintenanceStateRequest>,
+    ) -> CloudResult<HttpResponse> {
+        maintenance_manager
+            .set_task_maintenance(&name, body.enabled)
+            .await?;
+        Ok(HttpResponse::Ok().json(maintenance_manager.get_state().await))
+    }
+}
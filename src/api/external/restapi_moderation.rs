@@ -0,0 +1,190 @@
+use actix_web::{HttpRequest, HttpResponse, web};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::api::external::ApiPlayer;
+use crate::error;
+use crate::manager::{ModerationManager, PlayerManagerRef};
+use crate::types::{ApiToken, PunishmentType, parse_punishment_duration};
+use crate::utils::error::*;
+
+pub struct ApiModeration;
+
+#[derive(Deserialize)]
+pub struct PunishmentRequest {
+    /// UUID of the Player
+    player: String,
+    reason: String,
+    /// `30m`, `12h`, `7d`, `2w`, without it the punishment is permanent
+    duration: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct WhitelistRequest {
+    /// UUID of the Player
+    player: String,
+}
+
+#[derive(Deserialize)]
+pub struct WhitelistStateRequest {
+    enabled: bool,
+}
+
+impl ApiModeration {
+    pub async fn get_bans(
+        moderation_manager: web::Data<Arc<ModerationManager>>,
+    ) -> CloudResult<HttpResponse> {
+        let bans = moderation_manager
+            .get_active_punishments(PunishmentType::Ban)
+            .await?;
+        Ok(HttpResponse::Ok().json(bans))
+    }
+
+    pub async fn get_mutes(
+        moderation_manager: web::Data<Arc<ModerationManager>>,
+    ) -> CloudResult<HttpResponse> {
+        let mutes = moderation_manager
+            .get_active_punishments(PunishmentType::Mute)
+            .await?;
+        Ok(HttpResponse::Ok().json(mutes))
+    }
+
+    pub async fn ban(
+        req: HttpRequest,
+        player_manager: web::Data<PlayerManagerRef>,
+        body: web::Json<PunishmentRequest>,
+    ) -> CloudResult<HttpResponse> {
+        Self::punish(req, &player_manager, &body, PunishmentType::Ban).await
+    }
+
+    pub async fn mute(
+        req: HttpRequest,
+        player_manager: web::Data<PlayerManagerRef>,
+        body: web::Json<PunishmentRequest>,
+    ) -> CloudResult<HttpResponse> {
+        Self::punish(req, &player_manager, &body, PunishmentType::Mute).await
+    }
+
+    pub async fn unban(
+        player_manager: web::Data<PlayerManagerRef>,
+        uuid: web::Path<String>,
+    ) -> CloudResult<HttpResponse> {
+        Self::pardon(&player_manager, &uuid, PunishmentType::Ban).await
+    }
+
+    pub async fn unmute(
+        player_manager: web::Data<PlayerManagerRef>,
+        uuid: web::Path<String>,
+    ) -> CloudResult<HttpResponse> {
+        Self::pardon(&player_manager, &uuid, PunishmentType::Mute).await
+    }
+
+    /// Every ban and mute the Player ever had
+    pub async fn get_punishments(
+        player_manager: web::Data<PlayerManagerRef>,
+        moderation_manager: web::Data<Arc<ModerationManager>>,
+        uuid: web::Path<String>,
+    ) -> CloudResult<HttpResponse> {
+        let player = ApiPlayer::find(&player_manager, &uuid).await?;
+        let punishments = moderation_manager
+            .get_punishments_of_player(&player.get_uuid())
+            .await?;
+        Ok(HttpResponse::Ok().json(punishments))
+    }
+
+    pub async fn get_whitelist(
+        moderation_manager: web::Data<Arc<ModerationManager>>,
+    ) -> CloudResult<HttpResponse> {
+        let entries = moderation_manager.get_whitelist().await?;
+        Ok(HttpResponse::Ok().json(json!({
+            "enabled": moderation_manager.is_whitelist_enabled(),
+            "players": entries,
+        })))
+    }
+
+    pub async fn add_to_whitelist(
+        req: HttpRequest,
+        player_manager: web::Data<PlayerManagerRef>,
+        moderation_manager: web::Data<Arc<ModerationManager>>,
+        body: web::Json<WhitelistRequest>,
+    ) -> CloudResult<HttpResponse> {
+        let player = ApiPlayer::find(&player_manager, &body.player).await?;
+        moderation_manager
+            .add_to_whitelist(&player, &Self::issuer(&req))
+            .await?;
+        Ok(HttpResponse::Created().finish())
+    }
+
+    pub async fn remove_from_whitelist(
+        player_manager: web::Data<PlayerManagerRef>,
+        moderation_manager: web::Data<Arc<ModerationManager>>,
+        uuid: web::Path<String>,
+    ) -> CloudResult<HttpResponse> {
+        let player = ApiPlayer::find(&player_manager, &uuid).await?;
+        if !moderation_manager.remove_from_whitelist(&player).await? {
+            return Err(error!(PunishmentNotFound, player.get_name()));
+        }
+        Ok(HttpResponse::NoContent().finish())
+    }
+
+    /// Switch the whitelist on or off until the next restart
+    pub async fn set_whitelist_state(
+        moderation_manager: web::Data<Arc<ModerationManager>>,
+        body: web::Json<WhitelistStateRequest>,
+    ) -> CloudResult<HttpResponse> {
+        moderation_manager.set_whitelist_enabled(body.enabled);
+        Ok(HttpResponse::NoContent().finish())
+    }
+
+    async fn punish(
+        req: HttpRequest,
+        player_manager: &PlayerManagerRef,
+        body: &PunishmentRequest,
+        kind: PunishmentType,
+    ) -> CloudResult<HttpResponse> {
+        let duration = match &body.duration {
+            Some(duration) => Some(parse_punishment_duration(duration).ok_or(error!(
+                InvalidRequest,
+                format!("Invalid duration '{}'", duration)
+            ))?),
+            None => None,
+        };
+        if body.reason.trim().is_empty() {
+            return Err(error!(InvalidRequest, "A reason is required"));
+        }
+
+        let player = ApiPlayer::find(player_manager, &body.player).await?;
+        let punishment = player_manager
+            .read()
+            .await
+            .punish_player(kind, &player, &body.reason, &Self::issuer(&req), duration)
+            .await?;
+        Ok(HttpResponse::Created().json(punishment))
+    }
+
+    async fn pardon(
+        player_manager: &PlayerManagerRef,
+        uuid: &str,
+        kind: PunishmentType,
+    ) -> CloudResult<HttpResponse> {
+        let player = ApiPlayer::find(player_manager, uuid).await?;
+        if !player_manager
+            .read()
+            .await
+            .pardon_player(kind, &player)
+            .await?
+        {
+            return Err(error!(PunishmentNotFound, player.get_name()));
+        }
+        Ok(HttpResponse::NoContent().finish())
+    }
+
+    /// The name of the Token which sent the request
+    fn issuer(req: &HttpRequest) -> String {
+        match req.extensions().get::<ApiToken>() {
+            Some(token) => format!("api:{}", token.get_name()),
+            None => String::from("api"),
+        }
+    }
+}
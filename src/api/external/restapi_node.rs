@@ -0,0 +1,28 @@
+use actix_web::{HttpResponse, web};
+use std::sync::Arc;
+
+use crate::manager::NodeManager;
+use crate::utils::error::*;
+
+pub struct ApiNode;
+
+impl ApiNode {
+    /// This Node with its last resource sample and all known Nodes of the Cluster
+    pub async fn get_all(node_manager: web::Data<Arc<NodeManager>>) -> CloudResult<HttpResponse> {
+        let cluster = node_manager.get_cluster();
+
+        let mut local = cluster.get_local_node().clone();
+        local.set_resources(
+            node_manager
+                .get_resource_monitor()
+                .get_node_resources()
+                .await,
+        );
+
+        let mut nodes = vec![local];
+        nodes.extend(cluster.get_nodes().await);
+        nodes.sort_by(|a, b| a.get_name().cmp(b.get_name()));
+
+        Ok(HttpResponse::Ok().json(nodes))
+    }
+}
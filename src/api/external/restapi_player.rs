@@ -0,0 +1,86 @@
+use actix_web::{HttpResponse, web};
+use serde::Deserialize;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error;
+use crate::manager::{NodeManager, PlayerManagerRef};
+use crate::types::{OnlinePlayer, Player};
+use crate::utils::error::*;
+
+const STATISTICS_DEFAULT_DAYS: u32 = 7;
+
+pub struct ApiPlayer;
+
+#[derive(Deserialize)]
+pub struct StatisticsQuery {
+    days: Option<u32>,
+}
+
+#[derive(Deserialize)]
+pub struct OnlineQuery {
+    task: Option<String>,
+}
+
+impl ApiPlayer {
+    /// Players online on the Proxies of all Nodes, optional filtered by `task`
+    pub async fn get_online(
+        node_manager: web::Data<Arc<NodeManager>>,
+        query: web::Query<OnlineQuery>,
+    ) -> CloudResult<HttpResponse> {
+        let players: Vec<OnlinePlayer> = node_manager
+            .get_network_players()
+            .await
+            .into_iter()
+            .filter(|p| query.task.as_ref().is_none_or(|t| p.is_on_task(t)))
+            .collect();
+        Ok(HttpResponse::Ok().json(players))
+    }
+
+    pub async fn get(
+        player_manager: web::Data<PlayerManagerRef>,
+        uuid: web::Path<String>,
+    ) -> CloudResult<HttpResponse> {
+        let player = Self::find(&player_manager, &uuid).await?;
+        Ok(HttpResponse::Ok().json(player))
+    }
+
+    /// Sessions and total playtime, newest session first
+    pub async fn history(
+        player_manager: web::Data<PlayerManagerRef>,
+        uuid: web::Path<String>,
+    ) -> CloudResult<HttpResponse> {
+        let player = Self::find(&player_manager, &uuid).await?;
+        let history = player_manager
+            .read()
+            .await
+            .get_player_history(&player)
+            .await?;
+        Ok(HttpResponse::Ok().json(history))
+    }
+
+    /// Peak and unique Players per day and the average session length of the last `days` days
+    pub async fn statistics(
+        player_manager: web::Data<PlayerManagerRef>,
+        query: web::Query<StatisticsQuery>,
+    ) -> CloudResult<HttpResponse> {
+        let days = query.days.unwrap_or(STATISTICS_DEFAULT_DAYS);
+        let statistics = player_manager
+            .read()
+            .await
+            .get_player_statistics(days)
+            .await?;
+        Ok(HttpResponse::Ok().json(statistics))
+    }
+
+    pub(crate) async fn find(player_manager: &PlayerManagerRef, uuid: &str) -> CloudResult<Player> {
+        let uuid = Uuid::parse_str(uuid).map_err(|e| error!(InvalidRequest, e))?;
+
+        player_manager
+            .read()
+            .await
+            .get_player_by_uuid(&uuid)
+            .await?
+            .ok_or(error!(PlayerNotFound, uuid))
+    }
+}
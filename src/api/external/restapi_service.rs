@@ -1,17 +1,23 @@
 use actix_web::{HttpResponse, web};
 use serde::Deserialize;
+use serde_json::json;
 use std::sync::Arc;
-use tokio::sync::RwLock;
 use uuid::Uuid;
 
-use crate::cloud::Cloud;
-use crate::types::task::Task;
+use crate::manager::{NodeManager, ServiceManagerRef, TaskManagerRef};
+use crate::types::{Service, ServiceEventFilter, ServiceProcessRef, ServiceStatus};
+use crate::utils::error::*;
+use crate::{error, log_info};
+
+/// Bytes of the console which are returned without an offset
+const CONSOLE_TAIL_BYTES: u64 = 16 * 1024;
 
 pub struct ApiService;
 
 #[derive(Deserialize)]
-pub struct ServiceGetRequest {
-    id: Uuid,
+pub struct ServiceFilterQuery {
+    status: Option<ServiceStatus>,
+    task: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -19,74 +25,187 @@ pub struct ServiceCreateRequest {
     task_name: String,
 }
 
+#[derive(Deserialize)]
+pub struct ServiceStopQuery {
+    msg: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ConsoleQuery {
+    offset: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct CommandRequest {
+    command: String,
+}
+
 impl ApiService {
-    pub async fn get_all(cloud: web::Data<Arc<RwLock<Cloud>>>) -> HttpResponse {
-        let all_services = { cloud.read().await.get_all().clone() };
-        let services = all_services.get_all().await;
-        HttpResponse::Ok().json(services)
+    /// All Services of the Cluster, optional filtered by `status` and `task`
+    pub async fn get_all(
+        node_manager: web::Data<Arc<NodeManager>>,
+        service_manager: web::Data<ServiceManagerRef>,
+        query: web::Query<ServiceFilterQuery>,
+    ) -> CloudResult<HttpResponse> {
+        let service_refs = service_manager
+            .read()
+            .await
+            .filter_services(|s| {
+                query.status.is_none_or(|status| s.get_status() == status)
+                    && query.task.as_ref().is_none_or(|t| s.get_task_name() == t)
+            })
+            .await;
+
+        let mut services: Vec<Service> = Vec::new();
+        for service_ref in service_refs {
+            services.push(service_ref.read().await.get_service().clone());
+        }
+        services.extend(
+            node_manager
+                .get_remote_services()
+                .await
+                .into_iter()
+                .filter(|s| {
+                    query.status.is_none_or(|status| s.get_status() == status)
+                        && query.task.as_ref().is_none_or(|t| s.get_task_name() == t)
+                }),
+        );
+        services.sort_by(|a, b| a.get_name().cmp(b.get_name()));
+
+        Ok(HttpResponse::Ok().json(services))
     }
 
-    pub async fn get_online(cloud: web::Data<Arc<RwLock<Cloud>>>) -> HttpResponse {
-        let all_services = { cloud.read().await.get_all().clone() };
-        let services = all_services.get_start_services().await;
-        HttpResponse::Ok().json(services)
+    pub async fn get(
+        node_manager: web::Data<Arc<NodeManager>>,
+        service_manager: web::Data<ServiceManagerRef>,
+        id: web::Path<Uuid>,
+    ) -> CloudResult<HttpResponse> {
+        let local = service_manager.read().await.find_from_id(&id);
+        if let Some(service_ref) = local {
+            let service = service_ref.read().await.get_service().clone();
+            return Ok(HttpResponse::Ok().json(service));
+        }
+
+        match node_manager
+            .get_remote_services()
+            .await
+            .into_iter()
+            .find(|s| s.get_id() == &*id)
+        {
+            Some(service) => Ok(HttpResponse::Ok().json(service)),
+            None => Err(error!(ServiceNotFound, id.to_string())),
+        }
     }
 
-    pub async fn get_prepare(cloud: web::Data<Arc<RwLock<Cloud>>>) -> HttpResponse {
-        let all_services = { cloud.read().await.get_all().clone() };
-        let services = all_services.get_prepare_services().await;
-        HttpResponse::Ok().json(services)
+    /// Start a new Service from the Task on the best Node
+    pub async fn create(
+        node_manager: web::Data<Arc<NodeManager>>,
+        task_manager: web::Data<TaskManagerRef>,
+        req: web::Json<ServiceCreateRequest>,
+    ) -> CloudResult<HttpResponse> {
+        let task = task_manager
+            .get_task_ref_from_name(&req.task_name)
+            .await
+            .map_err(|_| error!(TaskNotFound, req.task_name.as_str()))?
+            .read()
+            .await
+            .clone();
+
+        node_manager.start_service_from_task(&task).await?;
+
+        log_info!(
+            "[REST API] Service for Task | {} | gestartet",
+            task.get_name()
+        );
+        Ok(HttpResponse::Accepted().finish())
     }
 
-    pub async fn get_offline(cloud: web::Data<Arc<RwLock<Cloud>>>) -> HttpResponse {
-        let all_services = { cloud.read().await.get_all().clone() };
-        let services = all_services.get_stopped_services().await;
-        HttpResponse::Ok().json(services)
+    pub async fn stop(
+        node_manager: web::Data<Arc<NodeManager>>,
+        id: web::Path<Uuid>,
+        query: web::Query<ServiceStopQuery>,
+    ) -> CloudResult<HttpResponse> {
+        let msg = query.msg.as_deref().unwrap_or("Stopped by REST API");
+        node_manager.stop_service(id.into_inner(), msg).await;
+        Ok(HttpResponse::Accepted().finish())
     }
 
-    pub async fn get_from_id(
-        cloud: web::Data<Arc<RwLock<Cloud>>>,
-        req: web::Query<ServiceGetRequest>,
-    ) -> HttpResponse {
-        if req.id.is_nil() {
-            return HttpResponse::NoContent().json("Bitte gebe ein Service ID an");
-        }
+    /// New console lines since `offset`, without offset the tail of the console
+    pub async fn console(
+        service_manager: web::Data<ServiceManagerRef>,
+        id: web::Path<Uuid>,
+        query: web::Query<ConsoleQuery>,
+    ) -> CloudResult<HttpResponse> {
+        let service_ref = Self::find_local(&service_manager, &id).await?;
+        let sp = service_ref.read().await;
+
+        let offset = query
+            .offset
+            .unwrap_or_else(|| sp.get_console_tail_offset(CONSOLE_TAIL_BYTES));
+        Ok(HttpResponse::Ok().json(sp.read_console(offset)?))
+    }
 
-        let all_service = { cloud.read().await.get_all().clone() };
+    /// Performance of the last minute, sent by the plugin of the Service.
+    /// The samples are only available on the Node running the Service.
+    pub async fn metrics(
+        service_manager: web::Data<ServiceManagerRef>,
+        id: web::Path<Uuid>,
+    ) -> CloudResult<HttpResponse> {
+        let service_ref = Self::find_local(&service_manager, &id).await?;
+        let sp = service_ref.read().await;
+
+        let metrics = sp.get_metrics();
+        Ok(HttpResponse::Ok().json(json!({
+            "latest": metrics.get_latest(),
+            "average_tps": metrics.get_average_tps(),
+            "average_mspt": metrics.get_average_mspt(),
+            "samples": metrics.get_samples(),
+        })))
+    }
 
-        let service = match all_service.get_from_id(&req.id).await {
-            Some(service) => service,
-            None => {
-                return HttpResponse::NoContent().json("Bitte gebe ein Gültige ID an");
-            }
-        };
+    /// Lifecycle events of the Service, newest first, optional limited by `from` / `to` (unix seconds)
+    pub async fn events(
+        service_manager: web::Data<ServiceManagerRef>,
+        id: web::Path<Uuid>,
+        query: web::Query<ServiceEventFilter>,
+    ) -> CloudResult<HttpResponse> {
+        let filter = query.into_inner().service(id.into_inner());
+        let events = service_manager.read().await.find_events(&filter).await?;
+        Ok(HttpResponse::Ok().json(events))
+    }
 
-        HttpResponse::Ok().json(service)
+    /// Lifecycle events of all Services, filtered by `service`, `task`, `from`, `to` and `limit`
+    pub async fn all_events(
+        service_manager: web::Data<ServiceManagerRef>,
+        query: web::Query<ServiceEventFilter>,
+    ) -> CloudResult<HttpResponse> {
+        let events = service_manager.read().await.find_events(&query).await?;
+        Ok(HttpResponse::Ok().json(events))
     }
 
-    pub async fn create(
-        cloud: web::Data<Arc<RwLock<Cloud>>>,
-        req: web::Json<ServiceCreateRequest>,
-    ) -> HttpResponse {
-        if req.task_name.is_empty() {
-            return HttpResponse::NoContent().json("Bitte gebe ein task_name an");
+    pub async fn command(
+        service_manager: web::Data<ServiceManagerRef>,
+        id: web::Path<Uuid>,
+        req: web::Json<CommandRequest>,
+    ) -> CloudResult<HttpResponse> {
+        if req.command.trim().is_empty() {
+            return Err(error!(InvalidRequest, "Empty command"));
         }
 
-        let task = match Task::get_task(&req.task_name) {
-            Some(task) => task,
-            None => return HttpResponse::NotFound().json("Task nicht gefunden!!!"),
-        };
-
-        let result = {
-            let mut cloud = cloud.write().await;
-            cloud.get_all_mut().start_service(&task).await
-        };
-
-        match result {
-            Ok(_) => HttpResponse::Ok().json("Service gestartet"),
-            Err(e) => {
-                HttpResponse::InternalServerError().json(format!("Fehler beim Starten: {}", e))
-            }
-        }
+        let service_ref = Self::find_local(&service_manager, &id).await?;
+        service_ref.send_command(&req.command).await?;
+        Ok(HttpResponse::NoContent().finish())
+    }
+
+    /// The console and the metrics are only available on the Node running the Service
+    async fn find_local(
+        service_manager: &ServiceManagerRef,
+        id: &Uuid,
+    ) -> CloudResult<ServiceProcessRef> {
+        service_manager
+            .read()
+            .await
+            .find_from_id(id)
+            .ok_or(error!(ServiceNotFound, id.to_string()))
     }
 }
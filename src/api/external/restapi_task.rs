@@ -1,115 +1,101 @@
 use actix_web::{HttpResponse, web};
 use serde::Deserialize;
 
-use crate::types::task::Task;
-use crate::utils::utils::Utils;
-use crate::utils::log::logger::Logger;
-use crate::{log_info, log_warning};
+use crate::manager::TaskManagerRef;
+use crate::types::{SoftwareLink, Task, TaskRef};
+use crate::utils::error::*;
+use crate::{error, log_info};
 
 pub struct ApiTask;
 
-#[derive(Deserialize)]
-pub struct TaskNameRequest {
-    task_name: String,
-}
-
 #[derive(Deserialize)]
 pub struct TaskCreateRequest {
-    task_name: String,
-    software_type: String,
-    software_name: String,
+    name: String,
+    software: SoftwareLink,
 }
 
 impl ApiTask {
-    pub async fn get_all() -> HttpResponse {
-        HttpResponse::Ok().json(Task::get_task_all())
-    }
+    pub async fn get_all(task_manager: web::Data<TaskManagerRef>) -> CloudResult<HttpResponse> {
+        let task_refs = task_manager.read().await.get_all_tasks();
 
-    pub async fn get(req: web::Query<TaskNameRequest>) -> HttpResponse {
-        if req.task_name.is_empty() {
-            return HttpResponse::NoContent().json("Bitte gebe ein task_name an");
+        let mut tasks = Vec::new();
+        for task_ref in task_refs {
+            tasks.push(task_ref.read().await.clone());
         }
+        tasks.sort_by_key(|t| t.get_name());
 
-        let task = match Task::get_task(&req.task_name) {
-            Some(task) => task,
-            None => {
-                return HttpResponse::NoContent().json("Bitte gebe ein Gültigen task_name an");
-            }
-        };
-
-        match Utils::convert_to_json(&task) {
-            Some(data) => HttpResponse::Ok().json(data),
-            None => HttpResponse::InternalServerError()
-                .json("Task konnte nicht in Json umgewandelt werden"),
-        }
+        Ok(HttpResponse::Ok().json(tasks))
     }
 
-    pub async fn create(req: web::Json<TaskCreateRequest>) -> HttpResponse {
-        if req.task_name.is_empty() {
-            return HttpResponse::BadRequest().json("Empty Task Name");
-        }
-
-        if req.software_type.is_empty() {
-            return HttpResponse::BadRequest().json("Empty Software Type");
-        }
+    pub async fn get(
+        task_manager: web::Data<TaskManagerRef>,
+        name: web::Path<String>,
+    ) -> CloudResult<HttpResponse> {
+        let task_ref = Self::find(&task_manager, &name).await?;
+        let task = task_ref.read().await.clone();
+        Ok(HttpResponse::Ok().json(task))
+    }
 
-        if req.software_name.is_empty() {
-            return HttpResponse::BadRequest().json("Empty Software Name");
+    pub async fn create(
+        task_manager: web::Data<TaskManagerRef>,
+        req: web::Json<TaskCreateRequest>,
+    ) -> CloudResult<HttpResponse> {
+        let req = req.into_inner();
+        if req.name.is_empty() {
+            return Err(error!(InvalidRequest, "Empty Task Name"));
         }
 
-        match Task::create(&req.task_name, &req.software_type, &req.software_name) {
-            Ok(task) => {
-                log_info!(
-                    "[RestAPI] Task | {} | Erfolgreich erstellt",
-                    task.get_name()
-                );
-                HttpResponse::Ok()
-                    .json(format!("Task | {} | erfolgreich erstellt", task.get_name()))
-            }
-            Err(e) => {
-                log_warning!(
-                    "[RestAPI] Fehler beim ersttellen der Task {}",
-                    req.task_name
-                );
-                HttpResponse::NotFound().json(e)
-            }
-        }
+        let task_ref = task_manager
+            .write()
+            .await
+            .create_task(req.name, req.software)
+            .await?;
+        let task = task_ref.read().await.clone();
+
+        log_info!(
+            "[REST API] Task | {} | erfolgreich erstellt",
+            task.get_name()
+        );
+        Ok(HttpResponse::Created().json(task))
     }
 
-    pub async fn update(param: web::Query<TaskNameRequest>, req: web::Json<Task>) -> HttpResponse {
-        if param.task_name.is_empty() || req.get_name().is_empty() {
-            return HttpResponse::BadRequest().json("Empty Task Name");
+    pub async fn update(
+        task_manager: web::Data<TaskManagerRef>,
+        name: web::Path<String>,
+        req: web::Json<Task>,
+    ) -> CloudResult<HttpResponse> {
+        let task = req.into_inner();
+        if task.get_name().is_empty() {
+            return Err(error!(InvalidRequest, "Empty Task Name"));
         }
 
-        let mut tasks = Task::get_task_all();
-        match tasks.iter_mut().find(|t| t.get_name() == param.task_name) {
-            Some(task) => {
-                task.update(req.into_inner());
-                log_info!("[RestAPI] Task | {} | wurde bearbeitet", task.get_name());
-                HttpResponse::Ok().json(task)
-            }
-            None => HttpResponse::BadRequest().json("Task existiert nicht"),
-        }
+        Self::find(&task_manager, &name).await?;
+        task_manager
+            .write()
+            .await
+            .update_task(&name, task.clone())
+            .await?;
+
+        log_info!("[REST API] Task | {} | wurde bearbeitet", task.get_name());
+        Ok(HttpResponse::Ok().json(task))
     }
 
-    pub async fn delete(req: web::Query<TaskNameRequest>) -> HttpResponse {
-        if req.task_name.is_empty() {
-            return HttpResponse::BadRequest().json("Empty Task Name");
-        }
+    pub async fn delete(
+        task_manager: web::Data<TaskManagerRef>,
+        name: web::Path<String>,
+    ) -> CloudResult<HttpResponse> {
+        Self::find(&task_manager, &name).await?;
+        task_manager.write().await.delete(&name);
 
-        match Task::get_task(&req.task_name) {
-            Some(task) => {
-                task.delete_as_file();
-                log_info!(
-                    "[RestAPI] Task | {} | wurde erfolgreich gelöscht",
-                    task.get_name()
-                );
-                HttpResponse::Ok().json("Task wurde erfolgreich gelöscht")
-            }
-            None => {
-                log_warning!("[RestAPI] Kein Task gefunden | {} |", req.task_name);
-                HttpResponse::BadRequest().json("Kein Task zum löschen gefunden")
-            }
-        }
+        log_info!("[REST API] Task | {} | wurde gelöscht", name);
+        Ok(HttpResponse::NoContent().finish())
+    }
+
+    async fn find(task_manager: &TaskManagerRef, name: &str) -> CloudResult<TaskRef> {
+        task_manager
+            .read()
+            .await
+            .get_from_name(name)
+            .map_err(|_| error!(TaskNotFound, name))
     }
 }
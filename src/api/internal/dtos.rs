@@ -1,12 +1,22 @@
 use bx::network::address::Address;
+use chrono::NaiveDateTime;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use uuid::Uuid;
 
+use strum_macros::EnumIter;
+
 use crate::log_error;
-use crate::types::{EntityId, PlayerAction, Service};
+use crate::types::{EntityId, OnlinePlayer, PlayerAction, Punishment, Service};
+
+/// Version of the internal WebSocket protocol this Node speaks
+pub const PROTOCOL_VERSION: u32 = 2;
 
-#[derive(Debug, Deserialize)]
+/// Oldest protocol version a plugin may connect with
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct IncomingMessage {
     #[serde(rename = "request_id")]
     request_id: Option<Uuid>,
@@ -40,7 +50,7 @@ impl IncomingMessage {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct OutgoingMessage {
     #[serde(rename = "request_id")]
     request_id: Option<Uuid>,
@@ -48,8 +58,9 @@ pub struct OutgoingMessage {
     #[serde(rename = "type")]
     msg_type: OutgoingMessageType,
 
-    // old
-    success: bool,
+    /// Deprecated since protocol 2, the `type` tells if the message is an error
+    #[serde(skip_serializing_if = "Option::is_none")]
+    success: Option<bool>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "data")]
@@ -69,7 +80,7 @@ impl OutgoingMessage {
         OutgoingMessage {
             msg_type: msg_type.into(),
             request_id,
-            success: true,
+            success: Some(true),
             data: Some(data),
             error: None,
         }
@@ -79,7 +90,7 @@ impl OutgoingMessage {
         OutgoingMessage {
             msg_type: OutgoingMessageType::Error,
             request_id,
-            success: false,
+            success: Some(false),
             data: None,
             error: Some(error),
         }
@@ -89,7 +100,7 @@ impl OutgoingMessage {
         OutgoingMessage {
             msg_type: OutgoingMessageType::ResponseNull,
             request_id,
-            success: true,
+            success: Some(true),
             data: None,
             error: None,
         }
@@ -99,6 +110,18 @@ impl OutgoingMessage {
         self.request_id = request_id;
     }
 
+    pub fn get_msg_type(&self) -> &OutgoingMessageType {
+        &self.msg_type
+    }
+
+    /// Remove the fields the protocol version of the plugin does not know anymore
+    pub fn for_protocol(mut self, protocol_version: u32) -> OutgoingMessage {
+        if protocol_version >= 2 {
+            self.success = None;
+        }
+        self
+    }
+
     pub fn to_string(&self) -> String {
         serde_json::to_string(&self).unwrap_or_else(|e| {
             log_error!("CantSerializeOutgoingMsg: {}", e);
@@ -107,7 +130,7 @@ impl OutgoingMessage {
     }
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema, EnumIter)]
 pub enum IncomingMessageType {
     #[serde(rename = "auth")]
     Auth,
@@ -123,6 +146,43 @@ pub enum IncomingMessageType {
 
     #[serde(rename = "player_action")]
     PlayerAction,
+
+    /// periodic performance sample, `data` is a `ServiceMetrics`
+    #[serde(rename = "metrics")]
+    Metrics,
+
+    /// `data` is a `FindPlayerRequest`, answered with the `OnlinePlayer`
+    #[serde(rename = "find_player")]
+    FindPlayer,
+
+    /// `data` is a `ListPlayersRequest`, answered with a `PlayerListResponse`
+    #[serde(rename = "list_players")]
+    ListPlayers,
+
+    /// `data` is a `PlayerMuteRequest`, answered with a `PlayerMuteMessage`
+    #[serde(rename = "get_mute")]
+    GetMute,
+
+    /// no `data`, answered with a `MaintenanceMessage`
+    #[serde(rename = "get_maintenance")]
+    GetMaintenance,
+
+    /// periodic list of every Player on the Service, `data` is a `PlayerSyncMessage`
+    #[serde(rename = "player_sync")]
+    PlayerSync,
+
+    /// answer to a request of the Cloud, matched by `request_id`
+    #[serde(rename = "response")]
+    Response,
+
+    /// failed answer to a request of the Cloud, `data.error` holds the reason
+    #[serde(rename = "error")]
+    Error,
+
+    /// any type this Node does not support
+    #[serde(other)]
+    #[serde(rename = "unknown")]
+    Unknown,
 }
 
 impl PartialEq<IncomingMessageType> for &IncomingMessageType {
@@ -131,7 +191,7 @@ impl PartialEq<IncomingMessageType> for &IncomingMessageType {
     }
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, EnumIter)]
 pub enum OutgoingMessageType {
     #[serde(rename = "error")]
     Error,
@@ -153,6 +213,34 @@ pub enum OutgoingMessageType {
 
     #[serde(rename = "connect_player_to_server")]
     ConnectPlayerToServer,
+
+    /// heartbeat, answered with a `response` carrying a `PluginHeartbeat`
+    #[serde(rename = "ping")]
+    Ping,
+
+    /// sent to the Proxy of the Player, `data` is a `PlayerKickMessage`
+    #[serde(rename = "kick_player")]
+    KickPlayer,
+
+    /// sent to the Proxy of the Player, `data` is a `PlayerChatMessage`
+    #[serde(rename = "message_player")]
+    MessagePlayer,
+
+    /// sent to all Proxies, `data` is a `BroadcastMessage`
+    #[serde(rename = "broadcast")]
+    Broadcast,
+
+    /// answer to a `join` on a Proxy which must not be accepted, `data` is a `JoinDeniedResponse`
+    #[serde(rename = "join_denied")]
+    JoinDenied,
+
+    /// sent to the Proxy of the Player when a mute starts or ends, `data` is a `PlayerMuteMessage`
+    #[serde(rename = "mute_player")]
+    MutePlayer,
+
+    /// sent to all Proxies when the maintenance changes, `data` is a `MaintenanceMessage`
+    #[serde(rename = "maintenance")]
+    Maintenance,
 }
 
 impl PartialEq<OutgoingMessageType> for &OutgoingMessageType {
@@ -178,7 +266,7 @@ impl From<&Uuid> for ServiceIdRequest {
     }
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, JsonSchema)]
 pub struct ServiceInfoResponse {
     #[serde(rename = "id")]
     id: Uuid,
@@ -186,7 +274,9 @@ pub struct ServiceInfoResponse {
     #[serde(rename = "name")]
     name: String,
 
+    /// `ip:port`
     #[serde(rename = "address")]
+    #[schemars(with = "String")]
     address: Address,
 
     #[serde(rename = "join_permission")]
@@ -228,7 +318,7 @@ impl From<Service> for ServiceInfoResponse {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
 pub struct PlayerActionMessage {
     #[serde(rename = "action")]
     action: PlayerAction,
@@ -244,16 +334,28 @@ pub struct PlayerActionMessage {
 
     #[serde(rename = "player_name")]
     player_name: String,
+
+    /// Permissions of the Player, used to check fallback groups and join permissions
+    #[serde(default)]
+    #[serde(rename = "permissions")]
+    permissions: Vec<String>,
 }
 
 impl PlayerActionMessage {
-    pub fn new(action: PlayerAction, service_uuid: Uuid, service_name: String, player_uuid: Uuid, player_name: String) -> PlayerActionMessage {
+    pub fn new(
+        action: PlayerAction,
+        service_uuid: Uuid,
+        service_name: String,
+        player_uuid: Uuid,
+        player_name: String,
+    ) -> PlayerActionMessage {
         PlayerActionMessage {
             action,
             service_uuid,
             service_name,
             player_uuid,
             player_name,
+            permissions: Vec::new(),
         }
     }
     pub fn get_action(&self) -> &PlayerAction {
@@ -275,4 +377,424 @@ impl PlayerActionMessage {
     pub fn get_player_uuid(&self) -> Uuid {
         self.player_uuid
     }
+
+    pub fn get_permissions(&self) -> &Vec<String> {
+        &self.permissions
+    }
+}
+
+/// `data` of `kick_player`
+#[derive(Serialize, Debug, JsonSchema)]
+pub struct PlayerKickMessage {
+    #[serde(rename = "player_uuid")]
+    player_uuid: Uuid,
+
+    #[serde(rename = "player_name")]
+    player_name: String,
+
+    #[serde(rename = "reason")]
+    reason: String,
+}
+
+impl PlayerKickMessage {
+    pub fn new(player_uuid: Uuid, player_name: &str, reason: &str) -> PlayerKickMessage {
+        PlayerKickMessage {
+            player_uuid,
+            player_name: player_name.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// `data` of `message_player`
+#[derive(Serialize, Debug, JsonSchema)]
+pub struct PlayerChatMessage {
+    #[serde(rename = "player_uuid")]
+    player_uuid: Uuid,
+
+    #[serde(rename = "player_name")]
+    player_name: String,
+
+    /// may contain `&` color codes
+    #[serde(rename = "message")]
+    message: String,
+}
+
+impl PlayerChatMessage {
+    pub fn new(player_uuid: Uuid, player_name: &str, message: &str) -> PlayerChatMessage {
+        PlayerChatMessage {
+            player_uuid,
+            player_name: player_name.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+/// `data` of `broadcast`
+#[derive(Serialize, Debug, JsonSchema)]
+pub struct BroadcastMessage {
+    /// may contain `&` color codes
+    #[serde(rename = "message")]
+    message: String,
+
+    /// only Players with this permission see the message, `None` for everyone
+    #[serde(rename = "permission")]
+    permission: Option<String>,
+}
+
+impl BroadcastMessage {
+    pub fn new(message: &str, permission: Option<&str>) -> BroadcastMessage {
+        BroadcastMessage {
+            message: message.to_string(),
+            permission: permission.map(String::from),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, JsonSchema)]
+pub enum JoinDenyReason {
+    #[serde(rename = "banned")]
+    Banned,
+
+    #[serde(rename = "whitelist")]
+    Whitelist,
+
+    #[serde(rename = "maintenance")]
+    Maintenance,
+}
+
+/// `data` of `join_denied`, the Proxy disconnects the Player with the `kick_message`
+#[derive(Serialize, Debug, JsonSchema)]
+pub struct JoinDeniedResponse {
+    #[serde(rename = "player_uuid")]
+    player_uuid: Uuid,
+
+    #[serde(rename = "player_name")]
+    player_name: String,
+
+    #[serde(rename = "reason")]
+    reason: JoinDenyReason,
+
+    #[serde(rename = "kick_message")]
+    kick_message: String,
+}
+
+impl JoinDeniedResponse {
+    pub fn new(
+        player_uuid: Uuid,
+        player_name: &str,
+        reason: JoinDenyReason,
+        kick_message: &str,
+    ) -> JoinDeniedResponse {
+        JoinDeniedResponse {
+            player_uuid,
+            player_name: player_name.to_string(),
+            reason,
+            kick_message: kick_message.to_string(),
+        }
+    }
+}
+
+/// `data` of `mute_player` and the answer to `get_mute`
+#[derive(Serialize, Debug, JsonSchema)]
+pub struct PlayerMuteMessage {
+    #[serde(rename = "player_uuid")]
+    player_uuid: Uuid,
+
+    #[serde(rename = "muted")]
+    muted: bool,
+
+    #[serde(rename = "reason")]
+    reason: Option<String>,
+
+    /// format -> YYYY-MM-DD HH:MM:SS, `None` for permanent mutes
+    #[serde(rename = "expires_at")]
+    #[schemars(with = "Option<String>")]
+    expires_at: Option<NaiveDateTime>,
+}
+
+impl PlayerMuteMessage {
+    pub fn new(player_uuid: Uuid, mute: Option<&Punishment>) -> PlayerMuteMessage {
+        PlayerMuteMessage {
+            player_uuid,
+            muted: mute.is_some(),
+            reason: mute.map(|m| m.get_reason().to_string()),
+            expires_at: mute.and_then(|m| m.get_expires_at()),
+        }
+    }
+}
+
+/// `data` of `maintenance` and the answer to `get_maintenance`
+#[derive(Serialize, Debug, JsonSchema)]
+pub struct MaintenanceMessage {
+    /// the whole Network is in maintenance
+    #[serde(rename = "enabled")]
+    enabled: bool,
+
+    /// Tasks in maintenance, their Services get no new Players
+    #[serde(rename = "tasks")]
+    tasks: Vec<String>,
+
+    /// may contain `&` color codes
+    #[serde(rename = "motd")]
+    motd: String,
+
+    #[serde(rename = "kick_message")]
+    kick_message: String,
+
+    #[serde(rename = "bypass_permission")]
+    bypass_permission: String,
+}
+
+impl MaintenanceMessage {
+    pub fn new(
+        enabled: bool,
+        tasks: Vec<String>,
+        motd: &str,
+        kick_message: &str,
+        bypass_permission: &str,
+    ) -> MaintenanceMessage {
+        MaintenanceMessage {
+            enabled,
+            tasks,
+            motd: motd.to_string(),
+            kick_message: kick_message.to_string(),
+            bypass_permission: bypass_permission.to_string(),
+        }
+    }
+
+    pub fn get_tasks(&self) -> &[String] {
+        &self.tasks
+    }
+}
+
+/// `data` of `get_mute`
+#[derive(Deserialize, Debug, JsonSchema)]
+pub struct PlayerMuteRequest {
+    #[serde(rename = "player_uuid")]
+    player_uuid: Uuid,
+}
+
+impl PlayerMuteRequest {
+    pub fn get_player_uuid(&self) -> &Uuid {
+        &self.player_uuid
+    }
+}
+
+/// `data` of `find_player`, the `uuid` is used if both are set
+#[derive(Deserialize, Debug, JsonSchema)]
+pub struct FindPlayerRequest {
+    #[serde(default)]
+    #[serde(rename = "name")]
+    name: Option<String>,
+
+    #[serde(default)]
+    #[serde(rename = "uuid")]
+    uuid: Option<Uuid>,
+}
+
+impl FindPlayerRequest {
+    pub fn get_name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn get_uuid(&self) -> Option<Uuid> {
+        self.uuid
+    }
+}
+
+/// `data` of `player_sync`, the plugin is the source of truth for the Players of its Service
+#[derive(Deserialize, Debug, JsonSchema)]
+pub struct PlayerSyncMessage {
+    #[serde(rename = "players")]
+    players: Vec<SyncedPlayer>,
+}
+
+impl PlayerSyncMessage {
+    pub fn get_players(&self) -> &[SyncedPlayer] {
+        &self.players
+    }
+}
+
+#[derive(Deserialize, Debug, JsonSchema)]
+pub struct SyncedPlayer {
+    #[serde(rename = "uuid")]
+    uuid: Uuid,
+
+    #[serde(rename = "name")]
+    name: String,
+}
+
+impl SyncedPlayer {
+    pub fn get_uuid(&self) -> &Uuid {
+        &self.uuid
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// `data` of `list_players`, without filter all online Players are listed
+#[derive(Deserialize, Debug, Default, JsonSchema)]
+pub struct ListPlayersRequest {
+    #[serde(default)]
+    #[serde(rename = "service")]
+    service: Option<Uuid>,
+
+    #[serde(default)]
+    #[serde(rename = "task")]
+    task: Option<String>,
+}
+
+impl ListPlayersRequest {
+    pub fn get_service(&self) -> Option<&Uuid> {
+        self.service.as_ref()
+    }
+
+    pub fn get_task(&self) -> Option<&str> {
+        self.task.as_deref()
+    }
+}
+
+#[derive(Serialize, Debug, JsonSchema)]
+pub struct PlayerListResponse {
+    /// all online Players, independent of the filter
+    #[serde(rename = "online")]
+    online: usize,
+
+    #[serde(rename = "players")]
+    players: Vec<OnlinePlayer>,
+}
+
+impl PlayerListResponse {
+    pub fn new(online: usize, players: Vec<OnlinePlayer>) -> PlayerListResponse {
+        PlayerListResponse { online, players }
+    }
+}
+
+/// `data` of the answer to a `ping`, all fields are optional
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+pub struct PluginHeartbeat {
+    #[serde(default)]
+    #[serde(rename = "tps")]
+    tps: Option<f64>,
+
+    #[serde(default)]
+    #[serde(rename = "player_count")]
+    player_count: Option<u32>,
+}
+
+impl PluginHeartbeat {
+    pub fn get_tps(&self) -> Option<f64> {
+        self.tps
+    }
+
+    pub fn get_player_count(&self) -> Option<u32> {
+        self.player_count
+    }
+}
+
+/// `data` of the `auth` message
+#[derive(Deserialize, Debug, JsonSchema)]
+pub struct AuthRequest {
+    #[serde(rename = "secret")]
+    secret: String,
+
+    /// Plugins without a version speak protocol 1
+    #[serde(default = "default_protocol_version")]
+    #[serde(rename = "protocol_version")]
+    protocol_version: u32,
+
+    /// Features the plugin supports, e.g. `request_response`
+    #[serde(default)]
+    #[serde(rename = "capabilities")]
+    capabilities: Vec<String>,
+}
+
+impl AuthRequest {
+    pub fn get_secret(&self) -> &str {
+        &self.secret
+    }
+
+    pub fn get_protocol_version(&self) -> u32 {
+        self.protocol_version
+    }
+
+    pub fn get_capabilities(&self) -> &Vec<String> {
+        &self.capabilities
+    }
+}
+
+fn default_protocol_version() -> u32 {
+    1
+}
+
+/// `data` of the response to `auth`
+#[derive(Serialize, Debug, JsonSchema)]
+pub struct AuthResponse {
+    /// kept for protocol 1 plugins
+    #[serde(rename = "success")]
+    success: bool,
+
+    /// the version both sides use on this connection
+    #[serde(rename = "protocol_version")]
+    protocol_version: u32,
+
+    #[serde(rename = "min_protocol_version")]
+    min_protocol_version: u32,
+
+    #[serde(rename = "capabilities")]
+    capabilities: Vec<String>,
+
+    #[serde(rename = "deprecated")]
+    deprecated: Vec<Deprecation>,
+}
+
+impl AuthResponse {
+    pub fn new(
+        protocol_version: u32,
+        capabilities: Vec<String>,
+        deprecated: Vec<Deprecation>,
+    ) -> AuthResponse {
+        AuthResponse {
+            success: true,
+            protocol_version,
+            min_protocol_version: MIN_PROTOCOL_VERSION,
+            capabilities,
+            deprecated,
+        }
+    }
+}
+
+/// A part of the protocol the plugin still uses, but which will be removed
+#[derive(Serialize, Clone, Debug, JsonSchema)]
+pub struct Deprecation {
+    #[serde(rename = "name")]
+    name: String,
+
+    /// protocol version which no longer has it
+    #[serde(rename = "removed_in")]
+    removed_in: u32,
+
+    #[serde(rename = "description")]
+    description: String,
+}
+
+impl Deprecation {
+    pub fn new(name: &str, removed_in: u32, description: &str) -> Deprecation {
+        Deprecation {
+            name: name.to_string(),
+            removed_in,
+            description: description.to_string(),
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_removed_in(&self) -> u32 {
+        self.removed_in
+    }
 }
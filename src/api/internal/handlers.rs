@@ -2,11 +2,13 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use crate::api::internal::{
-    OutgoingMessage, OutgoingMessageType, PlayerActionMessage, ServiceInfoResponse,
+    FindPlayerRequest, ListPlayersRequest, OutgoingMessage, OutgoingMessageType,
+    PlayerActionMessage, PlayerListResponse, PlayerMuteMessage, PlayerMuteRequest,
+    PlayerSyncMessage, ServiceInfoResponse,
 };
 use crate::cloud::Cloud;
 use crate::log_error;
-use crate::types::EntityId;
+use crate::types::{EntityId, OnlinePlayer, PunishmentType, ServiceMetrics};
 use crate::utils::utils::Utils;
 
 pub struct APIInternalHandler;
@@ -50,6 +52,29 @@ impl APIInternalHandler {
         }
     }
 
+    /// Called periodically by the Minecraft Process (Minecraft Plugin) with its performance
+    pub async fn service_metrics(
+        cloud: Arc<RwLock<Cloud>>,
+        service_id: EntityId,
+        metrics: ServiceMetrics,
+    ) -> OutgoingMessage {
+        let node_manager = {
+            let cloud_guard = cloud.read().await;
+            cloud_guard.get_node_manager()
+        };
+
+        match node_manager
+            .on_local_service_metrics(service_id, metrics)
+            .await
+        {
+            Ok(()) => OutgoingMessage::null(None),
+            Err(e) => {
+                log_error!(3, "[service_metrics] Error: {}", e);
+                OutgoingMessage::err(None, e.to_string())
+            }
+        }
+    }
+
     /// Returns all backend servers currently available online
     pub async fn get_online_backend_services(cloud: Arc<RwLock<Cloud>>) -> OutgoingMessage {
         let node_manager = {
@@ -70,6 +95,132 @@ impl APIInternalHandler {
         }
     }
 
+    /// Where a Player is online in the Network, used by the plugins for `/find`
+    pub async fn find_player(
+        cloud: Arc<RwLock<Cloud>>,
+        request: FindPlayerRequest,
+    ) -> OutgoingMessage {
+        let node_manager = {
+            let cloud_guard = cloud.read().await;
+            cloud_guard.get_node_manager()
+        };
+
+        let player = match (request.get_uuid(), request.get_name()) {
+            (Some(uuid), _) => {
+                node_manager
+                    .find_network_player(|p| p.get_uuid() == uuid)
+                    .await
+            }
+            (None, Some(name)) => {
+                node_manager
+                    .find_network_player(|p| p.get_name().eq_ignore_ascii_case(name))
+                    .await
+            }
+            (None, None) => {
+                return OutgoingMessage::err(None, "name or uuid is required".to_string());
+            }
+        };
+
+        match player.and_then(|p| Utils::convert_to_json(&p)) {
+            Some(data) => OutgoingMessage::ok(None, OutgoingMessageType::Response, data),
+            None => OutgoingMessage::err(None, "Player is not online".to_string()),
+        }
+    }
+
+    /// Asked by the Proxy when a Player joins, the chat of muted Players is blocked by the Proxy
+    pub async fn get_mute(
+        cloud: Arc<RwLock<Cloud>>,
+        request: PlayerMuteRequest,
+    ) -> OutgoingMessage {
+        let moderation_manager = {
+            let cloud_guard = cloud.read().await;
+            cloud_guard.get_moderation_manager()
+        };
+
+        let uuid = request.get_player_uuid();
+        let mute = match moderation_manager
+            .get_active(PunishmentType::Mute, uuid)
+            .await
+        {
+            Ok(mute) => mute,
+            Err(e) => {
+                log_error!("{}", e);
+                return OutgoingMessage::err(None, e.to_string());
+            }
+        };
+
+        match Utils::convert_to_json(&PlayerMuteMessage::new(*uuid, mute.as_ref())) {
+            Some(data) => OutgoingMessage::ok(None, OutgoingMessageType::Response, data),
+            None => OutgoingMessage::err(None, "Cant Serialize Data".to_string()),
+        }
+    }
+
+    /// Asked by the Proxy after the start, later changes are pushed with `maintenance`
+    pub async fn get_maintenance(cloud: Arc<RwLock<Cloud>>) -> OutgoingMessage {
+        let maintenance_manager = {
+            let cloud_guard = cloud.read().await;
+            cloud_guard.get_maintenance_manager()
+        };
+
+        match Utils::convert_to_json(&maintenance_manager.get_state().await) {
+            Some(data) => OutgoingMessage::ok(None, OutgoingMessageType::Response, data),
+            None => OutgoingMessage::err(None, "Cant Serialize Data".to_string()),
+        }
+    }
+
+    /// The plugin sends all Players of its Service, the Node corrects its count and sessions
+    pub async fn player_sync(
+        cloud: Arc<RwLock<Cloud>>,
+        service_id: EntityId,
+        sync: PlayerSyncMessage,
+    ) -> OutgoingMessage {
+        let player_manager = {
+            let cloud_guard = cloud.read().await;
+            cloud_guard.get_player_manager()
+        };
+
+        match player_manager
+            .read()
+            .await
+            .sync_players(&service_id, &sync)
+            .await
+        {
+            Ok(()) => OutgoingMessage::null(None),
+            Err(e) => {
+                log_error!(3, "[player_sync] Error: {}", e);
+                OutgoingMessage::err(None, e.to_string())
+            }
+        }
+    }
+
+    /// Online Players of the Network, used by the plugins for `/glist`
+    pub async fn list_players(
+        cloud: Arc<RwLock<Cloud>>,
+        request: ListPlayersRequest,
+    ) -> OutgoingMessage {
+        let node_manager = {
+            let cloud_guard = cloud.read().await;
+            cloud_guard.get_node_manager()
+        };
+
+        let all = node_manager.get_network_players().await;
+        let online_count = all.len();
+        let players: Vec<OnlinePlayer> = match (request.get_service(), request.get_task()) {
+            (Some(service), _) => all
+                .into_iter()
+                .filter(|p| p.is_on_service(service))
+                .collect(),
+            (None, Some(task)) => all.into_iter().filter(|p| p.is_on_task(task)).collect(),
+            (None, None) => all,
+        };
+        let response = PlayerListResponse::new(online_count, players);
+
+        match Utils::convert_to_json(&response) {
+            Some(data) => OutgoingMessage::ok(None, OutgoingMessageType::Response, data),
+            None => OutgoingMessage::err(None, "Cant Serialize Data".to_string()),
+        }
+    }
+
     /// Called when a player performs an action (e.g., server change)
     pub async fn player_action(
         cloud: Arc<RwLock<Cloud>>,
@@ -80,7 +231,9 @@ impl APIInternalHandler {
             cloud_guard.get_player_manager()
         };
 
-        player_manager.read().await
+        player_manager
+            .read()
+            .await
             .handle_action(request)
             .await
             .unwrap_or_else(|e| {
@@ -1,16 +1,22 @@
-use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+use actix_web::{App, HttpRequest, HttpResponse, HttpServer, web};
 use actix_ws::{Message, Session};
 use futures_util::StreamExt;
 use serde_json::json;
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use uuid::Uuid;
 
+use crate::api::cluster::ClusterHandler;
 use crate::api::internal::{
-    APIInternalHandler, IncomingMessage, IncomingMessageType, OutgoingMessage, OutgoingMessageType,
-    PlayerActionMessage,
+    APIInternalHandler, AuthRequest, AuthResponse, FindPlayerRequest, IncomingMessage,
+    IncomingMessageType, ListPlayersRequest, MIN_PROTOCOL_VERSION, OutgoingMessage,
+    OutgoingMessageType, PROTOCOL_VERSION, PlayerActionMessage, PlayerMuteRequest,
+    PlayerSyncMessage, Protocol, ProtocolDescriptor,
 };
+#[cfg(feature = "metrics")]
+use crate::api::metrics::MetricsHandler;
 use crate::cloud::Cloud;
-use crate::types::{EntityId, ServiceProcessRef};
+use crate::types::{EntityId, ServiceMetrics, ServiceProcessRef};
 use crate::utils::error::{CantBindAddress, CloudResult, IntoCloudError};
 use crate::{error, log_error, log_info, log_warning};
 
@@ -29,12 +35,25 @@ async fn ws_handler(
     Ok(response)
 }
 
+/// Routes of optional cargo features
+fn configure_features(_cfg: &mut web::ServiceConfig) {
+    #[cfg(feature = "metrics")]
+    MetricsHandler::configure(_cfg);
+}
+
+async fn protocol_handler() -> HttpResponse {
+    HttpResponse::Ok().json(ProtocolDescriptor::current())
+}
+
 async fn handle_connection(
     cloud: Arc<RwLock<Cloud>>,
     mut session: Session,
     mut stream: impl StreamExt<Item = Result<Message, actix_ws::ProtocolError>> + Unpin,
 ) {
-    let mut bound_service: Option<ServiceProcessRef> = None;
+    // set once by a successful Auth, every other message needs it
+    let mut bound_service: Option<(ServiceProcessRef, Uuid)> = None;
+    // plugins speak protocol 1 until the Auth handshake says otherwise
+    let mut protocol_version = MIN_PROTOCOL_VERSION;
 
     while let Some(Ok(msg)) = stream.next().await {
         match msg {
@@ -42,45 +61,95 @@ async fn handle_connection(
                 let incoming: IncomingMessage = match serde_json::from_str(&text) {
                     Ok(m) => m,
                     Err(e) => {
-                        let _ = session.text(e.to_string()).await;
+                        let response = OutgoingMessage::err(None, e.to_string());
+                        let _ = session
+                            .text(response.for_protocol(protocol_version).to_string())
+                            .await;
                         continue;
                     }
                 };
 
-                let service_process_ref = {
-                    let sm = cloud.read().await.get_node_manager().get_service_manager();
-                    sm.read().await.find_from_id(&incoming.get_service_id())
-                };
-
-                bound_service = service_process_ref.clone();
+                if incoming.get_msg_typ() == &IncomingMessageType::Auth {
+                    let auth =
+                        match serde_json::from_value::<AuthRequest>(incoming.get_data().clone()) {
+                            Ok(auth) => auth,
+                            Err(e) => {
+                                let response = OutgoingMessage::err(
+                                    incoming.get_request_id(),
+                                    format!("Invalid Auth data: {}", e),
+                                );
+                                let _ = session.text(response.to_string()).await;
+                                let _ = session.close(None).await;
+                                return;
+                            }
+                        };
 
-                if bound_service.is_none() {
-                    let _ = session
-                        .text(
-                            OutgoingMessage::err(
+                    let version = match Protocol::negotiate(auth.get_protocol_version()) {
+                        Some(version) => version,
+                        None => {
+                            log_warning!(
+                                2,
+                                "[API] Service '{}' uses protocol {}, at least {} is required",
+                                incoming.get_service_id(),
+                                auth.get_protocol_version(),
+                                MIN_PROTOCOL_VERSION
+                            );
+                            let response = OutgoingMessage::err(
                                 incoming.get_request_id(),
-                                format!("Cant find Service: {}", incoming.get_service_id()),
-                            )
-                            .to_string(),
-                        )
-                        .await;
-                    continue;
-                }
+                                format!(
+                                    "Unsupported protocol version {}, supported are {} to {}",
+                                    auth.get_protocol_version(),
+                                    MIN_PROTOCOL_VERSION,
+                                    PROTOCOL_VERSION
+                                ),
+                            );
+                            let _ = session.text(response.to_string()).await;
+                            let _ = session.close(None).await;
+                            return;
+                        }
+                    };
 
-                if incoming.get_msg_typ() == IncomingMessageType::Auth {
-                    match service_process_ref {
+                    match authenticate(&cloud, &incoming, &auth).await {
                         Some(spr) => {
-                            spr.write().await.attach_session(session.clone());
+                            if let Some((old, session_id)) = bound_service.take() {
+                                old.write().await.detach_session(&session_id);
+                            }
+                            let session_id = spr.write().await.attach_session(
+                                session.clone(),
+                                version,
+                                auth.get_capabilities().clone(),
+                            );
+                            bound_service = Some((spr, session_id));
+                            protocol_version = version;
+
+                            log_info!(
+                                4,
+                                "[API] Server '{}' Auth with protocol {}",
+                                incoming.get_service_id(),
+                                version
+                            );
 
-                            log_info!(4, "[API] Server '{}' Auth", incoming.get_service_id());
+                            let deprecated = Protocol::deprecations(version);
+                            for deprecation in &deprecated {
+                                log_warning!(
+                                    4,
+                                    "[API] Server '{}' uses deprecated '{}', removed in protocol {}",
+                                    incoming.get_service_id(),
+                                    deprecation.get_name(),
+                                    deprecation.get_removed_in()
+                                );
+                            }
 
                             let response = OutgoingMessage::ok(
                                 incoming.get_request_id(),
                                 OutgoingMessageType::Response,
-                                json!({
-                                    "success": true
-                                }),
-                            );
+                                json!(AuthResponse::new(
+                                    version,
+                                    Protocol::capabilities(),
+                                    deprecated
+                                )),
+                            )
+                            .for_protocol(version);
 
                             if session.text(response.to_string()).await.is_err() {
                                 log_warning!(
@@ -92,9 +161,14 @@ async fn handle_connection(
                         }
 
                         None => {
+                            log_warning!(
+                                2,
+                                "[API] Rejected Auth for Service '{}'",
+                                incoming.get_service_id()
+                            );
                             let response = OutgoingMessage::err(
                                 incoming.get_request_id(),
-                                format!("Unknown service: '{}'", incoming.get_service_id()),
+                                "Authentication failed".to_string(),
                             );
 
                             let _ = session.text(response.to_string()).await;
@@ -107,9 +181,41 @@ async fn handle_connection(
                     continue;
                 }
 
+                // only the authenticated Service may send messages on this connection
+                let is_bound = match &bound_service {
+                    Some((spr, _)) => spr.get_id().await == incoming.get_service_id(),
+                    None => false,
+                };
+                if !is_bound {
+                    let response = OutgoingMessage::err(
+                        incoming.get_request_id(),
+                        "Not authenticated".to_string(),
+                    )
+                    .for_protocol(protocol_version);
+                    let _ = session.text(response.to_string()).await;
+                    continue;
+                }
+
+                // answers to requests of the Cloud are not answered again
+                if let Some((spr, _)) = &bound_service {
+                    if let Some(result) = response_result(&incoming) {
+                        let request_id = incoming.get_request_id().unwrap_or_default();
+                        if !spr.write().await.resolve_request(&request_id, result) {
+                            log_warning!(
+                                5,
+                                "[API] Unknown or timed out request '{}' from '{}'",
+                                request_id,
+                                incoming.get_service_id()
+                            );
+                        }
+                        continue;
+                    }
+                }
+
                 // Normales Message-Routing
                 let msg = handle_text_message(incoming, cloud.clone())
                     .await
+                    .for_protocol(protocol_version)
                     .to_string();
 
                 if session.text(msg).await.is_err() {
@@ -123,8 +229,8 @@ async fn handle_connection(
             }
 
             Message::Close(reason) => {
-                if let Some(svc) = &bound_service {
-                    svc.write().await.detach_session();
+                if let Some((svc, session_id)) = &bound_service {
+                    svc.write().await.detach_session(session_id);
                     log_info!(4, "[API] Server '{}' disconnected", svc.get_name().await);
                 }
                 let _ = session.close(reason).await;
@@ -135,12 +241,50 @@ async fn handle_connection(
         }
     }
 
-    if let Some(svc) = &bound_service {
-        svc.write().await.detach_session();
+    if let Some((svc, session_id)) = &bound_service {
+        svc.write().await.detach_session(session_id);
         log_info!(3, "[API] Server '{}' lost connection", svc.get_name().await);
     }
 }
 
+/// `Some` if the message answers a request of the Cloud
+fn response_result(incoming: &IncomingMessage) -> Option<CloudResult<serde_json::Value>> {
+    match incoming.get_msg_typ() {
+        IncomingMessageType::Response => Some(Ok(incoming.get_data().clone())),
+        IncomingMessageType::Error => {
+            let reason = incoming
+                .get_data()
+                .get("error")
+                .and_then(|e| e.as_str())
+                .unwrap_or("Unknown error")
+                .to_string();
+            Some(Err(error!(RequestFailed, reason)))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the Service if the secret in the Auth message is right
+async fn authenticate(
+    cloud: &Arc<RwLock<Cloud>>,
+    incoming: &IncomingMessage,
+    auth: &AuthRequest,
+) -> Option<ServiceProcessRef> {
+    let service_process_ref = {
+        let sm = cloud.read().await.get_node_manager().get_service_manager();
+        sm.read().await.find_from_id(&incoming.get_service_id())
+    }?;
+
+    if !service_process_ref
+        .read()
+        .await
+        .verify_secret(auth.get_secret())
+    {
+        return None;
+    }
+    Some(service_process_ref)
+}
+
 async fn handle_text_message(msg: IncomingMessage, cloud: Arc<RwLock<Cloud>>) -> OutgoingMessage {
     let mut result = match msg.get_msg_typ() {
         IncomingMessageType::GetOnlineBackendServices => {
@@ -158,23 +302,93 @@ async fn handle_text_message(msg: IncomingMessage, cloud: Arc<RwLock<Cloud>>) ->
         }
 
         IncomingMessageType::PlayerAction => {
-            match serde_json::from_value::<PlayerActionMessage>(
-                msg.get_data().clone()
-            ) {
-                Ok(data) => {
-                    APIInternalHandler::player_action(cloud, data).await
+            match serde_json::from_value::<PlayerActionMessage>(msg.get_data().clone()) {
+                // a plugin only reports the Players of the Service it is authenticated for
+                Ok(data) if data.get_service_uuid() != msg.get_service_id() => {
+                    log_warning!(
+                        2,
+                        "[PlayerAction] Service '{}' sent an action for '{}'",
+                        msg.get_service_id(),
+                        data.get_service_uuid()
+                    );
+                    OutgoingMessage::err(
+                        None,
+                        "PlayerAction for another Service is not allowed".to_string(),
+                    )
                 }
+                Ok(data) => APIInternalHandler::player_action(cloud, data).await,
 
                 Err(e) => {
                     log_error!(3, "[PlayerAction] Invalid request data: {}", e);
 
-                    OutgoingMessage::err(
-                        None,
-                        format!("Invalid PlayerAction data: {}", e),
+                    OutgoingMessage::err(None, format!("Invalid PlayerAction data: {}", e))
+                }
+            }
+        }
+        IncomingMessageType::Metrics => {
+            match serde_json::from_value::<ServiceMetrics>(msg.get_data().clone()) {
+                Ok(metrics) => {
+                    APIInternalHandler::service_metrics(
+                        cloud,
+                        EntityId::from(msg.get_service_id()),
+                        metrics,
                     )
+                    .await
+                }
+                Err(e) => OutgoingMessage::err(None, format!("Invalid Metrics data: {}", e)),
+            }
+        }
+
+        IncomingMessageType::FindPlayer => {
+            match serde_json::from_value::<FindPlayerRequest>(msg.get_data().clone()) {
+                Ok(request) => APIInternalHandler::find_player(cloud, request).await,
+                Err(e) => OutgoingMessage::err(None, format!("Invalid find_player data: {}", e)),
+            }
+        }
+
+        IncomingMessageType::ListPlayers => {
+            // `data` is optional, no data lists all Players
+            let request = if msg.get_data().is_null() {
+                Ok(ListPlayersRequest::default())
+            } else {
+                serde_json::from_value::<ListPlayersRequest>(msg.get_data().clone())
+            };
+            match request {
+                Ok(request) => APIInternalHandler::list_players(cloud, request).await,
+                Err(e) => OutgoingMessage::err(None, format!("Invalid list_players data: {}", e)),
+            }
+        }
+
+        IncomingMessageType::GetMute => {
+            match serde_json::from_value::<PlayerMuteRequest>(msg.get_data().clone()) {
+                Ok(request) => APIInternalHandler::get_mute(cloud, request).await,
+                Err(e) => OutgoingMessage::err(None, format!("Invalid get_mute data: {}", e)),
+            }
+        }
+
+        IncomingMessageType::GetMaintenance => APIInternalHandler::get_maintenance(cloud).await,
+
+        IncomingMessageType::PlayerSync => {
+            match serde_json::from_value::<PlayerSyncMessage>(msg.get_data().clone()) {
+                Ok(sync) => {
+                    APIInternalHandler::player_sync(
+                        cloud,
+                        EntityId::from(msg.get_service_id()),
+                        sync,
+                    )
+                    .await
                 }
+                Err(e) => OutgoingMessage::err(None, format!("Invalid player_sync data: {}", e)),
             }
         }
+
+        IncomingMessageType::Unknown => OutgoingMessage::err(
+            msg.get_request_id(),
+            format!(
+                "Unsupported message type for protocol {}, see GET /internal/protocol",
+                PROTOCOL_VERSION
+            ),
+        ),
         _ => OutgoingMessage::err(None, "Unknown message type".to_string()),
     };
 
@@ -204,6 +418,9 @@ impl APIInternal {
                     App::new()
                         .app_data(web::Data::new(cloud.clone()))
                         .route("/internal", web::get().to(ws_handler))
+                        .route("/internal/protocol", web::get().to(protocol_handler))
+                        .configure(ClusterHandler::configure)
+                        .configure(configure_features)
                 };
 
                 let server = match HttpServer::new(app).bind(&bind_addr) {
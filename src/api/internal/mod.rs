@@ -1,7 +1,9 @@
 pub use dtos::*;
 pub use handlers::*;
 pub use internal_server::*;
+pub use protocol::*;
 
 mod dtos;
 mod handlers;
 mod internal_server;
+mod protocol;
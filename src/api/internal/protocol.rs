@@ -0,0 +1,202 @@
+use schemars::schema_for;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use strum::IntoEnumIterator;
+
+use crate::api::internal::{
+    AuthRequest, AuthResponse, BroadcastMessage, Deprecation, FindPlayerRequest, IncomingMessage,
+    IncomingMessageType, JoinDeniedResponse, ListPlayersRequest, MIN_PROTOCOL_VERSION,
+    MaintenanceMessage, OutgoingMessage, OutgoingMessageType, PROTOCOL_VERSION,
+    PlayerActionMessage, PlayerChatMessage, PlayerKickMessage, PlayerListResponse,
+    PlayerMuteMessage, PlayerMuteRequest, PlayerSyncMessage, PluginHeartbeat, ServiceInfoResponse,
+};
+use crate::types::{OnlinePlayer, ServiceMetrics};
+
+/// Features beside the message types, announced in the Auth handshake
+pub const CAPABILITY_REQUEST_RESPONSE: &str = "request_response";
+
+/// The plugin answers `ping`, only together with `request_response` the Node tracks its liveness
+pub const CAPABILITY_HEARTBEAT: &str = "heartbeat";
+
+/// Machine readable description of the internal WebSocket protocol,
+/// served at `GET /internal/protocol`
+#[derive(Serialize)]
+pub struct ProtocolDescriptor {
+    #[serde(rename = "protocol_version")]
+    protocol_version: u32,
+
+    #[serde(rename = "min_protocol_version")]
+    min_protocol_version: u32,
+
+    #[serde(rename = "capabilities")]
+    capabilities: Vec<String>,
+
+    #[serde(rename = "incoming_types")]
+    incoming_types: Vec<String>,
+
+    #[serde(rename = "outgoing_types")]
+    outgoing_types: Vec<String>,
+
+    #[serde(rename = "deprecated")]
+    deprecated: Vec<Deprecation>,
+
+    /// JSON Schemas generated from the dtos
+    #[serde(rename = "schemas")]
+    schemas: BTreeMap<String, Value>,
+}
+
+impl ProtocolDescriptor {
+    pub fn current() -> ProtocolDescriptor {
+        let mut schemas = BTreeMap::new();
+        schemas.insert(
+            "IncomingMessage".to_string(),
+            schema(schema_for!(IncomingMessage)),
+        );
+        schemas.insert(
+            "OutgoingMessage".to_string(),
+            schema(schema_for!(OutgoingMessage)),
+        );
+        schemas.insert("AuthRequest".to_string(), schema(schema_for!(AuthRequest)));
+        schemas.insert(
+            "AuthResponse".to_string(),
+            schema(schema_for!(AuthResponse)),
+        );
+        schemas.insert(
+            "PlayerActionMessage".to_string(),
+            schema(schema_for!(PlayerActionMessage)),
+        );
+        schemas.insert(
+            "PluginHeartbeat".to_string(),
+            schema(schema_for!(PluginHeartbeat)),
+        );
+        schemas.insert(
+            "ServiceMetrics".to_string(),
+            schema(schema_for!(ServiceMetrics)),
+        );
+        schemas.insert(
+            "ServiceInfoResponse".to_string(),
+            schema(schema_for!(ServiceInfoResponse)),
+        );
+        schemas.insert(
+            "FindPlayerRequest".to_string(),
+            schema(schema_for!(FindPlayerRequest)),
+        );
+        schemas.insert(
+            "ListPlayersRequest".to_string(),
+            schema(schema_for!(ListPlayersRequest)),
+        );
+        schemas.insert(
+            "PlayerListResponse".to_string(),
+            schema(schema_for!(PlayerListResponse)),
+        );
+        schemas.insert(
+            "OnlinePlayer".to_string(),
+            schema(schema_for!(OnlinePlayer)),
+        );
+        schemas.insert(
+            "PlayerKickMessage".to_string(),
+            schema(schema_for!(PlayerKickMessage)),
+        );
+        schemas.insert(
+            "PlayerChatMessage".to_string(),
+            schema(schema_for!(PlayerChatMessage)),
+        );
+        schemas.insert(
+            "BroadcastMessage".to_string(),
+            schema(schema_for!(BroadcastMessage)),
+        );
+        schemas.insert(
+            "JoinDeniedResponse".to_string(),
+            schema(schema_for!(JoinDeniedResponse)),
+        );
+        schemas.insert(
+            "PlayerMuteMessage".to_string(),
+            schema(schema_for!(PlayerMuteMessage)),
+        );
+        schemas.insert(
+            "PlayerMuteRequest".to_string(),
+            schema(schema_for!(PlayerMuteRequest)),
+        );
+        schemas.insert(
+            "MaintenanceMessage".to_string(),
+            schema(schema_for!(MaintenanceMessage)),
+        );
+        schemas.insert(
+            "PlayerSyncMessage".to_string(),
+            schema(schema_for!(PlayerSyncMessage)),
+        );
+
+        ProtocolDescriptor {
+            protocol_version: PROTOCOL_VERSION,
+            min_protocol_version: MIN_PROTOCOL_VERSION,
+            capabilities: Protocol::capabilities(),
+            incoming_types: Protocol::incoming_types(),
+            outgoing_types: Protocol::outgoing_types(),
+            deprecated: Protocol::deprecations(MIN_PROTOCOL_VERSION),
+            schemas,
+        }
+    }
+}
+
+pub struct Protocol;
+
+impl Protocol {
+    /// The version used on a connection, `None` if the plugin is too old
+    pub fn negotiate(plugin_version: u32) -> Option<u32> {
+        if plugin_version < MIN_PROTOCOL_VERSION {
+            return None;
+        }
+        Some(plugin_version.min(PROTOCOL_VERSION))
+    }
+
+    pub fn capabilities() -> Vec<String> {
+        let mut capabilities = vec![
+            CAPABILITY_REQUEST_RESPONSE.to_string(),
+            CAPABILITY_HEARTBEAT.to_string(),
+        ];
+        capabilities.extend(Self::incoming_types());
+        capabilities
+    }
+
+    pub fn incoming_types() -> Vec<String> {
+        IncomingMessageType::iter()
+            .filter(|t| t != IncomingMessageType::Unknown)
+            .filter_map(|t| serde_name(&t))
+            .collect()
+    }
+
+    pub fn outgoing_types() -> Vec<String> {
+        OutgoingMessageType::iter()
+            .filter_map(|t| serde_name(&t))
+            .collect()
+    }
+
+    /// Everything a plugin on this version still uses, but a newer version removed
+    pub fn deprecations(protocol_version: u32) -> Vec<Deprecation> {
+        let all = vec![
+            Deprecation::new(
+                "OutgoingMessage.success",
+                2,
+                "Use the message type, `error` means the request failed",
+            ),
+            Deprecation::new(
+                "auth without protocol_version",
+                2,
+                "Send `protocol_version` and `capabilities` in the auth data",
+            ),
+        ];
+
+        all.into_iter()
+            .filter(|d| protocol_version < d.get_removed_in())
+            .collect()
+    }
+}
+
+fn serde_name<T: Serialize>(value: &T) -> Option<String> {
+    serde_json::to_value(value).ok()?.as_str().map(String::from)
+}
+
+fn schema(schema: schemars::Schema) -> Value {
+    serde_json::to_value(schema).unwrap_or(Value::Null)
+}
@@ -0,0 +1,110 @@
+use actix_web::{HttpResponse, web};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::cloud::Cloud;
+use crate::utils::metrics::{METRICS, escape};
+
+/// `GET /metrics` in the Prometheus text format, served by the internal API.
+///
+/// Every Node exports only its local Services, so every Node has to be scraped.
+pub struct MetricsHandler;
+
+impl MetricsHandler {
+    pub fn configure(cfg: &mut web::ServiceConfig) {
+        cfg.route("/metrics", web::get().to(Self::metrics));
+    }
+
+    async fn metrics(cloud: web::Data<Arc<RwLock<Cloud>>>) -> HttpResponse {
+        let mut out = String::new();
+        Self::write_services(&cloud, &mut out).await;
+        METRICS.write_prometheus(&mut out);
+
+        HttpResponse::Ok()
+            .content_type("text/plain; version=0.0.4; charset=utf-8")
+            .body(out)
+    }
+
+    async fn write_services(cloud: &Arc<RwLock<Cloud>>, out: &mut String) {
+        let (node, service_manager) = {
+            let c = cloud.read().await;
+            (
+                c.get_config().get_name(),
+                c.get_node_manager().get_service_manager(),
+            )
+        };
+        let node = escape(&node);
+        let service_refs = service_manager.read().await.filter_services(|_| true).await;
+
+        // (task, status) -> count
+        let mut services: BTreeMap<(String, String), u64> = BTreeMap::new();
+        let mut task_players: BTreeMap<String, u64> = BTreeMap::new();
+        let mut service_players = Vec::new();
+        let mut sessions = 0;
+
+        for service_ref in service_refs {
+            let sp = service_ref.read().await;
+            let task = escape(sp.get_task_name());
+
+            *services
+                .entry((task.clone(), sp.get_status().to_string()))
+                .or_insert(0) += 1;
+            if sp.is_start() {
+                *task_players.entry(task.clone()).or_insert(0) += sp.get_current_players() as u64;
+                service_players.push((escape(sp.get_name()), task, sp.get_current_players()));
+            }
+            if sp.has_session() {
+                sessions += 1;
+            }
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP minecloud_services Services per Task and status"
+        );
+        let _ = writeln!(out, "# TYPE minecloud_services gauge");
+        for ((task, status), count) in &services {
+            let _ = writeln!(
+                out,
+                "minecloud_services{{node=\"{}\",task=\"{}\",status=\"{}\"}} {}",
+                node, task, status, count
+            );
+        }
+
+        let _ = writeln!(out, "# HELP minecloud_service_players Players per Service");
+        let _ = writeln!(out, "# TYPE minecloud_service_players gauge");
+        for (service, task, players) in &service_players {
+            let _ = writeln!(
+                out,
+                "minecloud_service_players{{node=\"{}\",task=\"{}\",service=\"{}\"}} {}",
+                node, task, service, players
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP minecloud_task_players Players per Task on this Node"
+        );
+        let _ = writeln!(out, "# TYPE minecloud_task_players gauge");
+        for (task, players) in &task_players {
+            let _ = writeln!(
+                out,
+                "minecloud_task_players{{node=\"{}\",task=\"{}\"}} {}",
+                node, task, players
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP minecloud_ws_sessions Plugins connected over the internal WebSocket"
+        );
+        let _ = writeln!(out, "# TYPE minecloud_ws_sessions gauge");
+        let _ = writeln!(
+            out,
+            "minecloud_ws_sessions{{node=\"{}\"}} {}",
+            node, sessions
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub use metrics_handler::MetricsHandler;
+
+mod metrics_handler;
@@ -1,4 +1,6 @@
 pub mod cluster;
+#[cfg(feature = "rest-api")]
+pub mod external;
 pub mod internal;
-//#[cfg(feature = "rest-api")]
-//pub mod external;
+#[cfg(feature = "metrics")]
+pub mod metrics;
@@ -5,20 +5,24 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 use std::{env, fs};
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, watch};
+use tokio::task::JoinHandle;
 
 use crate::api::internal::APIInternal;
 use crate::config::{CloudConfig, SoftwareConfig, SoftwareConfigRef};
 use crate::database::table::Tables;
-use crate::log_info;
-use crate::manager::{GroupManagerRef, Manager, NodeManager, PlayerManagerRef, TaskManagerRef};
+use crate::manager::{
+    ApiTokenManager, GroupManagerRef, MaintenanceManager, Manager, ModerationManager, NodeManager,
+    PlayerManagerRef, TaskManagerRef,
+};
 use crate::node::scheduler::Scheduler;
 use crate::terminal::cmd::Cmd;
 use crate::utils::error::*;
 use crate::utils::log::logger::Logger;
+use crate::{error, log_info, log_warning};
 
 #[cfg(feature = "rest-api")]
-use crate::api::external::restapi_main::ApiMain;
+use crate::api::external::ApiMain;
 
 pub struct Cloud {
     config: Arc<CloudConfig>,
@@ -29,11 +33,22 @@ pub struct Cloud {
     node_manager: Arc<NodeManager>,
     player_manager: PlayerManagerRef,
     group_manager: GroupManagerRef,
+    api_token_manager: Arc<ApiTokenManager>,
+    moderation_manager: Arc<ModerationManager>,
+    maintenance_manager: Arc<MaintenanceManager>,
+    shutdown: watch::Sender<bool>,
+    background_tasks: Vec<JoinHandle<()>>,
 }
 
 impl Cloud {
     pub async fn new(cloud_config: CloudConfig, url: String) -> CloudResult<Self> {
         let config = Arc::new(cloud_config);
+        // without a secret any Node could start and stop Services on this one
+        let cluster = config.get_cluster();
+        if !cluster.get_seeds().is_empty() && cluster.get_secret().is_empty() {
+            return Err(error!(ClusterSecretMissing));
+        }
+
         let software_config = SoftwareConfigRef::new(
             SoftwareConfig::check_and_get(config.clone(), &url)
                 .await
@@ -45,7 +60,7 @@ impl Cloud {
         Tables::check_tables(db.as_ref()).await?;
         log_info!("Database check successfully");
 
-        let (pm, tm, nm, gm) =
+        let (pm, tm, nm, gm, mm, mtm) =
             Manager::create_all(db.clone(), config.clone(), software_config.clone()).await?;
         let scheduler = Arc::new(Scheduler::new(
             db.clone(),
@@ -55,6 +70,8 @@ impl Cloud {
             tm.clone(),
         ));
 
+        let api_token_manager = Arc::new(ApiTokenManager::new(db.clone()));
+
         Ok(Self {
             config,
             _software_config: software_config,
@@ -64,6 +81,11 @@ impl Cloud {
             task_manager: tm,
             player_manager: pm,
             group_manager: gm,
+            api_token_manager,
+            moderation_manager: mm,
+            maintenance_manager: mtm,
+            shutdown: watch::channel(false).0,
+            background_tasks: Vec::new(),
         })
     }
 
@@ -79,12 +101,68 @@ impl Cloud {
     pub fn get_scheduler(&self) -> &Arc<Scheduler> {
         &self.scheduler
     }
-    pub fn get_task_manager(self) -> TaskManagerRef {
+    pub fn get_task_manager(&self) -> TaskManagerRef {
         self.task_manager.clone()
     }
     pub fn get_player_manager(&self) -> PlayerManagerRef {
         self.player_manager.clone()
     }
+    pub fn get_group_manager(&self) -> GroupManagerRef {
+        self.group_manager.clone()
+    }
+    pub fn get_api_token_manager(&self) -> Arc<ApiTokenManager> {
+        self.api_token_manager.clone()
+    }
+    pub fn get_moderation_manager(&self) -> Arc<ModerationManager> {
+        self.moderation_manager.clone()
+    }
+    pub fn get_maintenance_manager(&self) -> Arc<MaintenanceManager> {
+        self.maintenance_manager.clone()
+    }
+
+    /// Receiver which switches to `true` as soon as the Cloud is shutting down
+    pub fn subscribe_shutdown(&self) -> watch::Receiver<bool> {
+        self.shutdown.subscribe()
+    }
+
+    /// Start the Scheduler, it runs until `disable` is called
+    pub fn start_scheduler(&mut self) {
+        let handle = self.scheduler.clone().start(
+            self.config.get_scheduler_interval(),
+            self.subscribe_shutdown(),
+        );
+        self.background_tasks.push(handle);
+        log_info!(3, "Scheduler started!");
+    }
+
+    /// Join the Cluster, the Node leaves it again when `disable` is called
+    pub async fn start_cluster(&mut self) {
+        let handle = self
+            .node_manager
+            .clone()
+            .start_cluster(self.subscribe_shutdown())
+            .await;
+        self.background_tasks.push(handle);
+        self.maintenance_manager.sync_from_cluster().await;
+    }
+
+    /// Sample the host and the Service processes until `disable` is called
+    pub fn start_resource_monitor(&mut self) {
+        let handle = self
+            .node_manager
+            .clone()
+            .start_resource_monitor(self.subscribe_shutdown());
+        self.background_tasks.push(handle);
+    }
+
+    /// Ping the plugins of the local Services until `disable` is called
+    pub fn start_plugin_heartbeat(&mut self) {
+        let handle = self
+            .node_manager
+            .clone()
+            .start_plugin_heartbeat(self.subscribe_shutdown());
+        self.background_tasks.push(handle);
+    }
 
     pub async fn enable(version: &str) -> CloudResult<()> {
         // download link
@@ -113,11 +191,8 @@ impl Cloud {
         APIInternal::start(cloud.clone()).await?;
 
         #[cfg(feature = "rest-api")]
-        {
-            let cloud_clone = cloud.clone();
-            std::thread::spawn(move || {
-                //let _ = ApiMain::start(cloud_clone);
-            });
+        if let Err(e) = ApiMain::start(cloud.clone()).await {
+            log_warning!(1, "REST API cant start: {}", e);
         }
 
         tokio::time::sleep(Duration::from_secs(1)).await;
@@ -126,21 +201,24 @@ impl Cloud {
             cloud.clone(),
         );
 
-        {
-            let cloud_clone = cloud.read().await;
-            let _scheduler = cloud_clone.scheduler.clone();
-
-            tokio::spawn(async move {
-                //scheduler.run().await;
-            });
-            log_info!(3, "Scheduler started!");
-        }
+        cloud.write().await.start_resource_monitor();
+        cloud.write().await.start_cluster().await;
+        cloud.write().await.start_plugin_heartbeat();
+        cloud.write().await.start_scheduler();
 
         cmd.start().await;
         Ok(())
     }
 
     pub async fn disable(&mut self) {
+        // stop all background loops first, so no new Service is started while shutting down
+        let _ = self.shutdown.send(true);
+        for handle in self.background_tasks.drain(..) {
+            if let Err(e) = handle.await {
+                log_warning!(2, "Background Task ended with Error: {}", e);
+            }
+        }
+
         self.node_manager
             .stop_all_local_services("Cloud Disable")
             .await;
@@ -4,8 +4,10 @@ use database_manager::config::DatabaseConfig;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use crate::cloud::Cloud;
+use crate::types::{Fallback, PlacementPolicy};
 use crate::utils::utils::Utils;
 use crate::{log_error, log_info};
 
@@ -17,10 +19,30 @@ pub struct CloudConfig {
     log_level: u8,
     server_host: String,
     max_ram: u64,
+    #[serde(default = "default_scheduler_interval")]
+    scheduler_interval: u64,
+    #[serde(default = "default_resource_monitor_interval")]
+    resource_monitor_interval: u64,
     node_host: Address,
     rest_api: Address,
     database: DatabaseConfig,
     path: CloudConfigPath,
+    #[serde(default)]
+    fallbacks: Vec<Fallback>,
+    #[serde(default)]
+    cluster: ClusterConfig,
+    #[serde(default)]
+    plugin_heartbeat: PluginHeartbeatConfig,
+    #[serde(default)]
+    placement_policy: PlacementPolicy,
+    /// Origins which may call the REST API from a browser
+    #[serde(default)]
+    rest_api_cors_origins: Vec<String>,
+    /// Only Players on the whitelist may join the Network, can be switched at runtime
+    #[serde(default)]
+    whitelist: bool,
+    #[serde(default)]
+    maintenance: MaintenanceConfig,
 }
 
 impl CloudConfig {
@@ -43,10 +65,19 @@ impl CloudConfig {
             log_level: log_level.clone(),
             server_host: server_host.clone(),
             max_ram: max_ram.clone(),
+            scheduler_interval: default_scheduler_interval(),
+            resource_monitor_interval: default_resource_monitor_interval(),
             node_host: node_host.clone(),
             rest_api: rest_api.clone(),
             database: datenbank.clone(),
             path: path.clone(),
+            fallbacks: Vec::new(),
+            cluster: ClusterConfig::default(),
+            plugin_heartbeat: PluginHeartbeatConfig::default(),
+            placement_policy: PlacementPolicy::default(),
+            rest_api_cors_origins: Vec::new(),
+            whitelist: false,
+            maintenance: MaintenanceConfig::default(),
         }
     }
 
@@ -73,6 +104,16 @@ impl CloudConfig {
         self.max_ram.clone()
     }
 
+    /// Interval between two Scheduler ticks
+    pub fn get_scheduler_interval(&self) -> Duration {
+        Duration::from_secs(self.scheduler_interval.max(1))
+    }
+
+    /// Interval between two samples of the host and the Service processes
+    pub fn get_resource_monitor_interval(&self) -> Duration {
+        Duration::from_secs(self.resource_monitor_interval.max(1))
+    }
+
     pub fn get_node_host(&self) -> Address {
         self.node_host.clone()
     }
@@ -81,6 +122,37 @@ impl CloudConfig {
         self.rest_api.clone()
     }
 
+    /// Fallback groups sorted by priority, highest first
+    pub fn get_fallbacks(&self) -> Vec<Fallback> {
+        let mut fallbacks = self.fallbacks.clone();
+        fallbacks.sort_by(|a, b| b.get_priority().cmp(&a.get_priority()));
+        fallbacks
+    }
+
+    pub fn get_rest_api_cors_origins(&self) -> &Vec<String> {
+        &self.rest_api_cors_origins
+    }
+
+    pub fn is_whitelist_enabled(&self) -> bool {
+        self.whitelist
+    }
+
+    pub fn get_placement_policy(&self) -> &PlacementPolicy {
+        &self.placement_policy
+    }
+
+    pub fn get_cluster(&self) -> &ClusterConfig {
+        &self.cluster
+    }
+
+    pub fn get_plugin_heartbeat(&self) -> &PluginHeartbeatConfig {
+        &self.plugin_heartbeat
+    }
+
+    pub fn get_maintenance(&self) -> &MaintenanceConfig {
+        &self.maintenance
+    }
+
     pub fn get_db_config(&self) -> DatabaseConfig {
         self.database.clone()
     }
@@ -134,11 +206,54 @@ impl CloudConfig {
         println!("Language: {}", self.get_language());
         println!("Server Host: {}", self.get_server_host());
         println!("Max RAM: {}", self.get_max_ram());
+        println!(
+            "Scheduler Interval: {}s",
+            self.get_scheduler_interval().as_secs()
+        );
+        println!(
+            "Resource Monitor Interval: {}s",
+            self.get_resource_monitor_interval().as_secs()
+        );
+        println!("Placement Policy: {}", self.get_placement_policy());
         println!("Node Host IP: {}", self.get_node_host().get_ip());
         println!("Node Host PORT: {}", self.get_node_host().get_port());
         println!("REST API IP: {}", self.get_rest_api().get_ip());
         println!("REST API PORT: {}", self.get_rest_api().get_port());
 
+        println!("Fallbacks:");
+        for fallback in self.get_fallbacks() {
+            println!(
+                "  {} (Priority: {}, Permission: '{}'): {}",
+                fallback.get_name(),
+                fallback.get_priority(),
+                fallback.get_permission(),
+                fallback.get_tasks().join(", ")
+            );
+        }
+
+        println!("Cluster:");
+        for seed in self.cluster.get_seeds() {
+            println!("  Seed: {}", seed);
+        }
+        println!(
+            "  Heartbeat Interval: {}s",
+            self.cluster.get_heartbeat_interval().as_secs()
+        );
+        println!(
+            "  Node Timeout: {}s",
+            self.cluster.get_node_timeout().as_secs()
+        );
+
+        println!("Plugin Heartbeat:");
+        println!(
+            "  Interval: {}s",
+            self.plugin_heartbeat.get_interval().as_secs()
+        );
+        println!(
+            "  Timeout: {}s",
+            self.plugin_heartbeat.get_timeout().as_secs()
+        );
+
         let path = self.get_cloud_path();
         println!("Path:");
         println!("  Task Folder: {}", path.get_task_folder());
@@ -168,6 +283,132 @@ impl CloudConfig {
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ClusterConfig {
+    /// Nodes which are contacted to join the Cluster
+    #[serde(default)]
+    seeds: Vec<Address>,
+
+    /// Shared secret every Node has to send, without it no Node is accepted
+    #[serde(default)]
+    secret: String,
+
+    /// Seconds between heartbeats,
+    /// the Services of the other Nodes are fetched again with every heartbeat
+    #[serde(default = "default_heartbeat_interval")]
+    heartbeat_interval: u64,
+
+    /// A Node without heartbeat for this time is removed from the Cluster
+    #[serde(default = "default_node_timeout")]
+    node_timeout: u64,
+}
+
+impl ClusterConfig {
+    pub fn get_seeds(&self) -> &Vec<Address> {
+        &self.seeds
+    }
+
+    pub fn get_secret(&self) -> &str {
+        &self.secret
+    }
+
+    pub fn get_heartbeat_interval(&self) -> Duration {
+        Duration::from_secs(self.heartbeat_interval.max(1))
+    }
+
+    pub fn get_node_timeout(&self) -> Duration {
+        Duration::from_secs(self.node_timeout.max(1))
+    }
+}
+
+impl Default for ClusterConfig {
+    fn default() -> Self {
+        ClusterConfig {
+            seeds: Vec::new(),
+            secret: String::new(),
+            heartbeat_interval: default_heartbeat_interval(),
+            node_timeout: default_node_timeout(),
+        }
+    }
+}
+
+/// Pings the Node sends to the plugins of the local Services
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PluginHeartbeatConfig {
+    #[serde(default = "default_plugin_heartbeat_interval")]
+    interval: u64,
+
+    /// A Service without answer for this time is killed and restarted by its Restart Policy
+    #[serde(default = "default_plugin_heartbeat_timeout")]
+    timeout: u64,
+}
+
+impl PluginHeartbeatConfig {
+    pub fn get_interval(&self) -> Duration {
+        Duration::from_secs(self.interval.max(1))
+    }
+
+    pub fn get_timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout.max(1))
+    }
+}
+
+impl Default for PluginHeartbeatConfig {
+    fn default() -> Self {
+        PluginHeartbeatConfig {
+            interval: default_plugin_heartbeat_interval(),
+            timeout: default_plugin_heartbeat_timeout(),
+        }
+    }
+}
+
+/// Network wide maintenance, Players without the bypass permission are denied at the Proxy
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MaintenanceConfig {
+    /// State after the start if no other Node of the Cluster is running, can be switched at runtime
+    #[serde(default)]
+    enabled: bool,
+
+    /// Shown by the Proxies in the server list while the maintenance is enabled
+    #[serde(default = "default_maintenance_motd")]
+    motd: String,
+
+    #[serde(default = "default_maintenance_kick_message")]
+    kick_message: String,
+
+    #[serde(default = "default_maintenance_bypass_permission")]
+    bypass_permission: String,
+}
+
+impl MaintenanceConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn get_motd(&self) -> &str {
+        &self.motd
+    }
+
+    pub fn get_kick_message(&self) -> &str {
+        &self.kick_message
+    }
+
+    pub fn get_bypass_permission(&self) -> &str {
+        &self.bypass_permission
+    }
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        MaintenanceConfig {
+            enabled: false,
+            motd: default_maintenance_motd(),
+            kick_message: default_maintenance_kick_message(),
+            bypass_permission: default_maintenance_bypass_permission(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct CloudConfigPath {
     task_folder: String,
@@ -318,6 +559,42 @@ impl CloudConfigSystem {
         Utils::get_path(&self.get_software_lib_folder())
     }
 }
+fn default_scheduler_interval() -> u64 {
+    10
+}
+
+fn default_resource_monitor_interval() -> u64 {
+    5
+}
+
+fn default_heartbeat_interval() -> u64 {
+    5
+}
+
+fn default_node_timeout() -> u64 {
+    20
+}
+
+fn default_plugin_heartbeat_interval() -> u64 {
+    10
+}
+
+fn default_plugin_heartbeat_timeout() -> u64 {
+    60
+}
+
+fn default_maintenance_motd() -> String {
+    String::from("&cDas Netzwerk befindet sich in Wartungsarbeiten")
+}
+
+fn default_maintenance_kick_message() -> String {
+    String::from("Das Netzwerk befindet sich in Wartungsarbeiten\nBitte versuche es später erneut")
+}
+
+fn default_maintenance_bypass_permission() -> String {
+    String::from("cloud.maintenance.bypass")
+}
+
 fn get_default_file() -> String {
     let json_str = r#"
     {
@@ -326,6 +603,9 @@ fn get_default_file() -> String {
       "language": "de",
       "server_host": "127.0.0.1",
       "max_ram": 2028,
+      "scheduler_interval": 10,
+      "resource_monitor_interval": 5,
+      "placement_policy": "least_loaded",
       "database": {
         "typ": "SQLITE",
         "sqlite": {
@@ -349,6 +629,24 @@ fn get_default_file() -> String {
         "ip": "127.0.0.1",
         "port": 5051
       },
+      "rest_api_cors_origins": [],
+      "whitelist": false,
+      "maintenance": {
+        "enabled": false,
+        "motd": "&cDas Netzwerk befindet sich in Wartungsarbeiten",
+        "kick_message": "Das Netzwerk befindet sich in Wartungsarbeiten\nBitte versuche es später erneut",
+        "bypass_permission": "cloud.maintenance.bypass"
+      },
+      "cluster": {
+        "seeds": [],
+        "secret": "",
+        "heartbeat_interval": 5,
+        "node_timeout": 20
+      },
+      "plugin_heartbeat": {
+        "interval": 10,
+        "timeout": 60
+      },
       "path": {
         "task_folder": "~task",
         "template_folder": "~template",
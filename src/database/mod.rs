@@ -1,7 +1,9 @@
-use database_manager::types::{DBVarChar, Value};
+use database_manager::types::{DBVarChar, DbResult, Value};
+use std::time::Instant;
 use uuid::Uuid;
 
 use crate::database::table::UUID_LENGTH;
+use crate::utils::metrics::METRICS;
 
 pub mod table;
 
@@ -14,4 +16,40 @@ impl DBTools {
     pub fn uuid_to_varchar(uuid: &Uuid) -> DBVarChar {
         DBVarChar::new(uuid.to_string(), UUID_LENGTH).unwrap()
     }
+
+    /// Run a query and record its duration under `query` in `minecloud_db_latency_seconds`
+    pub async fn timed<T>(query: &str, future: impl Future<Output = DbResult<T>>) -> DbResult<T> {
+        let started = Instant::now();
+        let result = future.await;
+        METRICS.observe_db_latency(query, started.elapsed());
+        result
+    }
+}
+
+/// A fresh sqlite Database with all tables, one file per test
+#[cfg(all(test, feature = "sqlite"))]
+pub async fn test_database() -> std::sync::Arc<database_manager::DatabaseManager> {
+    // the logger reads the config.json of a Node, tests have none
+    crate::utils::log::logger::Logger::init_log_level(0);
+
+    let file = std::env::temp_dir().join(format!("minecloud-test-{}.db", Uuid::new_v4()));
+    let config: database_manager::config::DatabaseConfig =
+        serde_json::from_value(serde_json::json!({
+            "typ": "SQLITE",
+            "sqlite": { "file": file.to_string_lossy() },
+            "mysql": {
+                "host": { "ip": "127.0.0.1", "port": 3306 },
+                "username": "minecloud",
+                "password": "minecloud",
+                "database": "minecloud"
+            }
+        }))
+        .expect("invalid test database config");
+
+    let mut db = database_manager::DatabaseManager::new(config).expect("no test database");
+    db.connect().await.expect("can't open test database");
+    table::Tables::check_tables(&db)
+        .await
+        .expect("can't create tables");
+    std::sync::Arc::new(db)
 }
@@ -1,16 +1,25 @@
 use crate::utils::error::CloudResult;
 use database_manager::{DatabaseController, Table};
 
+pub use table_api_audit_log::*;
+pub use table_api_tokens::*;
 pub use table_player_events::*;
+pub use table_player_punishments::*;
 pub use table_player_sessions::*;
 pub use table_players::*;
+pub use table_service_events::*;
 pub use table_services::TableServices;
+pub use table_whitelist::*;
 
+mod table_api_audit_log;
+mod table_api_tokens;
 mod table_player_events;
+mod table_player_punishments;
 mod table_player_sessions;
 mod table_players;
-//mod table_service_events;
+mod table_service_events;
 mod table_services;
+mod table_whitelist;
 
 pub struct Tables;
 impl Tables {
@@ -19,6 +28,11 @@ impl Tables {
         TablePlayerSessions::sync(manager).await?;
         TablePlayerEvents::sync(manager).await?;
         TableServices::sync(manager).await?;
+        TableServiceEvents::sync(manager).await?;
+        TableApiTokens::sync(manager).await?;
+        TableApiAuditLog::sync(manager).await?;
+        TablePlayerPunishments::sync(manager).await?;
+        TableWhitelist::sync(manager).await?;
 
         Ok(())
     }
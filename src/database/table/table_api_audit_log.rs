@@ -0,0 +1,71 @@
+use database_manager::types::{DBDatetime, DBText, DBUInt, DbResult};
+use database_manager::{DatabaseController, Table, TableDerive};
+
+use crate::database::DBTools;
+use crate::types::ApiToken;
+
+#[derive(TableDerive, Debug, Clone)]
+#[table_name("t_api_audit_log")]
+pub struct TableApiAuditLog {
+    #[primary_key]
+    #[auto_increment]
+    id: DBUInt, // audit entry ID
+    created_at: DBDatetime, // format -> YYYY-MM-DD HH:MM:SS
+
+    token_id: DBUInt,
+    token_name: DBText,
+    method: DBText,
+    path: DBText,
+    status: DBUInt,
+}
+
+impl TableApiAuditLog {
+    pub fn new(token: &ApiToken, method: &str, path: &str, status: u16) -> Self {
+        TableApiAuditLog {
+            id: Default::default(),
+            created_at: DBDatetime::get_now(),
+            token_id: DBUInt::from(token.get_id()),
+            token_name: DBText::from(token.get_name()),
+            method: DBText::from(method),
+            path: DBText::from(path),
+            status: DBUInt::from(status as u64),
+        }
+    }
+
+    pub async fn create<M: DatabaseController>(&self, db: &M) -> DbResult<()> {
+        DBTools::timed("t_api_audit_log.create", self.insert(db)).await?;
+        Ok(())
+    }
+}
+
+/// The audit log is only written by the Node, the tests read it back
+#[cfg(all(test, feature = "sqlite"))]
+impl TableApiAuditLog {
+    pub async fn find_all<M: DatabaseController>(db: &M) -> DbResult<Vec<Self>> {
+        let rows = db
+            .query(
+                Self::table_name(),
+                &database_manager::types::QueryFilters::new(),
+            )
+            .await?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.push(Self::from_row(&row)?);
+        }
+        Ok(entries)
+    }
+
+    pub fn get_token_name(&self) -> &str {
+        &self.token_name.0
+    }
+    pub fn get_method(&self) -> &str {
+        &self.method.0
+    }
+    pub fn get_path(&self) -> &str {
+        &self.path.0
+    }
+    pub fn get_status(&self) -> u64 {
+        self.status.0
+    }
+}
@@ -0,0 +1,165 @@
+use database_manager::types::*;
+use database_manager::{DatabaseController, Table, TableDerive};
+
+use crate::database::DBTools;
+use crate::types::{ApiScope, ApiToken};
+use crate::utils::utils::Utils;
+
+/// sha256 as hex
+pub const TOKEN_HASH_LENGTH: usize = 64;
+
+#[derive(TableDerive, Debug, Clone)]
+#[table_name("t_api_tokens")]
+pub struct TableApiTokens {
+    #[primary_key]
+    #[auto_increment]
+    id: DBUInt, // token ID
+    created_at: DBDatetime, // format -> YYYY-MM-DD HH:MM:SS
+
+    name: DBText,
+    token_hash: DBVarChar,
+    scopes: DBText, // comma separated
+
+    #[nullable]
+    last_used: Option<DBDatetime>, // format -> YYYY-MM-DD HH:MM:SS
+
+    #[nullable]
+    revoked_at: Option<DBDatetime>, // format -> YYYY-MM-DD HH:MM:SS
+}
+
+impl TableApiTokens {
+    pub fn new(name: &str, token_hash: &str, scopes: &[ApiScope]) -> DbResult<Self> {
+        Ok(Self {
+            id: DBUInt::default(),
+            created_at: DBDatetime::get_now(),
+            name: DBText::from(name),
+            token_hash: DBVarChar::new(token_hash.to_string(), TOKEN_HASH_LENGTH)?,
+            scopes: DBText::from(ApiScope::join(scopes)),
+            last_used: None,
+            revoked_at: None,
+        })
+    }
+
+    pub async fn create<M: DatabaseController>(&self, manager: &M) -> DbResult<()> {
+        DBTools::timed("t_api_tokens.create", self.insert(manager)).await?;
+        Ok(())
+    }
+
+    /// only not revoked Tokens
+    pub async fn find_by_hash<M: DatabaseController>(
+        manager: &M,
+        token_hash: &str,
+    ) -> DbResult<Option<Self>> {
+        let row = DBTools::timed(
+            "t_api_tokens.find_by_hash",
+            manager.query_one(
+                Self::table_name(),
+                &QueryFilters::new().add(Filter::eq(
+                    "token_hash",
+                    Value::VarChar(DBVarChar::new(token_hash.to_string(), TOKEN_HASH_LENGTH)?),
+                )),
+            ),
+        )
+        .await?;
+
+        match row {
+            Some(row) => {
+                let token = Self::from_row(&row)?;
+                Ok(if token.is_revoked() {
+                    None
+                } else {
+                    Some(token)
+                })
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// only not revoked Tokens
+    pub async fn find_by_name<M: DatabaseController>(
+        manager: &M,
+        name: &str,
+    ) -> DbResult<Option<Self>> {
+        let rows = DBTools::timed(
+            "t_api_tokens.find_by_name",
+            manager.query(
+                Self::table_name(),
+                &QueryFilters::new().add(Filter::eq("name", Value::from(DBText::from(name)))),
+            ),
+        )
+        .await?;
+
+        for row in rows {
+            let token = Self::from_row(&row)?;
+            if !token.is_revoked() {
+                return Ok(Some(token));
+            }
+        }
+        Ok(None)
+    }
+
+    pub async fn find_all_active<M: DatabaseController>(manager: &M) -> DbResult<Vec<Self>> {
+        let rows = DBTools::timed(
+            "t_api_tokens.find_all_active",
+            manager.query(Self::table_name(), &QueryFilters::new()),
+        )
+        .await?;
+
+        let mut tokens = Vec::new();
+        for row in rows {
+            let token = Self::from_row(&row)?;
+            if !token.is_revoked() {
+                tokens.push(token);
+            }
+        }
+        Ok(tokens)
+    }
+
+    pub async fn update_last_used<M: DatabaseController>(manager: &M, id: u64) -> DbResult<usize> {
+        DBTools::timed(
+            "t_api_tokens.update_last_used",
+            manager.update(
+                Self::table_name(),
+                &QueryFilters::new().add(Filter::eq("id", Value::from(id))),
+                &Row::from([(
+                    "last_used".into(),
+                    Value::DateTime(Utils::get_datetime_now().into()),
+                )]),
+            ),
+        )
+        .await
+    }
+
+    pub async fn revoke<M: DatabaseController>(manager: &M, id: u64) -> DbResult<usize> {
+        DBTools::timed(
+            "t_api_tokens.revoke",
+            manager.update(
+                Self::table_name(),
+                &QueryFilters::new().add(Filter::eq("id", Value::from(id))),
+                &Row::from([(
+                    "revoked_at".into(),
+                    Value::DateTime(Utils::get_datetime_now().into()),
+                )]),
+            ),
+        )
+        .await
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+
+    pub fn get_id(&self) -> DBUInt {
+        self.id.clone()
+    }
+}
+
+impl From<TableApiTokens> for ApiToken {
+    fn from(table: TableApiTokens) -> ApiToken {
+        ApiToken::new(
+            table.id.0,
+            table.name.0,
+            ApiScope::parse_list(&table.scopes.0),
+        )
+    }
+}
@@ -1,8 +1,12 @@
-use database_manager::types::{DBDatetime, DBText, DBUInt, DBVarChar, DbResult};
+use chrono::Utc;
+use database_manager::types::{
+    DBDatetime, DBText, DBUInt, DBVarChar, DbResult, Filter, QueryFilters, Value,
+};
 use database_manager::{DatabaseController, Table, TableDerive};
+use uuid::Uuid;
 
 use crate::database::DBTools;
-use crate::types::{Player, ServiceProcessRef};
+use crate::types::{Player, PlayerAction, PlayerEventRecord, ServiceProcessRef};
 
 #[derive(TableDerive, Debug, Clone)]
 #[table_name("t_player_events")]
@@ -19,6 +23,13 @@ pub struct TablePlayerEvents {
     session_id: Option<DBUInt>,
 
     service_uuid: DBVarChar,
+
+    // empty for events recorded before the analytics, they are skipped
+    #[nullable]
+    task_name: Option<DBText>,
+
+    #[nullable]
+    timestamp: Option<DBUInt>, // unix seconds
 }
 
 impl TablePlayerEvents {
@@ -38,6 +49,7 @@ impl TablePlayerEvents {
                 .clone()
                 .map(|s| DBUInt::from(s.get_id()))
         });
+        let task_name = service.read().await.get_task_name().to_string();
 
         TablePlayerEvents {
             id: Default::default(),
@@ -46,11 +58,171 @@ impl TablePlayerEvents {
             service_uuid: DBTools::uuid_to_varchar(&service.get_id().await),
             event_type: DBText::from(event_type),
             session_id,
+            task_name: Some(DBText::from(task_name)),
+            timestamp: Some(DBUInt::from(Utc::now().timestamp().max(0) as u64)),
+        }
+    }
+
+    /// Leave on a Service which no longer exists, the Task is taken from the Join of the session
+    pub fn new_orphaned_leave(
+        player_id: u64,
+        session_id: u64,
+        service_uuid: &Uuid,
+        task_name: Option<String>,
+    ) -> Self {
+        TablePlayerEvents {
+            id: Default::default(),
+            created_at: DBDatetime::get_now(),
+            player_id: DBUInt::from(player_id),
+            service_uuid: DBTools::uuid_to_varchar(service_uuid),
+            event_type: DBText::from(PlayerAction::Leave.to_string()),
+            session_id: Some(DBUInt::from(session_id)),
+            task_name: task_name.map(DBText::from),
+            timestamp: Some(DBUInt::from(Utc::now().timestamp().max(0) as u64)),
         }
     }
 
     pub async fn create<M: DatabaseController>(&self, db: &M) -> DbResult<()> {
-        self.insert(db).await?;
+        DBTools::timed("t_player_events.create", self.insert(db)).await?;
         Ok(())
     }
+
+    pub async fn find_by_player_id<M: DatabaseController>(
+        db: &M,
+        player_id: u64,
+    ) -> DbResult<Vec<PlayerEventRecord>> {
+        let f = QueryFilters::new().add(Filter::eq("player_id", Value::from(player_id)));
+        Self::query_records(db, &f).await
+    }
+
+    pub async fn find_by_session_id<M: DatabaseController>(
+        db: &M,
+        session_id: u64,
+    ) -> DbResult<Vec<PlayerEventRecord>> {
+        let f = QueryFilters::new().add(Filter::eq("session_id", Value::from(session_id)));
+        Self::query_records(db, &f).await
+    }
+
+    /// Events from `since` (unix seconds) on, older rows are not read
+    pub async fn find_since<M: DatabaseController>(
+        db: &M,
+        since: i64,
+    ) -> DbResult<Vec<PlayerEventRecord>> {
+        let f = QueryFilters::new().add(Filter::gte("timestamp", Value::from(since.max(0) as u64)));
+        Self::query_records(db, &f).await
+    }
+
+    async fn query_records<M: DatabaseController>(
+        db: &M,
+        filters: &QueryFilters,
+    ) -> DbResult<Vec<PlayerEventRecord>> {
+        let rows = DBTools::timed(
+            "t_player_events.query_records",
+            db.query(Self::table_name(), filters),
+        )
+        .await?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            if let Some(record) = Self::from_row(&row)?.into_record() {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+
+    fn into_record(self) -> Option<PlayerEventRecord> {
+        Some(PlayerEventRecord::new(
+            self.id.0,
+            self.player_id.0,
+            self.session_id.map(|id| id.0),
+            self.task_name?.0,
+            self.event_type.0,
+            self.timestamp?.0 as i64,
+        ))
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::database::test_database;
+    use crate::types::{PlayerAnalytics, PlayerHistory};
+    use std::collections::BTreeSet;
+
+    const SERVICE: Uuid = Uuid::from_u128(1);
+
+    async fn record(
+        db: &impl DatabaseController,
+        player_id: u64,
+        session_id: u64,
+        task: &str,
+        action: PlayerAction,
+        timestamp: u64,
+    ) {
+        TablePlayerEvents {
+            id: Default::default(),
+            created_at: DBDatetime::get_now(),
+            player_id: DBUInt::from(player_id),
+            event_type: DBText::from(action.to_string()),
+            session_id: Some(DBUInt::from(session_id)),
+            service_uuid: DBTools::uuid_to_varchar(&SERVICE),
+            task_name: Some(DBText::from(task)),
+            timestamp: Some(DBUInt::from(timestamp)),
+        }
+        .create(db)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn rebuilds_sessions_and_playtime_of_a_player() {
+        let db = test_database().await;
+        record(db.as_ref(), 1, 10, "Lobby", PlayerAction::Join, 1_000).await;
+        record(db.as_ref(), 1, 10, "BedWars", PlayerAction::Join, 1_100).await;
+        record(db.as_ref(), 1, 10, "BedWars", PlayerAction::Leave, 1_600).await;
+        record(db.as_ref(), 1, 11, "Lobby", PlayerAction::Join, 2_000).await;
+        record(db.as_ref(), 2, 12, "Lobby", PlayerAction::Join, 1_000).await;
+
+        let events = TablePlayerEvents::find_by_player_id(db.as_ref(), 1)
+            .await
+            .unwrap();
+        let online = BTreeSet::from([11]);
+        let history = PlayerHistory::new(PlayerAnalytics::sessions(&events, &online, 2_300));
+
+        let sessions = history.get_sessions();
+        assert_eq!(sessions.len(), 2);
+        assert!(sessions[0].is_open());
+        assert!(!sessions[1].is_open());
+        assert_eq!(history.get_total_playtime(), 600 + 300);
+    }
+
+    #[tokio::test]
+    async fn session_lost_by_the_node_ends_with_its_last_event() {
+        let db = test_database().await;
+        record(db.as_ref(), 1, 20, "Lobby", PlayerAction::Join, 1_000).await;
+        record(db.as_ref(), 1, 20, "BedWars", PlayerAction::Join, 1_250).await;
+
+        let events = TablePlayerEvents::find_by_player_id(db.as_ref(), 1)
+            .await
+            .unwrap();
+        let history =
+            PlayerHistory::new(PlayerAnalytics::sessions(&events, &BTreeSet::new(), 5_000));
+
+        assert!(!history.get_sessions()[0].is_open());
+        assert_eq!(history.get_total_playtime(), 250);
+    }
+
+    #[tokio::test]
+    async fn find_since_skips_older_events() {
+        let db = test_database().await;
+        record(db.as_ref(), 1, 30, "Lobby", PlayerAction::Join, 1_000).await;
+        record(db.as_ref(), 1, 30, "Lobby", PlayerAction::Leave, 2_000).await;
+        record(db.as_ref(), 2, 31, "Lobby", PlayerAction::Join, 3_000).await;
+
+        let events = TablePlayerEvents::find_since(db.as_ref(), 2_000)
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 2);
+    }
 }
@@ -0,0 +1,139 @@
+use chrono::Utc;
+use database_manager::types::*;
+use database_manager::{DatabaseController, Table, TableDerive};
+use uuid::Uuid;
+
+use crate::database::DBTools;
+use crate::types::{Player, Punishment, PunishmentType};
+use crate::utils::utils::Utils;
+
+#[derive(TableDerive, Debug, Clone)]
+#[table_name("t_player_punishments")]
+pub struct TablePlayerPunishments {
+    #[primary_key]
+    #[auto_increment]
+    id: DBUInt, // punishment ID
+    created_at: DBDatetime, // format -> YYYY-MM-DD HH:MM:SS
+    timestamp: DBUInt,      // unix seconds
+
+    kind: DBText, // ban / mute
+    player_uuid: DBVarChar,
+    player_name: DBText,
+    reason: DBText,
+    issuer: DBText,
+
+    #[nullable]
+    expires_at: Option<DBUInt>, // unix seconds, NULL for permanent
+
+    #[nullable]
+    revoked_at: Option<DBDatetime>, // format -> YYYY-MM-DD HH:MM:SS
+}
+
+impl TablePlayerPunishments {
+    pub fn new(
+        kind: PunishmentType,
+        player: &Player,
+        reason: &str,
+        issuer: &str,
+        duration: Option<i64>,
+    ) -> Self {
+        let now = Utc::now().timestamp().max(0);
+        Self {
+            id: DBUInt::default(),
+            created_at: DBDatetime::get_now(),
+            timestamp: DBUInt::from(now as u64),
+            kind: DBText::from(kind.to_string()),
+            player_uuid: DBTools::uuid_to_varchar(&player.get_uuid()),
+            player_name: DBText::from(player.get_name()),
+            reason: DBText::from(reason),
+            issuer: DBText::from(issuer),
+            expires_at: duration.map(|d| DBUInt::from(now.saturating_add(d) as u64)),
+            revoked_at: None,
+        }
+    }
+
+    pub async fn create<M: DatabaseController>(&self, manager: &M) -> DbResult<()> {
+        DBTools::timed("t_player_punishments.create", self.insert(manager)).await?;
+        Ok(())
+    }
+
+    /// all punishments of the Player, also revoked and expired ones
+    pub async fn find_by_player<M: DatabaseController>(
+        manager: &M,
+        uuid: &Uuid,
+    ) -> DbResult<Vec<Self>> {
+        let rows = DBTools::timed(
+            "t_player_punishments.find_by_player",
+            manager.query(
+                Self::table_name(),
+                &QueryFilters::new().add(Filter::eq("player_uuid", DBTools::uuid_to_value(uuid))),
+            ),
+        )
+        .await?;
+
+        let mut punishments = Vec::new();
+        for row in rows {
+            punishments.push(Self::from_row(&row)?);
+        }
+        Ok(punishments)
+    }
+
+    /// not revoked punishments of this kind, expired ones included
+    pub async fn find_not_revoked<M: DatabaseController>(
+        manager: &M,
+        kind: PunishmentType,
+    ) -> DbResult<Vec<Self>> {
+        let rows = DBTools::timed(
+            "t_player_punishments.find_not_revoked",
+            manager.query(
+                Self::table_name(),
+                &QueryFilters::new().add(Filter::eq("kind", Value::from(kind.to_string()))),
+            ),
+        )
+        .await?;
+
+        let mut punishments = Vec::new();
+        for row in rows {
+            let punishment = Self::from_row(&row)?;
+            if punishment.revoked_at.is_none() {
+                punishments.push(punishment);
+            }
+        }
+        Ok(punishments)
+    }
+
+    pub async fn revoke<M: DatabaseController>(manager: &M, id: u64) -> DbResult<usize> {
+        DBTools::timed(
+            "t_player_punishments.revoke",
+            manager.update(
+                Self::table_name(),
+                &QueryFilters::new().add(Filter::eq("id", Value::from(id))),
+                &Row::from([(
+                    "revoked_at".into(),
+                    Value::DateTime(Utils::get_datetime_now().into()),
+                )]),
+            ),
+        )
+        .await
+    }
+
+    pub fn get_id(&self) -> u64 {
+        self.id.0
+    }
+}
+
+impl From<TablePlayerPunishments> for Punishment {
+    fn from(table: TablePlayerPunishments) -> Punishment {
+        Punishment::new(
+            table.id.0,
+            PunishmentType::parse(&table.kind.0).unwrap_or(PunishmentType::Ban),
+            Uuid::parse_str(table.player_uuid.value().as_ref()).unwrap_or_default(),
+            table.player_name.0,
+            table.reason.0,
+            table.issuer.0,
+            table.timestamp.0 as i64,
+            table.expires_at.map(|e| e.0 as i64),
+            table.revoked_at.is_some(),
+        )
+    }
+}
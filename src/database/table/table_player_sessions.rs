@@ -49,7 +49,7 @@ impl TablePlayerSessions {
     }
 
     pub async fn create<M: DatabaseController>(&self, manager: &M) -> DbResult<()> {
-        self.insert(manager).await?;
+        DBTools::timed("t_player_sessions.create", self.insert(manager)).await?;
         Ok(())
     }
 
@@ -58,8 +58,9 @@ impl TablePlayerSessions {
         id: u64,
         service_uuid: &Uuid,
     ) -> DbResult<()> {
-        manager
-            .update(
+        DBTools::timed(
+            "t_player_sessions.update_by_player_id",
+            manager.update(
                 Self::table_name(),
                 &QueryFilters::new().add(Filter::eq("player_id", Value::from(id))),
                 &Row::from([
@@ -72,18 +73,21 @@ impl TablePlayerSessions {
                         DBTools::uuid_to_value(service_uuid),
                     ),
                 ]),
-            )
-            .await?;
+            ),
+        )
+        .await?;
         Ok(())
     }
 
     pub async fn delete_by_player_id<M: DatabaseController>(manager: &M, id: u64) -> DbResult<()> {
-        manager
-            .delete(
+        DBTools::timed(
+            "t_player_sessions.delete_by_player_id",
+            manager.delete(
                 Self::table_name(),
                 &QueryFilters::new().add(Filter::eq("player_id", Value::from(id))),
-            )
-            .await?;
+            ),
+        )
+        .await?;
         Ok(())
     }
 
@@ -94,9 +98,11 @@ impl TablePlayerSessions {
         let mut filter = QueryFilters::new();
         filter.add_filter(Filter::eq("player_id", Value::from(id)));
 
-        let row = db
-            .query_one(TablePlayerSessions::table_name(), &filter)
-            .await?;
+        let row = DBTools::timed(
+            "t_player_sessions.find_by_player_id",
+            db.query_one(TablePlayerSessions::table_name(), &filter),
+        )
+        .await?;
 
         if let Some(row) = row {
             Ok(Some(Self::from_row(&row)?))
@@ -105,6 +111,52 @@ impl TablePlayerSessions {
         }
     }
 
+    /// Open sessions whose Player was last seen on the Service
+    pub async fn find_by_service<M: DatabaseController>(
+        db: &M,
+        service_uuid: &Uuid,
+    ) -> DbResult<Vec<TablePlayerSessions>> {
+        let f = QueryFilters::new().add(Filter::eq(
+            "service_uuid",
+            DBTools::uuid_to_value(service_uuid),
+        ));
+        let rows = DBTools::timed(
+            "t_player_sessions.find_by_service",
+            db.query(TablePlayerSessions::table_name(), &f),
+        )
+        .await?;
+
+        let mut sessions = Vec::new();
+        for row in rows {
+            sessions.push(Self::from_row(&row)?);
+        }
+        Ok(sessions)
+    }
+
+    /// All open sessions of the Network
+    pub async fn find_all<M: DatabaseController>(db: &M) -> DbResult<Vec<TablePlayerSessions>> {
+        let rows = DBTools::timed(
+            "t_player_sessions.find_all",
+            db.query(TablePlayerSessions::table_name(), &QueryFilters::new()),
+        )
+        .await?;
+
+        let mut sessions = Vec::new();
+        for row in rows {
+            sessions.push(Self::from_row(&row)?);
+        }
+        Ok(sessions)
+    }
+
+    /// IDs of all open sessions
+    pub async fn find_all_ids<M: DatabaseController>(db: &M) -> DbResult<Vec<u64>> {
+        Ok(Self::find_all(db)
+            .await?
+            .iter()
+            .map(|s| s.get_id())
+            .collect())
+    }
+
     pub async fn count_players_from_task<M: DatabaseController>(
         db: &M,
         task_name: &String,
@@ -116,9 +168,11 @@ impl TablePlayerSessions {
             format!("{}.task", TableServices::table_name()),
             Value::from(task_name.to_string()),
         ));
-        let rows = db
-            .query_with_join(&table_name, vec![(TableServices::table_name(), z1, z2)], &f)
-            .await?;
+        let rows = DBTools::timed(
+            "t_player_sessions.count_players_from_task",
+            db.query_with_join(&table_name, vec![(TableServices::table_name(), z1, z2)], &f),
+        )
+        .await?;
 
         Ok(rows.len() as u64)
     }
@@ -126,6 +180,14 @@ impl TablePlayerSessions {
     pub fn get_id(&self) -> u64 {
         self.id.0
     }
+
+    pub fn get_player_id(&self) -> u64 {
+        self.player_id.0
+    }
+
+    pub fn get_service_uuid(&self) -> Uuid {
+        Uuid::parse_str(self.service_uuid.value().as_ref()).unwrap_or_default()
+    }
 }
 
 impl From<TablePlayerSessions> for PlayerSession {
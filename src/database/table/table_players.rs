@@ -41,41 +41,64 @@ impl TablePlayers {
     }
 
     pub async fn update_last_login<M: DatabaseController>(manager: &M, id: u64) -> DbResult<usize> {
-        manager
-            .update(
+        DBTools::timed(
+            "t_players.update_last_login",
+            manager.update(
                 Self::table_name(),
                 &QueryFilters::new().add(Filter::eq("id", Value::from(id))),
                 &Row::from([(
                     "last_login".into(),
                     Value::DateTime(Utils::get_datetime_now().into()),
                 )]),
-            )
-            .await
+            ),
+        )
+        .await
     }
 
     pub async fn update_last_seen<M: DatabaseController>(manager: &M, id: u64) -> DbResult<usize> {
-        manager
-            .update(
+        DBTools::timed(
+            "t_players.update_last_seen",
+            manager.update(
                 Self::table_name(),
                 &QueryFilters::new().add(Filter::eq("id", Value::UInt(id.into()))),
                 &Row::from([(
                     "last_seen".into(),
                     Value::DateTime(Utils::get_datetime_now().into()),
                 )]),
-            )
-            .await
+            ),
+        )
+        .await
+    }
+
+    pub async fn find_by_id<M: DatabaseController>(manager: &M, id: u64) -> DbResult<Option<Self>> {
+        let row = DBTools::timed(
+            "t_players.find_by_id",
+            manager.query_one(
+                Self::table_name(),
+                &QueryFilters::new().add(Filter::eq("id", Value::UInt(id.into()))),
+            ),
+        )
+        .await?;
+
+        if let Some(row) = row {
+            Ok(Some(Self::from_row(&row)?))
+        } else {
+            Ok(None)
+        }
     }
 
     pub async fn find_by_uuid<M: DatabaseController>(
         manager: &M,
         uuid: &Uuid,
     ) -> DbResult<Option<Self>> {
-        let row = manager
-            .query_one(
+        let row = DBTools::timed(
+            "t_players.find_by_uuid",
+            manager.query_one(
                 Self::table_name(),
                 &QueryFilters::new().add(Filter::eq("uuid", DBTools::uuid_to_value(uuid))),
-            )
-            .await?;
+            ),
+        )
+        .await?;
 
         if let Some(row) = row {
             Ok(Some(Self::from_row(&row)?))
@@ -88,12 +111,14 @@ impl TablePlayers {
         manager: &M,
         name: &str,
     ) -> DbResult<Option<Self>> {
-        let row = manager
-            .query_one(
+        let row = DBTools::timed(
+            "t_players.find_by_name",
+            manager.query_one(
                 Self::table_name(),
                 &QueryFilters::new().add(Filter::eq("name", Value::from(DBText(name.to_string())))),
-            )
-            .await?;
+            ),
+        )
+        .await?;
 
         if let Some(row) = row {
             Ok(Some(Self::from_row(&row)?))
@@ -103,7 +128,7 @@ impl TablePlayers {
     }
 
     pub async fn create<M: DatabaseController>(&self, manager: &M) -> DbResult<()> {
-        self.insert(manager).await?;
+        DBTools::timed("t_players.create", self.insert(manager)).await?;
         Ok(())
     }
 
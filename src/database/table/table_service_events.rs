@@ -1,104 +1,199 @@
-use serde::{Deserialize, Serialize};
-
-use crate::database::manager::*;
-use crate::database::db_tools::DbTools;
-use crate::database::db_types::*;
-use crate::error;
-use crate::utils::error::cloud_error::CloudError;
-use crate::utils::error::error_kind::CloudErrorKind::*;
-
-
-const TABLE_SERVICE_EVENTS: &str = "t_service_events";
-
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+use chrono::Utc;
+use database_manager::types::{
+    DBDatetime, DBText, DBUInt, DBVarChar, DbResult, Filter, QueryFilters, Value,
+};
+use database_manager::{DatabaseController, Table, TableDerive};
+use uuid::Uuid;
+
+use crate::database::DBTools;
+use crate::types::{Service, ServiceEvent, ServiceEventFilter, ServiceEventType};
+
+#[derive(TableDerive, Debug, Clone)]
+#[table_name("t_service_events")]
 pub struct TableServiceEvents {
-    id: DbInteger,              // service event id
-    created_at: DbDateTime,     // format -> YYYY-MM-DD HH:MM:SS
-    
-    service_uuid: DbString,
-    event_type: DbString,
+    #[primary_key]
+    #[auto_increment]
+    id: DBUInt, // service event ID
+    created_at: DBDatetime, // format -> YYYY-MM-DD HH:MM:SS
+    timestamp: DBUInt,      // unix seconds, used for the time range
+
+    service_uuid: DBVarChar,
+    service_name: DBText,
+    task: DBText,
+    node: DBText,
+    event_type: DBText,
+
+    #[nullable]
+    message: Option<DBText>,
 }
 
 impl TableServiceEvents {
-    pub fn new() -> Self {
-        Self::default()
+    pub fn new(service: &Service, event_type: ServiceEventType, message: Option<String>) -> Self {
+        TableServiceEvents {
+            id: Default::default(),
+            created_at: DBDatetime::get_now(),
+            timestamp: DBUInt::from(Utc::now().timestamp().max(0) as u64),
+            service_uuid: DBTools::uuid_to_varchar(service.get_id()),
+            service_name: DBText::from(service.get_name()),
+            task: DBText::from(service.get_task_name()),
+            node: DBText::from(service.get_parent_node()),
+            event_type: DBText::from(event_type.to_string()),
+            message: message.map(DBText::from),
+        }
     }
 
-    pub async fn add(&self, db: DatabaseManager) -> Result<(), CloudError> {
-        db.add_record(TABLE_SERVICE_EVENTS, DbTools::struct_to_db_map(self)?)
-            .await
-            .map_err(|e| error!(CantCreateDBRecord, e))?;
+    pub async fn create<M: DatabaseController>(&self, db: &M) -> DbResult<()> {
+        DBTools::timed("t_service_events.create", self.insert(db)).await?;
         Ok(())
     }
 
-    pub fn get_schema() -> Result<Record, CloudError> {
-        DbTools::get_schema::<Self>()
+    /// newest first, events of an unknown type are skipped
+    pub async fn find<M: DatabaseController>(
+        db: &M,
+        filter: &ServiceEventFilter,
+    ) -> DbResult<Vec<ServiceEvent>> {
+        let mut f = QueryFilters::new();
+        if let Some(service) = filter.get_service() {
+            f.add_filter(Filter::eq("service_uuid", DBTools::uuid_to_value(service)));
+        }
+        if let Some(task) = filter.get_task() {
+            f.add_filter(Filter::eq("task", Value::from(task.to_string())));
+        }
+        if let Some(from) = filter.get_from() {
+            f.add_filter(Filter::gte("timestamp", Value::from(from.max(0) as u64)));
+        }
+
+        let rows =
+            DBTools::timed("t_service_events.find", db.query(Self::table_name(), &f)).await?;
+
+        let mut found = Vec::new();
+        for row in rows {
+            let event = Self::from_row(&row)?;
+            if filter.get_to().is_none_or(|to| event.get_timestamp() <= to) {
+                found.push(event);
+            }
+        }
+        found.sort_by(|a, b| b.timestamp.0.cmp(&a.timestamp.0).then(b.id.0.cmp(&a.id.0)));
+
+        let mut events: Vec<ServiceEvent> =
+            found.into_iter().filter_map(Self::into_event).collect();
+        if let Some(limit) = filter.get_limit() {
+            events.truncate(limit);
+        }
+        Ok(events)
     }
 
-    pub async fn check_table(_db: &DatabaseManager) -> Result<(), CloudError> {
-        Ok(())
-        /*db.check_table(TABLE_SERVICE_EVENTS, &Self::get_schema()?)
-            .await
-            .map_err(|e| Error!(CantCreateTable, e))?;
-        Ok(())*/
+    pub fn get_timestamp(&self) -> i64 {
+        self.timestamp.0 as i64
     }
 
-    pub fn from_record(record: &Record) -> Result<Self, CloudError> {
-        let json = DbTools::record_to_json(record);
-        serde_json::from_value(json)
-            .map_err(|e| error!(DeserializationError, e))
+    /// `None` for an event type this version doesn't know, e.g. written by a newer Node
+    fn into_event(self) -> Option<ServiceEvent> {
+        let timestamp = self.get_timestamp();
+        Some(ServiceEvent::new(
+            self.id.0,
+            Uuid::parse_str(self.service_uuid.value().as_ref()).unwrap_or_default(),
+            self.service_name.0,
+            self.task.0,
+            self.node.0,
+            ServiceEventType::parse(&self.event_type.0)?,
+            self.message.map(|m| m.0),
+            timestamp,
+        ))
     }
+}
 
-    pub fn from_records(records: Vec<Record>) -> Result<Vec<Self>, CloudError> {
-        records.iter().map(Self::from_record).collect()
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::database::test_database;
+
+    const LOBBY_1: Uuid = Uuid::from_u128(1);
+    const LOBBY_2: Uuid = Uuid::from_u128(2);
+    const BEDWARS_1: Uuid = Uuid::from_u128(3);
+
+    async fn record(
+        db: &impl DatabaseController,
+        service: &Uuid,
+        task: &str,
+        event_type: &str,
+        timestamp: u64,
+    ) {
+        TableServiceEvents {
+            id: Default::default(),
+            created_at: DBDatetime::get_now(),
+            timestamp: DBUInt::from(timestamp),
+            service_uuid: DBTools::uuid_to_varchar(service),
+            service_name: DBText::from(format!("{}-{}", task, service.as_u128())),
+            task: DBText::from(task),
+            node: DBText::from("Node-1"),
+            event_type: DBText::from(event_type),
+            message: None,
+        }
+        .create(db)
+        .await
+        .unwrap();
     }
 
-    // Getter
-    pub fn get_service_uuid(&self) -> DbString {
-        self.service_uuid.clone()
+    async fn fill(db: &impl DatabaseController) {
+        record(db, &LOBBY_1, "Lobby", "starting", 1_000).await;
+        record(db, &LOBBY_1, "Lobby", "online", 1_010).await;
+        record(db, &LOBBY_2, "Lobby", "starting", 1_500).await;
+        record(db, &BEDWARS_1, "BedWars", "starting", 2_000).await;
+        record(db, &LOBBY_1, "Lobby", "crashed", 3_000).await;
     }
 
-    pub fn get_event_type(&self) -> DbString {
-        self.event_type.clone()
-    }
+    #[tokio::test]
+    async fn find_returns_newest_first_and_applies_the_limit() {
+        let db = test_database().await;
+        fill(db.as_ref()).await;
 
-    // Setter
-    pub fn set_service_uuid(&mut self, uuid: DbString) {
-        self.service_uuid = uuid;
-    }
+        let all = TableServiceEvents::find(db.as_ref(), &ServiceEventFilter::new())
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 5);
+        assert_eq!(all[0].get_event_type(), ServiceEventType::Crashed);
+        assert_eq!(all[4].get_event_type(), ServiceEventType::Starting);
 
-    pub fn set_event_type(&mut self, event_type: DbString) {
-        self.event_type = event_type;
+        let limited = TableServiceEvents::find(db.as_ref(), &ServiceEventFilter::new().limit(2))
+            .await
+            .unwrap();
+        assert_eq!(limited.len(), 2);
+        assert_eq!(limited[1].get_service_uuid(), &BEDWARS_1);
     }
 
-    // Query Methoden
-    pub async fn get_by_service_id(
-        db: DatabaseManager,
-        service_id: i64,
-    ) -> Result<Vec<Self>, CloudError> {
-        let mut filter = Record::new();
-        filter.insert("service_id".to_string(), DbValue::Integer(service_id));
+    #[tokio::test]
+    async fn find_filters_by_service_task_and_time() {
+        let db = test_database().await;
+        fill(db.as_ref()).await;
 
-        let records = db
-            .get_records(TABLE_SERVICE_EVENTS, Some(filter))
+        let of_service = ServiceEventFilter::new().service(LOBBY_1);
+        let events = TableServiceEvents::find(db.as_ref(), &of_service)
             .await
-            .map_err(|e| error!(CantDBGetRecords, e))?;
-
-        Self::from_records(records)
+            .unwrap();
+        assert_eq!(events.len(), 3);
+
+        let of_task: ServiceEventFilter = serde_json::from_value(
+            serde_json::json!({"task": "Lobby", "from": 1_010, "to": 2_000}),
+        )
+        .unwrap();
+        let events = TableServiceEvents::find(db.as_ref(), &of_task)
+            .await
+            .unwrap();
+        let uuids: Vec<&Uuid> = events.iter().map(|e| e.get_service_uuid()).collect();
+        assert_eq!(uuids, vec![&LOBBY_2, &LOBBY_1]);
     }
 
-    pub async fn get_by_event_type(
-        db: DatabaseManager,
-        event_type: String,
-    ) -> Result<Vec<Self>, CloudError> {
-        let mut filter = Record::new();
-        filter.insert("event_type".to_string(), DbValue::String(event_type));
+    #[tokio::test]
+    async fn find_skips_unknown_event_types() {
+        let db = test_database().await;
+        record(db.as_ref(), &LOBBY_1, "Lobby", "starting", 1_000).await;
+        record(db.as_ref(), &LOBBY_1, "Lobby", "migrated", 1_100).await;
 
-        let records = db
-            .get_records(TABLE_SERVICE_EVENTS, Some(filter))
+        let events = TableServiceEvents::find(db.as_ref(), &ServiceEventFilter::new())
             .await
-            .map_err(|e| error!(CantDBGetRecords, e))?;
-
-        Self::from_records(records)
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].get_event_type(), ServiceEventType::Starting);
     }
-}
\ No newline at end of file
+}
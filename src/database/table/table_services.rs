@@ -71,11 +71,20 @@ impl TableServices {
 
         let f =
             QueryFilters::new().add(Filter::eq("uuid", DBTools::uuid_to_value(service.get_id())));
-        if db.count(Self::table_name(), &f).await? > 0 {
+        let count = DBTools::timed(
+            "t_services.create_if_not_exists",
+            db.count(Self::table_name(), &f),
+        )
+        .await?;
+        if count > 0 {
             Self::update(db, &service).await?;
         } else {
             let ts = Self::new_from_service(&service).await;
-            db.insert(Self::table_name(), &Self::to_row(&ts)).await?;
+            DBTools::timed(
+                "t_services.create_if_not_exists",
+                db.insert(Self::table_name(), &Self::to_row(&ts)),
+            )
+            .await?;
         }
         Ok(())
     }
@@ -83,8 +92,7 @@ impl TableServices {
     pub async fn update<M: DatabaseController>(db: &M, service: &Service) -> DbResult<()> {
         let f =
             QueryFilters::new().add(Filter::eq("uuid", DBTools::uuid_to_value(service.get_id())));
-        let row = db
-            .query_one(Self::table_name(), &f)
+        let row = DBTools::timed("t_services.update", db.query_one(Self::table_name(), &f))
             .await?
             .ok_or(DbError::NotFound(String::from("Service not found")))?;
 
@@ -94,14 +102,17 @@ impl TableServices {
         new_service.created_at = table_service.created_at;
 
         let f = QueryFilters::new().add(Filter::eq("id", Value::UInt(table_service.id)));
-        db.update(Self::table_name(), &f, &Self::to_row(&new_service))
-            .await?;
+        DBTools::timed(
+            "t_services.update",
+            db.update(Self::table_name(), &f, &Self::to_row(&new_service)),
+        )
+        .await?;
         Ok(())
     }
 
     pub async fn delete<M: DatabaseController>(db: &M, service_uuid: &Uuid) -> DbResult<()> {
         let f = QueryFilters::new().add(Filter::eq("uuid", DBTools::uuid_to_value(service_uuid)));
-        db.delete(Self::table_name(), &f).await?;
+        DBTools::timed("t_services.delete", db.delete(Self::table_name(), &f)).await?;
         Ok(())
     }
 
@@ -118,10 +129,36 @@ impl TableServices {
                 DBTools::uuid_to_value(&s.get_id().await),
             ));
         }
-        db.delete(Self::table_name(), &f).await?;
+        DBTools::timed(
+            "t_services.delete_others",
+            db.delete(Self::table_name(), &f),
+        )
+        .await?;
         Ok(())
     }
 
+    /// UUIDs of the Services the other Nodes have registered
+    pub async fn find_uuids_of_other_nodes<M: DatabaseController>(
+        db: &M,
+        node_name: &str,
+    ) -> DbResult<Vec<Uuid>> {
+        let f = QueryFilters::new().add(Filter::not_eq("node", Value::from(node_name.to_string())));
+        let rows = DBTools::timed(
+            "t_services.find_uuids_of_other_nodes",
+            db.query(Self::table_name(), &f),
+        )
+        .await?;
+
+        let mut uuids = Vec::new();
+        for row in rows {
+            let service = Self::from_row(&row)?;
+            if let Ok(uuid) = Uuid::parse_str(service.uuid.value().as_ref()) {
+                uuids.push(uuid);
+            }
+        }
+        Ok(uuids)
+    }
+
     pub async fn find_next_free_number<M: DatabaseController>(
         db: &M,
         task: &TaskRef,
@@ -133,7 +170,11 @@ impl TableServices {
 
         let filters = QueryFilters::new().add(Filter::eq("task", Value::from(name)));
 
-        let services = db.query(Self::table_name(), &filters).await?;
+        let services = DBTools::timed(
+            "t_services.find_next_free_number",
+            db.query(Self::table_name(), &filters),
+        )
+        .await?;
 
         let mut used_numbers = Vec::new();
 
@@ -0,0 +1,94 @@
+use chrono::Utc;
+use database_manager::types::*;
+use database_manager::{DatabaseController, Table, TableDerive};
+use uuid::Uuid;
+
+use crate::database::DBTools;
+use crate::types::{Player, WhitelistEntry};
+
+#[derive(TableDerive, Debug, Clone)]
+#[table_name("t_whitelist")]
+pub struct TableWhitelist {
+    #[primary_key]
+    #[auto_increment]
+    id: DBUInt, // whitelist entry ID
+    created_at: DBDatetime, // format -> YYYY-MM-DD HH:MM:SS
+    timestamp: DBUInt,      // unix seconds
+
+    player_uuid: DBVarChar,
+    player_name: DBText,
+    issuer: DBText,
+}
+
+impl TableWhitelist {
+    pub fn new(player: &Player, issuer: &str) -> Self {
+        Self {
+            id: DBUInt::default(),
+            created_at: DBDatetime::get_now(),
+            timestamp: DBUInt::from(Utc::now().timestamp().max(0) as u64),
+            player_uuid: DBTools::uuid_to_varchar(&player.get_uuid()),
+            player_name: DBText::from(player.get_name()),
+            issuer: DBText::from(issuer),
+        }
+    }
+
+    pub async fn create<M: DatabaseController>(&self, manager: &M) -> DbResult<()> {
+        DBTools::timed("t_whitelist.create", self.insert(manager)).await?;
+        Ok(())
+    }
+
+    pub async fn find_by_player<M: DatabaseController>(
+        manager: &M,
+        uuid: &Uuid,
+    ) -> DbResult<Option<Self>> {
+        match DBTools::timed(
+            "t_whitelist.find_by_player",
+            manager.query_one(
+                Self::table_name(),
+                &QueryFilters::new().add(Filter::eq("player_uuid", DBTools::uuid_to_value(uuid))),
+            ),
+        )
+        .await?
+        {
+            Some(row) => Ok(Some(Self::from_row(&row)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn find_all<M: DatabaseController>(manager: &M) -> DbResult<Vec<Self>> {
+        let rows = DBTools::timed(
+            "t_whitelist.find_all",
+            manager.query(Self::table_name(), &QueryFilters::new()),
+        )
+        .await?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.push(Self::from_row(&row)?);
+        }
+        Ok(entries)
+    }
+
+    pub async fn delete_by_player<M: DatabaseController>(manager: &M, uuid: &Uuid) -> DbResult<()> {
+        DBTools::timed(
+            "t_whitelist.delete_by_player",
+            manager.delete(
+                Self::table_name(),
+                &QueryFilters::new().add(Filter::eq("player_uuid", DBTools::uuid_to_value(uuid))),
+            ),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+impl From<TableWhitelist> for WhitelistEntry {
+    fn from(table: TableWhitelist) -> WhitelistEntry {
+        WhitelistEntry::new(
+            Uuid::parse_str(table.player_uuid.value().as_ref()).unwrap_or_default(),
+            table.player_name.0,
+            table.issuer.0,
+            table.timestamp.0 as i64,
+        )
+    }
+}
@@ -15,11 +15,16 @@ pub mod terminal {
     pub mod command_manager;
 
     pub mod command {
+        pub mod cmd_broadcast;
         pub mod cmd_help;
-        pub mod cmd_me;
+        pub mod cmd_maintenance;
+        pub mod cmd_player;
+        pub mod cmd_punishment;
         pub mod cmd_service;
+        pub mod cmd_token;
         //pub mod cmd_task;
         pub mod cmd_template;
+        pub mod cmd_whitelist;
     }
 }
 
@@ -0,0 +1,119 @@
+use database_manager::DatabaseManager;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::database::table::{TableApiAuditLog, TableApiTokens};
+use crate::types::{ApiScope, ApiToken};
+use crate::utils::error::*;
+use crate::{error, log_info, log_warning};
+
+const TOKEN_PREFIX: &str = "mc_";
+
+/// Tokens for the REST API, only the sha256 hash of a Token is stored
+pub struct ApiTokenManager {
+    db: Arc<DatabaseManager>,
+}
+
+impl ApiTokenManager {
+    pub fn new(db: Arc<DatabaseManager>) -> ApiTokenManager {
+        ApiTokenManager { db }
+    }
+
+    /// Returns the plain Token, it can not be shown again
+    pub async fn create_token(&self, name: &str, scopes: &[ApiScope]) -> CloudResult<String> {
+        if name.is_empty() || scopes.is_empty() {
+            return Err(error!(
+                InvalidRequest,
+                "Token needs a name and at least one scope"
+            ));
+        }
+        if TableApiTokens::find_by_name(self.db.as_ref(), name)
+            .await?
+            .is_some()
+        {
+            return Err(error!(
+                InvalidRequest,
+                format!("Token '{}' already exists", name)
+            ));
+        }
+
+        let token = format!(
+            "{}{}{}",
+            TOKEN_PREFIX,
+            Uuid::new_v4().simple(),
+            Uuid::new_v4().simple()
+        );
+        TableApiTokens::new(name, &Self::hash(&token), scopes)?
+            .create(self.db.as_ref())
+            .await?;
+
+        log_info!(
+            3,
+            "[REST API] Token '{}' created ({})",
+            name,
+            ApiScope::join(scopes)
+        );
+        Ok(token)
+    }
+
+    /// Returns `false` if no active Token has this name
+    pub async fn revoke_token(&self, name: &str) -> CloudResult<bool> {
+        match TableApiTokens::find_by_name(self.db.as_ref(), name).await? {
+            Some(token) => {
+                TableApiTokens::revoke(self.db.as_ref(), token.get_id().0).await?;
+                log_info!(3, "[REST API] Token '{}' revoked", name);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    pub async fn get_tokens(&self) -> CloudResult<Vec<ApiToken>> {
+        Ok(TableApiTokens::find_all_active(self.db.as_ref())
+            .await?
+            .into_iter()
+            .map(ApiToken::from)
+            .collect())
+    }
+
+    /// `None` for unknown or revoked Tokens
+    pub async fn authenticate(&self, token: &str) -> CloudResult<Option<ApiToken>> {
+        let table = match TableApiTokens::find_by_hash(self.db.as_ref(), &Self::hash(token)).await?
+        {
+            Some(table) => table,
+            None => return Ok(None),
+        };
+
+        if let Err(e) = TableApiTokens::update_last_used(self.db.as_ref(), table.get_id().0).await {
+            log_warning!(5, "[REST API] Cant update last use of Token: {}", e);
+        }
+        Ok(Some(ApiToken::from(table)))
+    }
+
+    /// Every changing request is written to the log and the audit table
+    pub async fn audit(&self, token: &ApiToken, method: &str, path: &str, status: u16) {
+        log_info!(
+            4,
+            "[REST API] [Audit] '{}' {} {} -> {}",
+            token.get_name(),
+            method,
+            path,
+            status
+        );
+
+        if let Err(e) = TableApiAuditLog::new(token, method, path, status)
+            .create(self.db.as_ref())
+            .await
+        {
+            log_warning!(3, "[REST API] Cant write audit log: {}", e);
+        }
+    }
+
+    fn hash(token: &str) -> String {
+        Sha256::digest(token.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+}
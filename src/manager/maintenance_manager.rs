@@ -0,0 +1,160 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use uuid::Uuid;
+
+use crate::api::cluster::ClusterClient;
+use crate::api::internal::{JoinDeniedResponse, JoinDenyReason, MaintenanceMessage};
+use crate::config::{CloudConfig, MaintenanceConfig};
+use crate::manager::{ServiceManagerRef, TaskManagerRef};
+use crate::utils::error::*;
+use crate::{log_info, log_warning};
+
+/// Maintenance of the whole Network and of single Tasks, pushed to the Proxies on every change
+pub struct MaintenanceManager {
+    config: MaintenanceConfig,
+    enabled: AtomicBool,
+    task_manager: TaskManagerRef,
+    service_manager: ServiceManagerRef,
+    cluster: Arc<dyn ClusterClient>,
+}
+
+impl MaintenanceManager {
+    pub fn new(
+        cloud_config: Arc<CloudConfig>,
+        task_manager: TaskManagerRef,
+        service_manager: ServiceManagerRef,
+        cluster: Arc<dyn ClusterClient>,
+    ) -> MaintenanceManager {
+        let config = cloud_config.get_maintenance().clone();
+        MaintenanceManager {
+            enabled: AtomicBool::new(config.is_enabled()),
+            config,
+            task_manager,
+            service_manager,
+            cluster,
+        }
+    }
+
+    /// `None` if the Player may join
+    pub fn check_join(
+        &self,
+        uuid: &Uuid,
+        name: &str,
+        permissions: &[String],
+    ) -> Option<JoinDeniedResponse> {
+        if !self.is_enabled()
+            || permissions
+                .iter()
+                .any(|p| p == self.config.get_bypass_permission())
+        {
+            return None;
+        }
+
+        log_info!(4, "Player |{}| can't join during maintenance", name);
+        Some(JoinDeniedResponse::new(
+            *uuid,
+            name,
+            JoinDenyReason::Maintenance,
+            self.config.get_kick_message(),
+        ))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Sent to all Nodes of the Cluster, a restarted Node takes it from the Cluster again.
+    /// Only when no other Node is running the default comes from `maintenance` in the config.
+    /// Returns the names of the local Proxies which did not get the new state
+    pub async fn set_enabled(&self, enabled: bool) -> CloudResult<Vec<String>> {
+        self.cluster.broadcast_maintenance(enabled).await;
+        self.apply(enabled).await
+    }
+
+    /// Remote (Node called) -> switched on another Node
+    pub async fn on_remote_maintenance(&self, enabled: bool) -> CloudResult<Vec<String>> {
+        self.apply(enabled).await
+    }
+
+    /// Take the state of the first Node which answers, used after joining the Cluster
+    pub async fn sync_from_cluster(&self) {
+        for node in self.cluster.get_nodes().await {
+            match self.cluster.get_maintenance(node.get_name()).await {
+                Ok(enabled) => {
+                    if enabled == self.is_enabled() {
+                        return;
+                    }
+                    if let Err(e) = self.apply(enabled).await {
+                        log_warning!(3, "Cant push maintenance to the Proxies: {}", e);
+                    }
+                    return;
+                }
+                Err(e) => log_warning!(
+                    3,
+                    "[Cluster] Cant get maintenance from Node {}: {}",
+                    node.get_name(),
+                    e
+                ),
+            }
+        }
+    }
+
+    async fn apply(&self, enabled: bool) -> CloudResult<Vec<String>> {
+        self.enabled.store(enabled, Ordering::Relaxed);
+        log_info!(
+            3,
+            "Maintenance {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+        self.push().await
+    }
+
+    /// Saved in the Task file, so it survives a restart.
+    /// Returns the names of the Proxies which did not get the new state
+    pub async fn set_task_maintenance(
+        &self,
+        task_name: &str,
+        enabled: bool,
+    ) -> CloudResult<Vec<String>> {
+        let task_ref = self.task_manager.get_task_ref_from_name(task_name).await?;
+        let task = {
+            let mut task = task_ref.write().await;
+            task.set_maintenance(enabled);
+            task.clone()
+        };
+        self.task_manager.read().await.save_task(&task)?;
+        log_info!(
+            3,
+            "Maintenance of Task [{}] {}",
+            task_name,
+            if enabled { "enabled" } else { "disabled" }
+        );
+        self.push().await
+    }
+
+    pub async fn get_state(&self) -> MaintenanceMessage {
+        let tasks = self
+            .task_manager
+            .read()
+            .await
+            .filter_tasks(|t| t.is_maintenance())
+            .await;
+        let mut task_names = Vec::new();
+        for task in tasks {
+            task_names.push(task.get_name().await);
+        }
+
+        MaintenanceMessage::new(
+            self.is_enabled(),
+            task_names,
+            self.config.get_motd(),
+            self.config.get_kick_message(),
+            self.config.get_bypass_permission(),
+        )
+    }
+
+    async fn push(&self) -> CloudResult<Vec<String>> {
+        let state = self.get_state().await;
+        self.service_manager.push_maintenance(&state).await
+    }
+}
@@ -1,16 +1,23 @@
 use database_manager::DatabaseManager;
 use std::sync::Arc;
 
+pub use api_token_manager::*;
 pub use group_manager::*;
+pub use maintenance_manager::*;
+pub use moderation_manager::*;
 pub use node_manager::*;
 pub use player_manager::*;
+pub use service_manager::*;
 pub use task_manager::*;
 
+use crate::api::cluster::{ClusterClient, RestClusterClient};
 use crate::config::{CloudConfig, SoftwareConfigRef};
-use crate::manager::service_manager::ServiceManagerRef;
 use crate::utils::error::CloudResult;
 
+mod api_token_manager;
 mod group_manager;
+mod maintenance_manager;
+mod moderation_manager;
 mod node_manager;
 mod player_manager;
 mod service_manager;
@@ -28,6 +35,8 @@ impl Manager {
         TaskManagerRef,
         Arc<NodeManager>,
         GroupManagerRef,
+        Arc<ModerationManager>,
+        Arc<MaintenanceManager>,
     )> {
         let group_manager = GroupManagerRef::new(db.clone(), cloud_config.clone());
         let task_manager = TaskManagerRef::new(
@@ -43,16 +52,50 @@ impl Manager {
             software_config.clone(),
         )
         .await?;
-        let player_manager = PlayerManagerRef::new(db.clone(), service_manager.clone(), task_manager.clone()).await;
+        let moderation_manager = Arc::new(ModerationManager::new(
+            db.clone(),
+            cloud_config.is_whitelist_enabled(),
+        ));
+        let cluster: Arc<dyn ClusterClient> =
+            Arc::new(RestClusterClient::new(cloud_config.clone()));
+        let maintenance_manager = Arc::new(MaintenanceManager::new(
+            cloud_config.clone(),
+            task_manager.clone(),
+            service_manager.clone(),
+            cluster.clone(),
+        ));
+        let player_manager = PlayerManagerRef::new(
+            db.clone(),
+            service_manager.clone(),
+            task_manager.clone(),
+            moderation_manager.clone(),
+            maintenance_manager.clone(),
+        )
+        .await;
 
-        let node_manager =
-            NodeManager::new(cloud_config.clone(), service_manager, task_manager.clone()).await?;
+        // the Services of this Node are stopped after a restart, their sessions are orphaned
+        player_manager
+            .read()
+            .await
+            .clear_orphaned_sessions()
+            .await?;
+
+        let node_manager = NodeManager::new(
+            cloud_config.clone(),
+            service_manager,
+            task_manager.clone(),
+            player_manager.clone(),
+            cluster,
+        )
+        .await?;
 
         Ok((
             player_manager,
             task_manager,
             Arc::new(node_manager),
             group_manager,
+            moderation_manager,
+            maintenance_manager,
         ))
     }
 }
@@ -0,0 +1,301 @@
+use chrono::Utc;
+use database_manager::DatabaseManager;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use uuid::Uuid;
+
+use crate::api::internal::{JoinDeniedResponse, JoinDenyReason};
+use crate::database::table::{TablePlayerPunishments, TableWhitelist};
+use crate::types::{
+    Player, Punishment, PunishmentType, WHITELIST_BYPASS_PERMISSION, WhitelistEntry,
+};
+use crate::utils::error::*;
+use crate::{error, log_info};
+
+const WHITELIST_KICK_MESSAGE: &str = "Du bist nicht auf der Whitelist des Netzwerks";
+
+/// Bans, mutes and the whitelist of the Network, checked when a Player joins a Proxy
+pub struct ModerationManager {
+    db: Arc<DatabaseManager>,
+    whitelist_enabled: AtomicBool,
+}
+
+impl ModerationManager {
+    pub fn new(db: Arc<DatabaseManager>, whitelist_enabled: bool) -> ModerationManager {
+        ModerationManager {
+            db,
+            whitelist_enabled: AtomicBool::new(whitelist_enabled),
+        }
+    }
+
+    /// `None` if the Player may join
+    pub async fn check_join(
+        &self,
+        uuid: &Uuid,
+        name: &str,
+        permissions: &[String],
+    ) -> CloudResult<Option<JoinDeniedResponse>> {
+        if let Some(ban) = self.get_active(PunishmentType::Ban, uuid).await? {
+            log_info!(4, "Player |{}| is banned: {}", name, ban.get_reason());
+            return Ok(Some(JoinDeniedResponse::new(
+                *uuid,
+                name,
+                JoinDenyReason::Banned,
+                &ban.get_kick_message(),
+            )));
+        }
+
+        if self.is_whitelist_enabled()
+            && !permissions.iter().any(|p| p == WHITELIST_BYPASS_PERMISSION)
+            && TableWhitelist::find_by_player(self.get_db(), uuid)
+                .await?
+                .is_none()
+        {
+            log_info!(4, "Player |{}| is not on the whitelist", name);
+            return Ok(Some(JoinDeniedResponse::new(
+                *uuid,
+                name,
+                JoinDenyReason::Whitelist,
+                WHITELIST_KICK_MESSAGE,
+            )));
+        }
+        Ok(None)
+    }
+
+    /// `duration` in seconds, `None` for a permanent punishment
+    pub async fn punish(
+        &self,
+        kind: PunishmentType,
+        player: &Player,
+        reason: &str,
+        issuer: &str,
+        duration: Option<i64>,
+    ) -> CloudResult<Punishment> {
+        if self.get_active(kind, &player.get_uuid()).await?.is_some() {
+            return Err(error!(
+                InvalidRequest,
+                format!(
+                    "Player {} already has an active {}",
+                    player.get_name(),
+                    kind
+                )
+            ));
+        }
+
+        let table = TablePlayerPunishments::new(kind, player, reason, issuer, duration);
+        table.create(self.get_db()).await?;
+        log_info!(
+            3,
+            "Player |{}| {} by {}: {}",
+            player.get_name(),
+            kind,
+            issuer,
+            reason
+        );
+
+        self.get_active(kind, &player.get_uuid())
+            .await?
+            .ok_or(error!(
+                CantDBGetRecords,
+                "punishment not found after insert"
+            ))
+    }
+
+    /// Returns `false` if the Player has no active punishment of this kind
+    pub async fn pardon(&self, kind: PunishmentType, player: &Player) -> CloudResult<bool> {
+        let now = Utc::now().timestamp();
+        let mut pardoned = false;
+        for table in
+            TablePlayerPunishments::find_by_player(self.get_db(), &player.get_uuid()).await?
+        {
+            let id = table.get_id();
+            let punishment = Punishment::from(table);
+            if punishment.get_kind() == kind && punishment.is_active(now) {
+                TablePlayerPunishments::revoke(self.get_db(), id).await?;
+                pardoned = true;
+            }
+        }
+
+        if pardoned {
+            log_info!(3, "Player |{}| {} revoked", player.get_name(), kind);
+        }
+        Ok(pardoned)
+    }
+
+    /// The active ban or mute of the Player, expired ones are ignored
+    pub async fn get_active(
+        &self,
+        kind: PunishmentType,
+        uuid: &Uuid,
+    ) -> CloudResult<Option<Punishment>> {
+        let now = Utc::now().timestamp();
+        Ok(TablePlayerPunishments::find_by_player(self.get_db(), uuid)
+            .await?
+            .into_iter()
+            .map(Punishment::from)
+            .find(|p| p.get_kind() == kind && p.is_active(now)))
+    }
+
+    /// All active punishments of this kind
+    pub async fn get_active_punishments(
+        &self,
+        kind: PunishmentType,
+    ) -> CloudResult<Vec<Punishment>> {
+        let now = Utc::now().timestamp();
+        Ok(
+            TablePlayerPunishments::find_not_revoked(self.get_db(), kind)
+                .await?
+                .into_iter()
+                .map(Punishment::from)
+                .filter(|p| p.is_active(now))
+                .collect(),
+        )
+    }
+
+    /// Every ban and mute the Player ever had
+    pub async fn get_punishments_of_player(&self, uuid: &Uuid) -> CloudResult<Vec<Punishment>> {
+        Ok(TablePlayerPunishments::find_by_player(self.get_db(), uuid)
+            .await?
+            .into_iter()
+            .map(Punishment::from)
+            .collect())
+    }
+
+    pub async fn add_to_whitelist(&self, player: &Player, issuer: &str) -> CloudResult<()> {
+        if TableWhitelist::find_by_player(self.get_db(), &player.get_uuid())
+            .await?
+            .is_some()
+        {
+            return Err(error!(
+                InvalidRequest,
+                format!("Player {} is already on the whitelist", player.get_name())
+            ));
+        }
+        TableWhitelist::new(player, issuer)
+            .create(self.get_db())
+            .await?;
+        log_info!(3, "Player |{}| added to the whitelist", player.get_name());
+        Ok(())
+    }
+
+    /// Returns `false` if the Player was not on the whitelist
+    pub async fn remove_from_whitelist(&self, player: &Player) -> CloudResult<bool> {
+        if TableWhitelist::find_by_player(self.get_db(), &player.get_uuid())
+            .await?
+            .is_none()
+        {
+            return Ok(false);
+        }
+        TableWhitelist::delete_by_player(self.get_db(), &player.get_uuid()).await?;
+        log_info!(
+            3,
+            "Player |{}| removed from the whitelist",
+            player.get_name()
+        );
+        Ok(true)
+    }
+
+    pub async fn get_whitelist(&self) -> CloudResult<Vec<WhitelistEntry>> {
+        Ok(TableWhitelist::find_all(self.get_db())
+            .await?
+            .into_iter()
+            .map(WhitelistEntry::from)
+            .collect())
+    }
+
+    pub fn is_whitelist_enabled(&self) -> bool {
+        self.whitelist_enabled.load(Ordering::Relaxed)
+    }
+
+    /// Only until the next restart, the default comes from `whitelist` in the config
+    pub fn set_whitelist_enabled(&self, enabled: bool) {
+        self.whitelist_enabled.store(enabled, Ordering::Relaxed);
+        log_info!(
+            3,
+            "Whitelist {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+    }
+
+    fn get_db(&self) -> &DatabaseManager {
+        self.db.as_ref()
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::database::table::TablePlayers;
+    use crate::database::test_database;
+
+    async fn player(db: &DatabaseManager) -> Player {
+        let uuid = Uuid::new_v4();
+        TablePlayers::new(&uuid, "Steve")
+            .unwrap()
+            .create(db)
+            .await
+            .unwrap();
+        let table = TablePlayers::find_by_uuid(db, &uuid)
+            .await
+            .unwrap()
+            .unwrap();
+        Player::new(table.get_id().0, "Steve".to_string(), uuid, None)
+    }
+
+    #[tokio::test]
+    async fn get_active_ignores_expired_bans() {
+        let db = test_database().await;
+        let manager = ModerationManager::new(db.clone(), false);
+        let player = player(db.as_ref()).await;
+
+        TablePlayerPunishments::new(PunishmentType::Ban, &player, "alt", "Console", Some(-60))
+            .create(db.as_ref())
+            .await
+            .unwrap();
+        assert!(
+            manager
+                .get_active(PunishmentType::Ban, &player.get_uuid())
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn get_active_finds_running_and_permanent_bans() {
+        let db = test_database().await;
+        let manager = ModerationManager::new(db.clone(), false);
+        let banned = player(db.as_ref()).await;
+        let forever = player(db.as_ref()).await;
+
+        manager
+            .punish(PunishmentType::Ban, &banned, "spam", "Console", Some(3600))
+            .await
+            .unwrap();
+        manager
+            .punish(PunishmentType::Ban, &forever, "cheats", "Console", None)
+            .await
+            .unwrap();
+
+        let active = manager
+            .get_active(PunishmentType::Ban, &banned.get_uuid())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(active.get_reason(), "spam");
+        assert!(
+            manager
+                .get_active(PunishmentType::Ban, &forever.get_uuid())
+                .await
+                .unwrap()
+                .is_some()
+        );
+        assert!(
+            manager
+                .get_active(PunishmentType::Mute, &banned.get_uuid())
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
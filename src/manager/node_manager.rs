@@ -1,16 +1,40 @@
+use futures_util::future::join_all;
+use serde_json::json;
+use std::collections::HashMap;
+use std::process::ExitStatus;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{RwLock, watch};
+use tokio::task::JoinHandle;
+use tokio::time::{MissedTickBehavior, interval, sleep};
 
-use crate::api::cluster::{ClusterClient, RestClusterClient};
+use crate::api::cluster::ClusterClient;
+use crate::api::internal::{
+    CAPABILITY_HEARTBEAT, CAPABILITY_REQUEST_RESPONSE, OutgoingMessage, OutgoingMessageType,
+    PluginHeartbeat,
+};
 use crate::config::CloudConfig;
-use crate::manager::{ServiceManagerRef, TaskManagerRef};
-use crate::types::{EntityId, Service, ServiceProcessRef, ServiceStatus, Task};
+use crate::manager::{PlayerManagerRef, ServiceManagerRef, TaskManagerRef};
+use crate::node::leader_election::LeaderElection;
+use crate::node::resource_monitor::ResourceMonitor;
+use crate::types::{
+    EntityId, Node, NodeLoad, OnlinePlayer, Service, ServiceEventType, ServiceMetrics,
+    ServiceProcessRef, ServiceStatus, Task,
+};
 use crate::utils::error::*;
-use crate::{error, log_warning};
+use crate::utils::metrics::METRICS;
+use crate::{error, log_error, log_info, log_warning};
+
+const PROCESS_WATCH_INTERVAL: Duration = Duration::from_secs(1);
 
 pub struct NodeManager {
     service_manager: ServiceManagerRef,
     task_manager: TaskManagerRef,
-    _cluster: Box<dyn ClusterClient>,
+    player_manager: PlayerManagerRef,
+    cluster: Arc<dyn ClusterClient>,
+    /// Services running on other Nodes of the Cluster
+    remote_services: RwLock<HashMap<EntityId, Service>>,
+    resource_monitor: Arc<ResourceMonitor>,
     cloud_config: Arc<CloudConfig>,
 }
 
@@ -19,15 +43,276 @@ impl NodeManager {
         cloud_config: Arc<CloudConfig>,
         service_manager: ServiceManagerRef,
         task_manager: TaskManagerRef,
+        player_manager: PlayerManagerRef,
+        cluster: Arc<dyn ClusterClient>,
     ) -> CloudResult<NodeManager> {
         Ok(NodeManager {
             service_manager,
             task_manager,
-            _cluster: Box::new(RestClusterClient::new(cloud_config.clone())),
+            player_manager,
+            cluster,
+            remote_services: RwLock::new(HashMap::new()),
+            resource_monitor: Arc::new(ResourceMonitor::new(
+                cloud_config
+                    .get_cloud_path()
+                    .get_service_folder()
+                    .get_temp_folder_path(),
+            )),
             cloud_config,
         })
     }
 
+    pub fn get_cluster(&self) -> &dyn ClusterClient {
+        self.cluster.as_ref()
+    }
+
+    pub fn get_resource_monitor(&self) -> Arc<ResourceMonitor> {
+        self.resource_monitor.clone()
+    }
+
+    /// Join the Cluster and send heartbeats until the Cloud is shutting down.
+    ///
+    /// The join is finished before this returns, so the Scheduler already knows
+    /// the other Nodes and their Services on its first tick.
+    pub async fn start_cluster(
+        self: Arc<Self>,
+        mut shutdown: watch::Receiver<bool>,
+    ) -> JoinHandle<()> {
+        match self.cluster.join_cluster().await {
+            Ok(_) => self.sync_remote_services().await,
+            Err(e) => log_warning!(2, "[Cluster] {}", e),
+        }
+
+        tokio::spawn(async move {
+            let mut ticker = interval(self.cloud_config.get_cluster().get_heartbeat_interval());
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {
+                        let resources = self.resource_monitor.get_node_resources().await;
+                        self.cluster.heartbeat(resources).await;
+                        for node in self.cluster.remove_dead_nodes().await {
+                            self.on_remote_node_lost(&node).await;
+                        }
+                        self.refresh_remote_services().await;
+                    }
+                    _ = shutdown.changed() => {
+                        if *shutdown.borrow() {
+                            break;
+                        }
+                    }
+                }
+            }
+
+            if let Err(e) = self.cluster.leave_cluster().await {
+                log_warning!(2, "[Cluster] Cant leave Cluster: {}", e);
+            }
+        })
+    }
+
+    /// Sample the host and the local Service processes until the Cloud is shutting down
+    pub fn start_resource_monitor(
+        self: Arc<Self>,
+        mut shutdown: watch::Receiver<bool>,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = interval(self.cloud_config.get_resource_monitor_interval());
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => self.sample_resources().await,
+                    _ = shutdown.changed() => {
+                        if *shutdown.borrow() {
+                            break;
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    async fn sample_resources(&self) {
+        let service_refs = self
+            .service_manager
+            .read()
+            .await
+            .filter_services(|_| true)
+            .await;
+        self.resource_monitor.sample_services(&service_refs).await;
+        self.resource_monitor
+            .sample_node(self.get_local_used_ram().await)
+            .await;
+    }
+
+    /// RAM in MB reserved for the local Services, see `ram_of`
+    pub async fn get_local_used_ram(&self) -> u64 {
+        let service_refs = self
+            .service_manager
+            .read()
+            .await
+            .filter_services(|s| Self::uses_ram(s))
+            .await;
+
+        let mut used_ram = 0;
+        for service_ref in service_refs {
+            used_ram += Self::ram_of(service_ref.read().await.get_service());
+        }
+        used_ram
+    }
+
+    /// Ping the plugins of all running local Services until the Cloud is shutting down.
+    ///
+    /// A missed ping marks the Service as Degraded, after the timeout it is killed
+    /// and `on_local_service_crashed` applies the Restart Policy of its Task.
+    pub fn start_plugin_heartbeat(
+        self: Arc<Self>,
+        mut shutdown: watch::Receiver<bool>,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = interval(self.cloud_config.get_plugin_heartbeat().get_interval());
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => self.check_plugin_heartbeats().await,
+                    _ = shutdown.changed() => {
+                        if *shutdown.borrow() {
+                            break;
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    async fn check_plugin_heartbeats(&self) {
+        let service_refs = self
+            .service_manager
+            .read()
+            .await
+            .filter_services(|s| s.is_running() || s.is_degraded())
+            .await;
+
+        join_all(
+            service_refs
+                .iter()
+                .map(|service_ref| self.check_plugin_heartbeat(service_ref)),
+        )
+        .await;
+    }
+
+    async fn check_plugin_heartbeat(&self, service_ref: &ServiceProcessRef) {
+        // older plugins do not answer pings, only a closed connection shows they are gone.
+        // Without request_response the answer can't be matched to the ping.
+        {
+            let sp = service_ref.read().await;
+            if !sp.has_capability(CAPABILITY_HEARTBEAT)
+                || !sp.has_capability(CAPABILITY_REQUEST_RESPONSE)
+            {
+                return;
+            }
+        }
+
+        let config = self.cloud_config.get_plugin_heartbeat();
+        let ping = OutgoingMessage::ok(None, OutgoingMessageType::Ping, json!({}));
+
+        match service_ref.request(&ping, config.get_interval()).await {
+            Ok(data) => {
+                let heartbeat = serde_json::from_value::<PluginHeartbeat>(data).unwrap_or_default();
+                let was_degraded = {
+                    let mut sp = service_ref.write().await;
+                    sp.record_heartbeat(heartbeat);
+                    sp.is_degraded()
+                };
+
+                if was_degraded {
+                    log_info!(
+                        3,
+                        "Service [{}] answers heartbeats again",
+                        service_ref.get_name().await
+                    );
+                    let sm = self.service_manager.read().await;
+                    sm.update_status(service_ref, ServiceStatus::Running).await;
+                }
+            }
+            Err(e) => {
+                let (name, since, is_running) = {
+                    let sp = service_ref.read().await;
+                    (
+                        sp.get_name().to_string(),
+                        sp.get_time_since_heartbeat().unwrap_or_default(),
+                        sp.is_running(),
+                    )
+                };
+
+                if since >= config.get_timeout() {
+                    log_error!(
+                        1,
+                        "Service [{}] did not answer heartbeats for {}s, kill it",
+                        name,
+                        since.as_secs()
+                    );
+                    if let Err(e) = service_ref.write().await.start_kill() {
+                        log_warning!(2, "Service [{}] can't kill: {}", name, e);
+                    }
+                } else if is_running {
+                    log_warning!(2, "Service [{}] missed a heartbeat: {}", name, e);
+                    let sm = self.service_manager.read().await;
+                    sm.update_status(service_ref, ServiceStatus::Degraded).await;
+                }
+            }
+        }
+    }
+
+    /// fetch the Services of all known Nodes, used after joining the Cluster
+    async fn sync_remote_services(&self) {
+        for node in self.cluster.get_nodes().await {
+            match self.cluster.get_services(node.get_name()).await {
+                Ok(services) => {
+                    for service in services {
+                        if let Err(e) = self.on_remote_service_registered(service).await {
+                            log_warning!(3, "{:?}", e);
+                        }
+                    }
+                }
+                Err(e) => log_warning!(
+                    3,
+                    "[Cluster] Cant get Services from Node {}: {}",
+                    node.get_name(),
+                    e
+                ),
+            }
+        }
+    }
+
+    /// Replace the Services of every known Node with their current state.
+    /// Players, idle time and performance of remote Services change without a broadcast,
+    /// so the Scheduler would scale on old values without this.
+    async fn refresh_remote_services(&self) {
+        let nodes = self.cluster.get_nodes().await;
+        let requests = nodes.iter().map(|node| async move {
+            (
+                node.get_name(),
+                self.cluster.get_services(node.get_name()).await,
+            )
+        });
+
+        for (node, result) in join_all(requests).await {
+            match result {
+                Ok(services) => {
+                    let mut remote_services = self.remote_services.write().await;
+                    remote_services.retain(|_, s| s.get_parent_node() != node);
+                    remote_services.extend(services.into_iter().map(|s| (*s.get_id(), s)));
+                }
+                Err(e) => {
+                    log_warning!(4, "[Cluster] Cant refresh Services of Node {}: {}", node, e)
+                }
+            }
+        }
+    }
+
     pub async fn stop_all_local_services(&self, msg: &str) {
         let services = self
             .service_manager
@@ -63,19 +348,65 @@ impl NodeManager {
                 .await;
         } else {
             // service is remote
-            todo!("Send stop command to Other Node");
+            let node = match self.remote_services.read().await.get(&id) {
+                Some(service) => service.get_parent_node().to_string(),
+                None => {
+                    log_warning!(3, "Cant find Service {} in the Cluster", id);
+                    return;
+                }
+            };
+
+            if let Err(e) = self.cluster.stop_service(&node, &id, msg).await {
+                log_warning!(2, "Cant stop Service {} on Node {}: {}", id, node, e);
+            }
         }
     }
     pub async fn is_responsible_for_task(&self, task: &Task) -> bool {
         task.is_responsible_node(&self.cloud_config.get_name())
     }
 
+    /// Only the leader of a Task starts and stops its Services,
+    /// the leader is elected from all alive Nodes responsible for the Task
+    pub async fn is_leader_for_task(&self, task: &Task) -> bool {
+        let local_name = self.cloud_config.get_name();
+        if !task.is_responsible_node(&local_name) {
+            return false;
+        }
+
+        let nodes = self.cluster.get_nodes().await;
+        let mut candidates: Vec<&str> = nodes
+            .iter()
+            .map(|n| n.get_name())
+            .filter(|name| task.is_responsible_node(name))
+            .collect();
+        candidates.push(&local_name);
+
+        LeaderElection::elect(task.get_name(), &candidates) == Some(local_name.as_str())
+    }
+
+    /// All local Services that are not stopped or wait for their restart
+    pub async fn get_local_services(&self) -> Vec<Service> {
+        let service_refs = self
+            .service_manager
+            .read()
+            .await
+            .filter_services(|s| {
+                !s.is_stop() || s.get_status() == ServiceStatus::Stopping || s.is_restart_pending()
+            })
+            .await;
+        let mut services = Vec::new();
+        for service_ref in service_refs {
+            services.push(service_ref.read().await.get_service().clone());
+        }
+        services
+    }
+
     pub async fn get_all_services_from_task(&self, task_name: &str) -> Vec<Service> {
         let service_refs = self
             .service_manager
             .read()
             .await
-            .filter_services(|s| s.get_name() == task_name)
+            .filter_services(|s| s.get_task_name() == task_name)
             .await;
         let mut services = Vec::new();
 
@@ -83,19 +414,50 @@ impl NodeManager {
             services.push(service_ref.read().await.get_service().clone());
         }
 
+        // Services on other Nodes of the Cluster
+        services.extend(
+            self.remote_services
+                .read()
+                .await
+                .values()
+                .filter(|s| s.get_task_name() == task_name)
+                .cloned(),
+        );
+
         services
     }
 
-    pub async fn start_service_from_task(&self, task: &Task) -> CloudResult<()> {
-        if self.cloud_config.get_name() != self.find_best_node(task).await {
-            // send start request to Node
+    pub async fn start_service_from_task(self: &Arc<Self>, task: &Task) -> CloudResult<()> {
+        let node = self.find_best_node(task).await?;
+        if self.cloud_config.get_name() == node {
+            self.start_local_service_from_task(task.get_name()).await?;
             return Ok(());
         }
-        // start service local
+
+        // send start request to Node
+        let service = self.cluster.start_service(&node, task.get_name()).await?;
+        log_info!(
+            4,
+            "Service [{}] is starting on Node {}",
+            service.get_name(),
+            node
+        );
+        self.remote_services
+            .write()
+            .await
+            .insert(*service.get_id(), service);
+        Ok(())
+    }
+
+    /// Start a Service from the Task on this Node
+    pub async fn start_local_service_from_task(
+        self: &Arc<Self>,
+        task_name: &str,
+    ) -> CloudResult<Service> {
         let service_ref = {
             let tasks = {
                 let tm = self.task_manager.read().await;
-                tm.filter_tasks(|t| t.get_name() == task.get_name()).await
+                tm.filter_tasks(|t| t.get_name() == task_name).await
             };
 
             let task_ref = match tasks.first() {
@@ -107,8 +469,157 @@ impl NodeManager {
             sm.get_or_create_service(task_ref).await?
         };
 
-        self.service_manager.read().await.start(service_ref).await?;
-        Ok(())
+        self.service_manager
+            .read()
+            .await
+            .start(service_ref.clone())
+            .await?;
+        self.watch_local_service(service_ref.clone());
+
+        let service = service_ref.read().await.get_service().clone();
+        Ok(service)
+    }
+
+    /// Watch the process of a local Service until it is stopped by the Cloud.
+    /// Unexpected exits are handled by `on_local_service_crashed`.
+    fn watch_local_service(self: &Arc<Self>, service_ref: ServiceProcessRef) {
+        let node_manager = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                sleep(PROCESS_WATCH_INTERVAL).await;
+
+                let status = {
+                    let mut sp = service_ref.write().await;
+                    if !sp.has_process() {
+                        break;
+                    }
+                    match sp.try_wait_exit() {
+                        Ok(Some(status)) => status,
+                        Ok(None) => continue,
+                        Err(e) => {
+                            log_warning!(2, "Cant watch process of [{}]: {}", sp.get_name(), e);
+                            break;
+                        }
+                    }
+                };
+
+                if service_ref.read().await.is_shutdown_init() {
+                    break;
+                }
+
+                if !node_manager
+                    .on_local_service_crashed(&service_ref, status)
+                    .await
+                {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// Local (Process exited unexpected) -> mark as Failed and apply the RestartPolicy of the Task.
+    /// Returns `true` if the Service was restarted.
+    async fn on_local_service_crashed(
+        &self,
+        service_ref: &ServiceProcessRef,
+        status: ExitStatus,
+    ) -> bool {
+        let (name, task_name, crash, attempt) = {
+            let mut sp = service_ref.write().await;
+            let crash = sp.record_crash(status.code());
+            METRICS.service_crashed(sp.get_task_name());
+            (
+                sp.get_name().to_string(),
+                sp.get_task_name().to_string(),
+                crash,
+                sp.get_restart_count() + 1,
+            )
+        };
+
+        log_error!(
+            1,
+            "Service [{}] crashed (Exit Code: {:?})\n{}",
+            name,
+            crash.get_exit_code(),
+            crash.get_stderr_tail().join("\n")
+        );
+
+        let backoff = match self.task_manager.get_task_ref_from_name(&task_name).await {
+            Ok(task_ref) => {
+                let restart_policy = task_ref.read().await.get_restart_policy().clone();
+                let backoff = restart_policy.get_backoff(attempt);
+                if backoff.is_none() {
+                    log_warning!(
+                        2,
+                        "Service [{}] is not restarted (Restart Policy: {})",
+                        name,
+                        restart_policy
+                    );
+                }
+                backoff
+            }
+            Err(_) => None,
+        };
+
+        // set before the status, otherwise the Scheduler could reuse the Failed Service
+        service_ref
+            .write()
+            .await
+            .set_restart_pending(backoff.is_some());
+
+        {
+            let sm = self.service_manager.read().await;
+            sm.update_status_with_event(
+                service_ref,
+                ServiceStatus::Failed,
+                ServiceEventType::Crashed,
+                Some(format!("Exit Code: {:?}", crash.get_exit_code())),
+            )
+            .await;
+        }
+
+        if let Err(e) = self.unregistered_local_service(service_ref).await {
+            log_warning!(3, "{:?}", e);
+        }
+
+        let backoff = match backoff {
+            Some(backoff) => backoff,
+            None => return false,
+        };
+
+        log_info!(
+            3,
+            "Restart Service [{}] in {}s (Attempt {})",
+            name,
+            backoff.as_secs(),
+            attempt
+        );
+        sleep(backoff).await;
+
+        // a stop in the meantime changed the status and cancelled the restart
+        if !service_ref.read().await.is_restart_pending() {
+            return false;
+        }
+
+        service_ref.write().await.increment_restart_count();
+        let sm = self.service_manager.read().await;
+        match sm.start(service_ref.clone()).await {
+            Ok(_) => {
+                sm.record_event(
+                    service_ref,
+                    ServiceEventType::Restarted,
+                    Some(format!("Attempt {}", attempt)),
+                )
+                .await;
+                true
+            }
+            Err(e) => {
+                log_error!(1, "Cant restart Service [{}]: {}", name, e);
+                sm.update_status(service_ref, ServiceStatus::Failed).await;
+                false
+            }
+        }
     }
 
     pub async fn get_online_backend_server(&self) -> Vec<Service> {
@@ -122,6 +633,14 @@ impl NodeManager {
         for s in services {
             result.push(s.read().await.get_service().clone())
         }
+        result.extend(
+            self.remote_services
+                .read()
+                .await
+                .values()
+                .filter(|s| s.is_running() && s.is_backend_server())
+                .cloned(),
+        );
         result
     }
 
@@ -134,32 +653,69 @@ impl NodeManager {
             sm.update_status(&service_ref, ServiceStatus::Running).await;
         }
 
-        self.service_manager
-            .read()
-            .await
-            .register_on_proxy(service_ref.read().await.get_service())
-            .await?;
+        // a fresh Service has no Players, so it is idle from now on
+        {
+            let mut sp = service_ref.write().await;
+            sp.reset_restart_count();
+            sp.stop_idle_timer();
+            sp.start_idle_timer();
+        }
+
+        let service = service_ref.read().await.get_service().clone();
+        self.cluster.broadcast_service_registered(&service).await;
+
+        self.service_manager.register_on_proxy(&service).await
+    }
 
+    /// Local (Server Plugin called) -> store the sample in the rolling window
+    pub async fn on_local_service_metrics(
+        &self,
+        id: EntityId,
+        metrics: ServiceMetrics,
+    ) -> CloudResult<()> {
+        let service_ref = { self.service_manager.read().await.get_from_id(&id)? };
+        service_ref.write().await.add_metrics(metrics);
         Ok(())
     }
 
     async fn unregistered_local_service(&self, service_ref: &ServiceProcessRef) -> CloudResult<()> {
-        self.service_manager
+        let service = service_ref.read().await.get_service().clone();
+        self.cluster.broadcast_service_shutdown(&service).await;
+
+        // a crashed Service sends no Leave for its Players
+        if let Err(e) = self
+            .player_manager
             .read()
             .await
-            .unregister_from_proxy(service_ref.read().await.get_service())
-            .await?;
+            .clear_players_of_service(service_ref)
+            .await
+        {
+            log_warning!(
+                3,
+                "Cant clear Players of Service [{}]: {}",
+                service.get_name(),
+                e
+            );
+        }
+
+        self.service_manager.unregister_from_proxy(&service).await?;
 
         Ok(())
     }
 
     /// Remote (Node called) -> info Local
     pub async fn on_remote_service_registered(&self, service: Service) -> CloudResult<()> {
-        self.service_manager
-            .read()
+        self.remote_services
+            .write()
             .await
-            .register_on_proxy(&service)
-            .await?;
+            .insert(*service.get_id(), service.clone());
+
+        // Services fetched while joining can still be starting
+        if !service.is_running() {
+            return Ok(());
+        }
+
+        self.service_manager.register_on_proxy(&service).await?;
         Ok(())
     }
 
@@ -181,12 +737,83 @@ impl NodeManager {
 
     /// Remote (Node called) -> info Local
     pub async fn on_remote_service_shutdown(&self, service: Service) -> CloudResult<()> {
-        self.service_manager
+        self.remote_services.write().await.remove(service.get_id());
+
+        self.service_manager.unregister_from_proxy(&service).await?;
+        Ok(())
+    }
+
+    /// Remote (Node left or timed out) -> all its Services are gone
+    pub async fn on_remote_node_lost(&self, node: &Node) {
+        let services: Vec<Service> = {
+            let mut remote_services = self.remote_services.write().await;
+            let ids: Vec<EntityId> = remote_services
+                .values()
+                .filter(|s| s.get_parent_node() == node.get_name())
+                .map(|s| *s.get_id())
+                .collect();
+            ids.iter()
+                .filter_map(|id| remote_services.remove(id))
+                .collect()
+        };
+
+        for service in services {
+            if let Err(e) = self.service_manager.unregister_from_proxy(&service).await {
+                log_warning!(3, "{:?}", e);
+            }
+        }
+    }
+
+    pub async fn get_remote_services(&self) -> Vec<Service> {
+        self.remote_services
             .read()
             .await
-            .unregister_from_proxy(&service)
-            .await?;
-        Ok(())
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Players online on the Proxies of this Node
+    pub async fn get_local_players(&self) -> Vec<OnlinePlayer> {
+        self.player_manager.read().await.get_online_players().await
+    }
+
+    /// Players online on the Proxies of all Nodes sorted by name, a Node which doesn't answer is skipped
+    pub async fn get_network_players(&self) -> Vec<OnlinePlayer> {
+        let mut players = self.get_local_players().await;
+        players.extend(self.get_remote_players().await);
+        players.sort_by(|a, b| a.get_name().cmp(b.get_name()));
+        players
+    }
+
+    /// The Player on any Proxy of the Network, this Node is asked first
+    pub async fn find_network_player<F>(&self, filter: F) -> Option<OnlinePlayer>
+    where
+        F: Fn(&OnlinePlayer) -> bool,
+    {
+        match self.get_local_players().await.into_iter().find(&filter) {
+            Some(player) => Some(player),
+            None => self.get_remote_players().await.into_iter().find(filter),
+        }
+    }
+
+    async fn get_remote_players(&self) -> Vec<OnlinePlayer> {
+        let nodes = self.cluster.get_nodes().await;
+        let requests = nodes.iter().map(|node| async move {
+            match self.cluster.get_players(node.get_name()).await {
+                Ok(players) => players,
+                Err(e) => {
+                    log_warning!(
+                        3,
+                        "[Cluster] Cant get Players from Node {}: {}",
+                        node.get_name(),
+                        e
+                    );
+                    Vec::new()
+                }
+            }
+        });
+        join_all(requests).await.into_iter().flatten().collect()
     }
 
     pub fn get_service_manager(&self) -> ServiceManagerRef {
@@ -194,7 +821,112 @@ impl NodeManager {
     }
 
     /// find the best Node in Cluster to Start the new Service from Task
-    async fn find_best_node(&self, _task: &Task) -> String {
-        String::from("Node-1")
+    async fn find_best_node(&self, task: &Task) -> CloudResult<String> {
+        let mut loads: Vec<NodeLoad> = Vec::new();
+
+        if task.is_responsible_node(&self.cloud_config.get_name()) {
+            let mut load = NodeLoad::new(
+                &self.cloud_config.get_name(),
+                self.cloud_config.get_max_ram(),
+            );
+            let services = self
+                .service_manager
+                .read()
+                .await
+                .filter_services(|s| Self::uses_ram(s))
+                .await;
+            for service_ref in services {
+                let sp = service_ref.read().await;
+                let service = sp.get_service();
+                load.add_service(
+                    Self::ram_of(service),
+                    service.get_task_name() == task.get_name(),
+                );
+            }
+            if let Some(resources) = self.resource_monitor.get_node_resources().await {
+                load.set_host_free_ram(resources.get_free_memory_mb());
+            }
+            loads.push(load);
+        }
+
+        for node in self.cluster.get_nodes().await {
+            if !task.is_responsible_node(node.get_name()) {
+                continue;
+            }
+            let mut load = NodeLoad::new(node.get_name(), node.get_max_ram());
+            // the Node measures its own Services, without a sample the max_ram is reserved
+            if let Some(resources) = node.get_resources() {
+                load.add_ram(resources.get_services_ram());
+                load.set_host_free_ram(resources.get_free_memory_mb());
+            }
+            for service in self.remote_services.read().await.values() {
+                if service.get_parent_node() != node.get_name() || !Self::uses_ram(service) {
+                    continue;
+                }
+                if node.get_resources().is_none() {
+                    load.add_ram(Self::ram_of(service));
+                } else if service.get_status() == ServiceStatus::Starting {
+                    // started after the last sample of the Node, so it is not in the sample yet
+                    load.add_ram(service.get_config().get_max_ram() as u64);
+                }
+                if service.get_task_name() == task.get_name() {
+                    load.add_task_service();
+                }
+            }
+            loads.push(load);
+        }
+
+        let policy = self.cloud_config.get_placement_policy();
+        match policy.select(&loads, task.get_max_ram() as u64) {
+            Some(load) => {
+                log_info!(
+                    6,
+                    "[Placement] Task [{}] -> Node {} ({}/{} MB used, Policy: {})",
+                    task.get_name(),
+                    load.get_name(),
+                    load.get_used_ram(),
+                    load.get_max_ram(),
+                    policy
+                );
+                Ok(load.get_name().to_string())
+            }
+            None => Err(error!(
+                InsufficientCapacity,
+                format!(
+                    "Task [{}] needs {} MB RAM, no allowed Node has enough free RAM ({})",
+                    task.get_name(),
+                    task.get_max_ram(),
+                    loads
+                        .iter()
+                        .map(|l| format!(
+                            "{}: {}/{} MB",
+                            l.get_name(),
+                            l.get_used_ram(),
+                            l.get_max_ram()
+                        ))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )
+            )),
+        }
+    }
+
+    /// RAM in MB reserved for the Service, the `max_ram` or the measured RAM if it uses more
+    fn ram_of(service: &Service) -> u64 {
+        let max_ram = service.get_config().get_max_ram() as u64;
+        match service.get_resources() {
+            Some(resources) => max_ram.max(resources.get_memory_mb()),
+            None => max_ram,
+        }
+    }
+
+    fn uses_ram(service: &Service) -> bool {
+        matches!(
+            service.get_status(),
+            ServiceStatus::Starting
+                | ServiceStatus::Running
+                | ServiceStatus::Degraded
+                | ServiceStatus::Stopping
+        )
     }
 }
@@ -1,18 +1,26 @@
+use chrono::{Duration, Utc};
 use database_manager::DatabaseManager;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::sync::Arc;
 use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use uuid::Uuid;
 
 use crate::api::internal::{
-    OutgoingMessage, OutgoingMessageType, PlayerActionMessage, ServiceInfoResponse,
+    OutgoingMessage, OutgoingMessageType, PlayerActionMessage, PlayerMuteMessage,
+    PlayerSyncMessage, ServiceInfoResponse,
 };
 use crate::cloud::Cloud;
 use crate::config::{CloudConfig, SoftwareConfigRef};
 use crate::database::table::{TablePlayerEvents, TablePlayerSessions, TablePlayers, TableServices};
 use crate::manager::service_manager::ServiceManager;
-use crate::manager::{ServiceManagerRef, TaskManager, TaskManagerRef};
-use crate::types::{EntityId, Player, PlayerAction, PlayerSession, ServiceProcessRef, ServiceStatus};
+use crate::manager::{
+    MaintenanceManager, ModerationManager, ServiceManagerRef, TaskManager, TaskManagerRef,
+};
+use crate::types::{
+    EntityId, OnlinePlayer, Player, PlayerAction, PlayerAnalytics, PlayerHistory, PlayerLocation,
+    PlayerSession, PlayerStatistics, Punishment, PunishmentType, ServiceProcessRef, ServiceStatus,
+    TaskRef,
+};
 use crate::utils::error::*;
 use crate::utils::utils::Utils;
 use crate::{error, log_info, log_warning};
@@ -21,8 +29,11 @@ pub struct PlayerManager {
     db: Arc<DatabaseManager>,
     service_manager: ServiceManagerRef,
     task_manager: TaskManagerRef,
+    moderation_manager: Arc<ModerationManager>,
+    maintenance_manager: Arc<MaintenanceManager>,
 
-    players: HashMap<u64, Player>,
+    /// Players online on the Proxies of this Node
+    players: RwLock<HashMap<Uuid, OnlinePlayer>>,
 }
 
 pub struct PlayerManagerRef(Arc<RwLock<PlayerManager>>);
@@ -32,13 +43,17 @@ impl PlayerManager {
         db: Arc<DatabaseManager>,
         service_manager: ServiceManagerRef,
         task_manager: TaskManagerRef,
+        moderation_manager: Arc<ModerationManager>,
+        maintenance_manager: Arc<MaintenanceManager>,
     ) -> PlayerManager {
         PlayerManager {
             db,
             service_manager,
             task_manager,
+            moderation_manager,
+            maintenance_manager,
 
-            players: HashMap::new(),
+            players: RwLock::new(HashMap::new()),
         }
     }
 
@@ -53,27 +68,59 @@ impl PlayerManager {
         let p = Player::from(&req);
         let mut player = self.get_or_create_player(&p).await?;
 
-        let (mut current_players, task_ref) = {
+        let (mut current_players, task_ref, location) = {
             let s = service_ref.read().await;
             let service = s.get_service();
             let task_ref = self
                 .task_manager
                 .get_task_ref_from_name(service.get_task_name())
                 .await?;
-            (service.get_current_players(), task_ref)
+            (
+                service.get_current_players(),
+                task_ref,
+                PlayerLocation::new(service),
+            )
+        };
+
+        // banned Players, Players which are not on the whitelist and Players without the
+        // maintenance bypass never get a session
+        let denied = if req.get_action() == PlayerAction::Join && service_ref.is_proxy().await {
+            match self
+                .moderation_manager
+                .check_join(&player.get_uuid(), player.get_name(), req.get_permissions())
+                .await?
+            {
+                Some(denied) => Some(denied),
+                None => self.maintenance_manager.check_join(
+                    &player.get_uuid(),
+                    player.get_name(),
+                    req.get_permissions(),
+                ),
+            }
+        } else {
+            None
         };
+        if let Some(denied) = denied {
+            return Ok(match Utils::convert_to_json(&denied) {
+                Some(data) => OutgoingMessage::ok(None, OutgoingMessageType::JoinDenied, data),
+                None => OutgoingMessage::err(None, "Cant parse the JoinDenied".to_string()),
+            });
+        }
 
         // Player Join
         if req.get_action() == PlayerAction::Join {
             let id = service_ref.get_id().await;
             current_players += 1;
-            self.add_event(&player, &service_ref, &req.get_action(), None)
-                .await?;
 
             // join on proxy
             if service_ref.is_proxy().await {
-                let service_manager = self.service_manager.read().await;
-                match service_manager.find_next_default_connect_server().await {
+                let fallback = self
+                    .service_manager
+                    .read()
+                    .await
+                    .find_fallback_server(req.get_permissions())
+                    .await;
+                match fallback {
                     Some(s) => {
                         match Utils::convert_to_json(&ServiceInfoResponse::new(
                             s.read().await.get_service(),
@@ -96,15 +143,53 @@ impl PlayerManager {
                     }
                 };
 
+                // a Proxy can send the Join again, the open session on it stays as it is
+                if self.has_session_on(&player, &service_ref).await {
+                    log_info!(
+                        7,
+                        "Ignore second Join of Player |{}| on [{}]",
+                        player.get_name(),
+                        location.get_name()
+                    );
+                    return Ok(out_msg);
+                }
+
+                // the event needs the id of the new session
                 self.create_session(&mut player, &id).await?;
+                self.add_event(&player, &service_ref, &req.get_action(), None)
+                    .await?;
                 self.update_last_login(&mut player).await?;
+
+                let mut online = OnlinePlayer::new(player.get_uuid(), player.get_name());
+                online.set_proxy(location);
+                self.players.write().await.insert(player.get_uuid(), online);
             } else
             // join on backend Server
             {
                 self.update_session(&mut player, &id).await?;
+                self.add_event(&player, &service_ref, &req.get_action(), None)
+                    .await?;
+
+                self.players
+                    .write()
+                    .await
+                    .entry(player.get_uuid())
+                    .or_insert_with(|| OnlinePlayer::new(player.get_uuid(), player.get_name()))
+                    .set_service(Some(location));
             }
         } else if req.get_action() == PlayerAction::Leave {
-            current_players -= 1;
+            // a join denied by a ban, the whitelist or the maintenance got no session,
+            // but the Proxy still reports the leave
+            if !self.has_session_on(&player, &service_ref).await {
+                log_info!(
+                    7,
+                    "Ignore Leave of Player |{}| without a session on [{}]",
+                    player.get_name(),
+                    location.get_name()
+                );
+                return Ok(out_msg);
+            }
+            current_players = current_players.saturating_sub(1);
 
             let session_id = player.get_session().clone().map(|s| s.get_id());
             self.add_event(&player, &service_ref, &req.get_action(), session_id)
@@ -117,8 +202,16 @@ impl PlayerManager {
                     Ok(_) => log_info!(7, "Session for Player |{}| deleted", player_id),
                     Err(e) => log_warning!("Cant delete Session for Player |{}|: {}", player_id, e),
                 }
+                self.players.write().await.remove(&player.get_uuid());
             } else {
-                // leave backend Serve
+                // leave backend Server, the join on the next Server can arrive first
+                let mut players = self.players.write().await;
+                if let Some(online) = players
+                    .get_mut(&player.get_uuid())
+                    .filter(|p| p.get_service() == Some(&location))
+                {
+                    online.set_service(None);
+                }
             }
         }
 
@@ -141,23 +234,299 @@ impl PlayerManager {
             }
         }*/
 
-        let (percent, max_p) = {
-            let t = task_ref.read().await;
+        self.update_player_count(&service_ref, &task_ref, current_players)
+            .await;
+        Ok(out_msg)
+    }
+
+    /// The plugin reports every Player of its Service, its list replaces what the Node counted.
+    /// Players the Node still holds on a Proxy but the Proxy no longer has get a synthetic Leave,
+    /// Players the Node missed get their session.
+    pub async fn sync_players(
+        &self,
+        service_id: &EntityId,
+        sync: &PlayerSyncMessage,
+    ) -> CloudResult<()> {
+        let service_ref = {
+            let sm = self.service_manager.read().await;
+            sm.get_from_id(service_id)?
+        };
+        let (counted, task_ref, location) = {
+            let s = service_ref.read().await;
+            let service = s.get_service();
+            let task_ref = self
+                .task_manager
+                .get_task_ref_from_name(service.get_task_name())
+                .await?;
             (
-                t.get_percent_of_players_to_check_should_auto_stop_the_service(),
-                t.get_max_players(),
+                service.get_current_players(),
+                task_ref,
+                PlayerLocation::new(service),
             )
         };
+        let reported: HashMap<Uuid, &str> = sync
+            .get_players()
+            .iter()
+            .map(|p| (*p.get_uuid(), p.get_name()))
+            .collect();
+
+        if service_ref.is_proxy().await {
+            let orphaned: Vec<Uuid> = self
+                .filter_online_players(|p| {
+                    p.get_proxy().map(|l| l.get_id()) == Some(service_id)
+                        && !reported.contains_key(&p.get_uuid())
+                })
+                .await
+                .iter()
+                .map(|p| p.get_uuid())
+                .collect();
+            for uuid in orphaned {
+                self.close_session(&uuid, &service_ref).await?;
+            }
 
-        let start_timer = current_players == 0 || percent > (max_p * 100) / current_players;
-        {
-            let mut s = service_ref.write().await;
-            s.set_current_player(current_players);
-            if start_timer {
-                s.start_idle_timer();
+            let missing: Vec<(Uuid, &str)> = {
+                let players = self.players.read().await;
+                reported
+                    .iter()
+                    .filter(|(uuid, _)| !players.contains_key(*uuid))
+                    .map(|(uuid, name)| (*uuid, *name))
+                    .collect()
+            };
+            for (uuid, name) in missing {
+                self.adopt_player(&uuid, name, &service_ref, &location)
+                    .await?;
+            }
+        } else {
+            // the Proxy holds the sessions, only the location of the Players is corrected
+            let mut players = self.players.write().await;
+            for online in players.values_mut() {
+                let is_here = online.get_service() == Some(&location);
+                let is_reported = reported.contains_key(&online.get_uuid());
+                if is_here && !is_reported {
+                    online.set_service(None);
+                } else if !is_here && is_reported {
+                    online.set_service(Some(location.clone()));
+                }
             }
         }
-        Ok(out_msg)
+
+        let current_players = reported.len() as u32;
+        if counted != current_players {
+            log_info!(
+                5,
+                "Service [{}] counted {} Players, the plugin reports {}",
+                location.get_name(),
+                counted,
+                current_players
+            );
+        }
+        self.update_player_count(&service_ref, &task_ref, current_players)
+            .await;
+        Ok(())
+    }
+
+    /// The Service crashed or stopped without a Leave for its Players.
+    /// Sessions held by a Proxy are closed with a synthetic Leave.
+    pub async fn clear_players_of_service(
+        &self,
+        service_ref: &ServiceProcessRef,
+    ) -> CloudResult<()> {
+        let service_id = service_ref.get_id().await;
+        service_ref.write().await.set_current_player(0);
+
+        if !service_ref.is_proxy().await {
+            for online in self.players.write().await.values_mut() {
+                if online.get_service().map(|l| l.get_id()) == Some(&service_id) {
+                    online.set_service(None);
+                }
+            }
+            return Ok(());
+        }
+
+        let orphaned: Vec<Uuid> = self
+            .filter_online_players(|p| p.get_proxy().map(|l| l.get_id()) == Some(&service_id))
+            .await
+            .iter()
+            .map(|p| p.get_uuid())
+            .collect();
+        for uuid in orphaned {
+            self.close_session(&uuid, service_ref).await?;
+        }
+
+        // Players which never reached a backend Server
+        for session in TablePlayerSessions::find_by_service(self.get_db(), &service_id).await? {
+            self.close_orphaned_session(session, service_ref).await?;
+        }
+        Ok(())
+    }
+
+    /// After a restart of the Node its Services are stopped, nobody sends the Leave
+    /// for the sessions which were open on them. The same goes for sessions on Services
+    /// which no longer exist, like deleted temporary Services. Sessions on Services of
+    /// other Nodes stay open, those Nodes still track them.
+    pub async fn clear_orphaned_sessions(&self) -> CloudResult<()> {
+        let (local_services, node_name) = {
+            let sm = self.service_manager.read().await;
+            let services = sm.filter_services(|_| true).await;
+            let mut local_services = HashMap::new();
+            for service_ref in services {
+                local_services.insert(service_ref.get_id().await, service_ref);
+            }
+            (local_services, sm.get_node_name())
+        };
+        let remote_services =
+            TableServices::find_uuids_of_other_nodes(self.get_db(), &node_name).await?;
+
+        let mut closed = 0;
+        for session in TablePlayerSessions::find_all(self.get_db()).await? {
+            let service_uuid = session.get_service_uuid();
+            match local_services.get(&service_uuid) {
+                Some(service_ref) => self.close_orphaned_session(session, service_ref).await?,
+                // still tracked by its own Node
+                None if remote_services.contains(&service_uuid) => continue,
+                None => self.close_session_of_unknown_service(session).await?,
+            }
+            closed += 1;
+        }
+        if closed > 0 {
+            log_info!(3, "Closed {} orphaned Player sessions", closed);
+        }
+        Ok(())
+    }
+
+    /// Sets the count of the Service and starts or stops its idle timer
+    async fn update_player_count(
+        &self,
+        service_ref: &ServiceProcessRef,
+        task_ref: &TaskRef,
+        current_players: u32,
+    ) {
+        let (empty_percent, max_p) = {
+            let t = task_ref.read().await;
+            (t.get_empty_percent(), t.get_max_players())
+        };
+
+        let is_idle = current_players.saturating_mul(100) <= max_p.saturating_mul(empty_percent);
+        let mut s = service_ref.write().await;
+        s.set_current_player(current_players);
+        if is_idle {
+            s.start_idle_timer();
+        } else {
+            s.stop_idle_timer();
+        }
+    }
+
+    /// Synthetic Leave for a Player the Proxy no longer has
+    async fn close_session(&self, uuid: &Uuid, proxy: &ServiceProcessRef) -> CloudResult<()> {
+        if let Some(mut player) = self.get_player_by_uuid(uuid).await? {
+            self.set_session_for_player(&mut player).await?;
+            if let Some(session_id) = player.get_session().clone().map(|s| s.get_id()) {
+                self.add_event(&player, proxy, &PlayerAction::Leave, Some(session_id))
+                    .await?;
+                self.delete_session(&mut player).await?;
+            }
+            log_info!(
+                5,
+                "Closed orphaned session of Player |{}|",
+                player.get_name()
+            );
+        }
+        self.players.write().await.remove(uuid);
+        Ok(())
+    }
+
+    async fn close_orphaned_session(
+        &self,
+        session: TablePlayerSessions,
+        service_ref: &ServiceProcessRef,
+    ) -> CloudResult<()> {
+        match TablePlayers::find_by_id(self.get_db(), session.get_player_id()).await? {
+            Some(player) => {
+                self.close_session(&Player::from(player).get_uuid(), service_ref)
+                    .await
+            }
+            // the Player itself is gone, only the session is left
+            None => {
+                TablePlayerSessions::delete_by_player_id(self.get_db(), session.get_player_id())
+                    .await?;
+                Ok(())
+            }
+        }
+    }
+
+    /// The Service is gone, so the Task of the Leave comes from the Join of the session
+    async fn close_session_of_unknown_service(
+        &self,
+        session: TablePlayerSessions,
+    ) -> CloudResult<()> {
+        let task_name = TablePlayerEvents::find_by_session_id(self.get_db(), session.get_id())
+            .await?
+            .first()
+            .map(|e| e.get_task().to_string());
+        TablePlayerEvents::new_orphaned_leave(
+            session.get_player_id(),
+            session.get_id(),
+            &session.get_service_uuid(),
+            task_name,
+        )
+        .create(self.get_db())
+        .await?;
+        TablePlayerSessions::delete_by_player_id(self.get_db(), session.get_player_id()).await?;
+
+        if let Some(player) =
+            TablePlayers::find_by_id(self.get_db(), session.get_player_id()).await?
+        {
+            let player = Player::from(player);
+            self.players.write().await.remove(&player.get_uuid());
+            log_info!(
+                5,
+                "Closed session of Player |{}| on a removed Service",
+                player.get_name()
+            );
+        }
+        Ok(())
+    }
+
+    /// Session for a Player whose Join the Node never got
+    async fn adopt_player(
+        &self,
+        uuid: &Uuid,
+        name: &str,
+        proxy: &ServiceProcessRef,
+        location: &PlayerLocation,
+    ) -> CloudResult<()> {
+        let mut player = self
+            .get_or_create_player(&Player::new(0, name.to_string(), *uuid, None))
+            .await?;
+        if player.get_session().is_none() {
+            self.create_session(&mut player, location.get_id()).await?;
+            self.add_event(&player, proxy, &PlayerAction::Join, None)
+                .await?;
+        }
+
+        let mut online = OnlinePlayer::new(player.get_uuid(), player.get_name());
+        online.set_proxy(location.clone());
+        self.players.write().await.insert(player.get_uuid(), online);
+        log_info!(5, "Adopted session of Player |{}|", player.get_name());
+        Ok(())
+    }
+
+    /// The Player has an open session and a Proxy only counts the Players which joined through it
+    async fn has_session_on(&self, player: &Player, service_ref: &ServiceProcessRef) -> bool {
+        if player.get_session().is_none() {
+            return false;
+        }
+        if !service_ref.is_proxy().await {
+            return true;
+        }
+
+        let service_id = service_ref.get_id().await;
+        self.players
+            .read()
+            .await
+            .get(&player.get_uuid())
+            .and_then(|p| p.get_proxy())
+            .is_some_and(|proxy| proxy.get_id() == &service_id)
     }
 
     async fn register_player(&self, player: &Player) -> CloudResult<Player> {
@@ -204,7 +573,7 @@ impl PlayerManager {
         Ok(())
     }
 
-    async fn get_player_by_uuid(&self, uuid: &Uuid) -> CloudResult<Option<Player>> {
+    pub async fn get_player_by_uuid(&self, uuid: &Uuid) -> CloudResult<Option<Player>> {
         Ok(TablePlayers::find_by_uuid(self.get_db(), uuid)
             .await?
             .map(Player::from))
@@ -216,7 +585,6 @@ impl PlayerManager {
             .map(Player::from))
     }
 
-
     async fn create_session(&self, player: &mut Player, service_uuid: &Uuid) -> CloudResult<()> {
         let _ = TablePlayerSessions::delete_by_player_id(self.get_db(), player.get_id()).await;
         let session = TablePlayerSessions::new(player.get_id(), service_uuid);
@@ -272,6 +640,237 @@ impl PlayerManager {
         Ok(())
     }
 
+    pub async fn find_online_by_uuid(&self, uuid: &Uuid) -> Option<OnlinePlayer> {
+        self.players.read().await.get(uuid).cloned()
+    }
+
+    /// Player names are case insensitive in Minecraft
+    pub async fn find_online_by_name(&self, name: &str) -> Option<OnlinePlayer> {
+        self.players
+            .read()
+            .await
+            .values()
+            .find(|p| p.get_name().eq_ignore_ascii_case(name))
+            .cloned()
+    }
+
+    /// sorted by name
+    pub async fn get_online_players(&self) -> Vec<OnlinePlayer> {
+        self.filter_online_players(|_| true).await
+    }
+
+    async fn filter_online_players<F>(&self, filter: F) -> Vec<OnlinePlayer>
+    where
+        F: Fn(&OnlinePlayer) -> bool,
+    {
+        let mut players: Vec<OnlinePlayer> = self
+            .players
+            .read()
+            .await
+            .values()
+            .filter(|p| filter(p))
+            .cloned()
+            .collect();
+        players.sort_by(|a, b| a.get_name().cmp(b.get_name()));
+        players
+    }
+
+    /// The Player must be online on a Proxy of this Node
+    pub async fn get_online_player(&self, name: &str) -> CloudResult<OnlinePlayer> {
+        self.find_online_by_name(name)
+            .await
+            .ok_or(error!(PlayerNotOnline, name))
+    }
+
+    /// The Proxy which holds the session of the Player
+    async fn get_proxy_of(&self, player: &OnlinePlayer) -> CloudResult<ServiceProcessRef> {
+        let proxy = player
+            .get_proxy()
+            .ok_or(error!(PlayerNotOnline, player.get_name()))?;
+        self.service_manager
+            .read()
+            .await
+            .find_from_id(proxy.get_id())
+            .ok_or(error!(ServiceNotFound, proxy.get_name()))
+    }
+
+    /// Move the Player to the Service or to a free Service of the Task with this name.
+    /// Returns the name of the target Service.
+    pub async fn send_player(&self, name: &str, target: &str) -> CloudResult<String> {
+        let player = self.get_online_player(name).await?;
+        let proxy = self.get_proxy_of(&player).await?;
+
+        let target_ref = {
+            let sm = self.service_manager.read().await;
+            match sm.find_from_name(target).await {
+                Some(service_ref) => service_ref,
+                None => {
+                    let task_ref = self
+                        .task_manager
+                        .get_task_ref_from_name(target)
+                        .await
+                        .map_err(|_| error!(ServiceNotFound, target))?;
+                    sm.find_next_free_server_by_task(&task_ref)
+                        .await
+                        .ok_or(error!(InsufficientCapacity, target))?
+                }
+            }
+        };
+
+        {
+            let target = target_ref.read().await;
+            if !target.is_running() || target.is_proxy() {
+                return Err(error!(
+                    InvalidRequest,
+                    format!("{} is not a running Server", target.get_name())
+                ));
+            }
+        }
+
+        self.service_manager
+            .connect_player_to_server(&proxy, &player, &target_ref)
+            .await?;
+        Ok(target_ref.get_name().await)
+    }
+
+    pub async fn kick_player(&self, name: &str, reason: &str) -> CloudResult<()> {
+        let player = self.get_online_player(name).await?;
+        self.kick(&player, reason).await
+    }
+
+    async fn kick(&self, player: &OnlinePlayer, reason: &str) -> CloudResult<()> {
+        let proxy = self.get_proxy_of(player).await?;
+        self.service_manager
+            .kick_player(&proxy, player, reason)
+            .await
+    }
+
+    /// Ban or mute the Player, a banned Player is kicked and the Proxy of a muted Player is told
+    pub async fn punish_player(
+        &self,
+        kind: PunishmentType,
+        player: &Player,
+        reason: &str,
+        issuer: &str,
+        duration: Option<i64>,
+    ) -> CloudResult<Punishment> {
+        let punishment = self
+            .moderation_manager
+            .punish(kind, player, reason, issuer, duration)
+            .await?;
+
+        let result = match (kind, self.find_online_by_uuid(&player.get_uuid()).await) {
+            (_, None) => Ok(()),
+            (PunishmentType::Ban, Some(online)) => {
+                self.kick(&online, &punishment.get_kick_message()).await
+            }
+            (PunishmentType::Mute, Some(online)) => {
+                self.notify_mute(&online, Some(&punishment)).await
+            }
+        };
+        if let Err(e) = result {
+            log_warning!(
+                "Cant notify the Proxy of Player |{}|: {}",
+                player.get_name(),
+                e
+            );
+        }
+        Ok(punishment)
+    }
+
+    /// Returns `false` if the Player has no active punishment of this kind
+    pub async fn pardon_player(&self, kind: PunishmentType, player: &Player) -> CloudResult<bool> {
+        if !self.moderation_manager.pardon(kind, player).await? {
+            return Ok(false);
+        }
+
+        let online = match kind {
+            PunishmentType::Mute => self.find_online_by_uuid(&player.get_uuid()).await,
+            PunishmentType::Ban => None,
+        };
+        if let Some(online) = online {
+            let result = self.notify_mute(&online, None).await;
+            if let Err(e) = result {
+                log_warning!(
+                    "Cant notify the Proxy of Player |{}|: {}",
+                    player.get_name(),
+                    e
+                );
+            }
+        }
+        Ok(true)
+    }
+
+    pub async fn message_player(&self, name: &str, message: &str) -> CloudResult<()> {
+        let player = self.get_online_player(name).await?;
+        let proxy = self.get_proxy_of(&player).await?;
+        self.service_manager
+            .message_player(&proxy, &player, message)
+            .await
+    }
+
+    /// Tell the Proxy of the Player that a mute started (`Some`) or ended (`None`)
+    async fn notify_mute(
+        &self,
+        player: &OnlinePlayer,
+        mute: Option<&Punishment>,
+    ) -> CloudResult<()> {
+        let proxy = self.get_proxy_of(player).await?;
+        self.service_manager
+            .mute_player(&proxy, &PlayerMuteMessage::new(player.get_uuid(), mute))
+            .await
+    }
+
+    /// Returns the names of the Proxies which failed
+    pub async fn broadcast(
+        &self,
+        message: &str,
+        permission: Option<&str>,
+    ) -> CloudResult<Vec<String>> {
+        self.service_manager.broadcast(message, permission).await
+    }
+
+    /// Sessions of the Player rebuilt from `t_player_events`, newest first
+    pub async fn get_player_history(&self, player: &Player) -> CloudResult<PlayerHistory> {
+        let events = TablePlayerEvents::find_by_player_id(self.get_db(), player.get_id()).await?;
+        let online_sessions: BTreeSet<u64> =
+            TablePlayerSessions::find_by_player_id(self.get_db(), player.get_id())
+                .await?
+                .map(|s| s.get_id())
+                .into_iter()
+                .collect();
+
+        let sessions = PlayerAnalytics::sessions(&events, &online_sessions, Utc::now().timestamp());
+        Ok(PlayerHistory::new(sessions))
+    }
+
+    /// Peak Players per Task, unique Players per day and the average session length
+    /// of the last `days` days
+    pub async fn get_player_statistics(&self, days: u32) -> CloudResult<PlayerStatistics> {
+        let now = Utc::now();
+        let since = (now.date_naive() - Duration::days(days.saturating_sub(1) as i64))
+            .and_hms_opt(0, 0, 0)
+            .unwrap_or_default()
+            .and_utc()
+            .timestamp();
+
+        // Players who joined the day before are still counted for the peaks of the first day
+        let events =
+            TablePlayerEvents::find_since(self.get_db(), since - Duration::days(1).num_seconds())
+                .await?;
+        let online_sessions: BTreeSet<u64> = TablePlayerSessions::find_all_ids(self.get_db())
+            .await?
+            .into_iter()
+            .collect();
+
+        Ok(PlayerAnalytics::statistics(
+            &events,
+            &online_sessions,
+            since,
+            now.timestamp(),
+        ))
+    }
+
     fn get_db(&self) -> &DatabaseManager {
         self.db.as_ref()
     }
@@ -282,8 +881,16 @@ impl PlayerManagerRef {
         db: Arc<DatabaseManager>,
         service_manager: ServiceManagerRef,
         task_manager: TaskManagerRef,
+        moderation_manager: Arc<ModerationManager>,
+        maintenance_manager: Arc<MaintenanceManager>,
     ) -> PlayerManagerRef {
-        PlayerManagerRef(Arc::new(RwLock::new(PlayerManager::new(db, service_manager, task_manager))))
+        PlayerManagerRef(Arc::new(RwLock::new(PlayerManager::new(
+            db,
+            service_manager,
+            task_manager,
+            moderation_manager,
+            maintenance_manager,
+        ))))
     }
 
     pub async fn read(&self) -> RwLockReadGuard<'_, PlayerManager> {
@@ -292,7 +899,6 @@ impl PlayerManagerRef {
     pub async fn write(&self) -> RwLockWriteGuard<'_, PlayerManager> {
         self.0.write().await
     }
-
 }
 
 impl Clone for PlayerManagerRef {
@@ -300,4 +906,3 @@ impl Clone for PlayerManagerRef {
         Self(self.0.clone())
     }
 }
-
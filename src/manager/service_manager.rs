@@ -1,31 +1,44 @@
 use bx::network::address::Address;
 use bx::path::Directory;
 use database_manager::DatabaseManager;
+use futures_util::future::join_all;
 use std::collections::HashMap;
 use std::fs;
 use std::fs::read_to_string;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use uuid::Uuid;
 
-use crate::api::internal::{OutgoingMessage, OutgoingMessageType, ServiceInfoResponse};
+use crate::api::internal::{
+    BroadcastMessage, MaintenanceMessage, OutgoingMessage, OutgoingMessageType,
+    PlayerActionMessage, PlayerChatMessage, PlayerKickMessage, PlayerMuteMessage,
+    ServiceInfoResponse,
+};
 use crate::config::{CloudConfig, SoftwareConfigRef};
-use crate::database::table::TableServices;
+use crate::database::table::{TableServiceEvents, TableServices};
 use crate::manager::TaskManagerRef;
 use crate::types::{
-    EntityId, JoinStrategy, Service, ServiceProcess, ServiceProcessRef, ServiceStatus, TaskRef,
+    EntityId, JoinStrategy, OnlinePlayer, PlayerAction, Service, ServiceEvent, ServiceEventFilter,
+    ServiceEventType, ServiceFile, ServiceProcess, ServiceProcessRef, ServiceStatus, TaskRef,
 };
 use crate::utils::error::*;
+use crate::utils::metrics::METRICS;
 use crate::utils::utils::Utils;
 use crate::{error, log_info, log_warning};
 
+/// how long a Proxy may take to apply an action
+const PROXY_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub struct ServiceManager {
     services: HashMap<EntityId, ServiceProcessRef>,
     db: Arc<DatabaseManager>,
     config: Arc<CloudConfig>,
     task_manager: TaskManagerRef,
     software_config: SoftwareConfigRef,
+    /// RoundRobin cursor per Task (Task name -> name of the last joined Service)
+    round_robin_cursors: Mutex<HashMap<String, String>>,
 }
 
 pub struct ServiceManagerRef(Arc<RwLock<ServiceManager>>);
@@ -50,6 +63,8 @@ impl ServiceManager {
         let sp = ServiceProcessRef::new(service, path);
 
         TableServices::create_if_not_exists(self.get_db(), &sp).await?;
+        self.record_event(&sp, ServiceEventType::Created, None)
+            .await;
         self.services.insert(id, sp.clone());
 
         Ok(sp)
@@ -60,8 +75,11 @@ impl ServiceManager {
         task_ref: &TaskRef,
     ) -> CloudResult<ServiceProcessRef> {
         let task_name = task_ref.get_name().await;
+        // a Service waiting for its restart is still owned by the Restart Policy
         let s = self
-            .filter_services(|sp| sp.is_stop() && sp.get_task_name() == task_name)
+            .filter_services(|sp| {
+                sp.is_stop() && !sp.is_restart_pending() && sp.get_task_name() == task_name
+            })
             .await;
         if let Some(sp) = s.first() {
             return Ok(sp.clone());
@@ -91,11 +109,13 @@ impl ServiceManager {
     }
 
     async fn prepare_to_start(&self, service: &ServiceProcessRef) -> CloudResult<()> {
+        let started = Instant::now();
         self.task_manager
             .read()
             .await
             .prepared_to_service(service)
             .await?;
+        METRICS.observe_template_copy(service.read().await.get_task_name(), started.elapsed());
         self.install_software_file(service).await?;
         self.install_system_plugin(service).await?;
         self.install_software_lib(service).await?;
@@ -131,6 +151,8 @@ impl ServiceManager {
                     if let Err(e) = TableServices::delete(self.get_db(), &id).await {
                         log_warning!("Error deleting Service {} in DB: {:?}", id, e);
                     }
+                    self.record_event(service_process_ref, ServiceEventType::Deleted, None)
+                        .await;
                     self.services.remove(&id);
                 } else {
                     self.update_status(service_process_ref, ServiceStatus::Stopped)
@@ -152,74 +174,16 @@ impl ServiceManager {
                 if let Err(e) = TableServices::delete(self.get_db(), &id).await {
                     log_warning!("Error deleting Service {} in DB: {:?}", id, e);
                 }
-                self.services.remove(&id);
-            }
-        }
-    }
-
-    pub async fn register_on_proxy(&self, service: &Service) -> CloudResult<()> {
-        if service.is_proxy() {
-            return Ok(());
-        }
-
-        let service_info_value = serde_json::to_value(ServiceInfoResponse::new(service))
-            .map_err(|e| error!(CantSerializeServiceInfo, e))?;
-        let msg = OutgoingMessage::ok(None, OutgoingMessageType::AddServer, service_info_value);
-
-        for proxy in self
-            .filter_services(|s| s.is_running() && s.is_proxy())
-            .await
-        {
-            let mut sp = proxy.write().await;
-
-            if sp.send(&msg).await {
-                log_info!(
-                    4,
-                    "Connected [{}] to Proxy [{}]",
-                    service.get_name(),
-                    sp.get_name()
-                );
-            } else {
-                log_warning!(
-                    2,
-                    "Can't register [{}] to Proxy [{}]: no session",
-                    service.get_name(),
-                    sp.get_name()
-                );
-            }
-        }
-        Ok(())
-    }
-
-    pub async fn unregister_from_proxy(&self, service: &Service) -> CloudResult<()> {
-        if service.is_proxy() {
-            return Ok(());
-        }
-
-        let service_info_value = serde_json::to_value(ServiceInfoResponse::new(service))
-            .map_err(|e| error!(CantSerializeServiceInfo, e))?;
-        let msg = OutgoingMessage::ok(None, OutgoingMessageType::RemoveServer, service_info_value);
-
-        for proxy in self.filter_services(|s| s.is_proxy() && s.is_start()).await {
-            let mut sp = proxy.write().await;
-
-            if sp.send(&msg).await {
-                log_info!(
-                    4,
-                    "Disconnected [{}] from Proxy [{}]",
-                    service.get_name(),
-                    sp.get_name()
-                );
-            } else {
-                log_warning!(
-                    2,
-                    "Can't unregister [{}] from Proxy [{}]: no session",
-                    service.get_name(),
-                    sp.get_name()
+                let event = TableServiceEvents::new(
+                    sp.get_service(),
+                    ServiceEventType::Deleted,
+                    Some(format!("Task {} not found", task_name)),
                 );
+                drop(sp);
+                self.insert_event(event).await;
+                self.services.remove(&id);
             }
         }
-        Ok(())
     }
 
     pub fn get_from_id(&self, id: &EntityId) -> CloudResult<ServiceProcessRef> {
@@ -289,10 +253,22 @@ impl ServiceManager {
             .map_err(|e| error!(CantCopySoftwareLib, e))
     }
 
+    /// Name of this Node, the parent of all Services in this manager
+    pub fn get_node_name(&self) -> String {
+        self.config.get_name()
+    }
+
     pub fn find_from_id(&self, id: &EntityId) -> Option<ServiceProcessRef> {
         self.services.get(id).cloned()
     }
 
+    pub async fn find_from_name(&self, name: &str) -> Option<ServiceProcessRef> {
+        self.filter_services(|s| s.get_name() == name)
+            .await
+            .into_iter()
+            .next()
+    }
+
     pub async fn filter_services<F>(&self, mut filter: F) -> Vec<ServiceProcessRef>
     where
         F: FnMut(&ServiceProcess) -> bool,
@@ -364,13 +340,61 @@ impl ServiceManager {
         service_process_ref: &ServiceProcessRef,
         status: ServiceStatus,
     ) {
-        let mut sp = service_process_ref.write().await;
-        sp.set_status(status);
-        if let Err(e) = TableServices::update(self.get_db(), sp.get_service()).await {
-            log_warning!(2, "Cant update Service in DB: {}", e);
+        self.update_status_with_event(
+            service_process_ref,
+            status,
+            ServiceEventType::from_status(status),
+            None,
+        )
+        .await;
+    }
+
+    /// Like `update_status`, but store `event_type` instead of the event of the status,
+    /// e.g. a crash sets Failed and only the `crashed` event is stored
+    pub async fn update_status_with_event(
+        &self,
+        service_process_ref: &ServiceProcessRef,
+        status: ServiceStatus,
+        event_type: ServiceEventType,
+        message: Option<String>,
+    ) {
+        let event = {
+            let mut sp = service_process_ref.write().await;
+            sp.set_status(status);
+            if let Err(e) = TableServices::update(self.get_db(), sp.get_service()).await {
+                log_warning!(2, "Cant update Service in DB: {}", e);
+            }
+            TableServiceEvents::new(sp.get_service(), event_type, message)
+        };
+        self.insert_event(event).await;
+    }
+
+    /// Store a lifecycle event of the Service, a failed insert is only logged
+    pub async fn record_event(
+        &self,
+        service_process_ref: &ServiceProcessRef,
+        event_type: ServiceEventType,
+        message: Option<String>,
+    ) {
+        let event = {
+            let sp = service_process_ref.read().await;
+            TableServiceEvents::new(sp.get_service(), event_type, message)
+        };
+        self.insert_event(event).await;
+    }
+
+    async fn insert_event(&self, event: TableServiceEvents) {
+        if let Err(e) = event.create(self.get_db()).await {
+            log_warning!(2, "Cant store Service event in DB: {}", e);
         }
     }
 
+    pub async fn find_events(&self, filter: &ServiceEventFilter) -> CloudResult<Vec<ServiceEvent>> {
+        TableServiceEvents::find(self.get_db(), filter)
+            .await
+            .map_err(|e| error!(CantDBGetRecords, e))
+    }
+
     async fn get_bind_ports_except(&self, exclude: &ServiceProcessRef) -> Vec<u32> {
         let host = self.config.get_server_host();
         let mut ports = Vec::new();
@@ -394,92 +418,116 @@ impl ServiceManager {
         ports
     }
 
-    pub async fn find_next_default_connect_server(&self) -> Option<ServiceProcessRef> {
-        let services = self
-            .filter_services(|service| {
-                service.is_running() && service.get_service().default_connect()
-            })
-            .await;
-
-        if services.is_empty() {
-            return None;
+    /// Walk the fallback groups from the CloudConfig (highest priority first) and return a
+    /// Service of the first group the Player is allowed to join and which has capacity.
+    ///
+    /// Without configured fallback groups all `default_connect` Tasks are used as one group.
+    pub async fn find_fallback_server(&self, permissions: &[String]) -> Option<ServiceProcessRef> {
+        let fallbacks = self.config.get_fallbacks();
+        if fallbacks.is_empty() {
+            return self.find_next_default_connect_server(permissions).await;
         }
 
-        let mut candidates = Vec::new();
-
-        for service in services {
-            let task_name = service.read().await.get_task_name().to_string();
-
-            let strategy = {
-                let task_manager = self.task_manager.read().await;
-
-                let task_ref = match task_manager.get_from_name(&task_name) {
-                    Ok(task) => task,
-                    Err(_) => continue,
-                };
-
-                task_ref.read().await.get_join_strategy().clone()
-            };
+        for fallback in fallbacks {
+            if !fallback.is_allowed(permissions) {
+                continue;
+            }
 
-            candidates.push((service, strategy));
-        }
+            let mut tasks = Vec::new();
+            for task_name in fallback.get_tasks() {
+                match self.task_manager.get_task_ref_from_name(task_name).await {
+                    Ok(task_ref) => tasks.push(task_ref),
+                    Err(_) => log_warning!(
+                        3,
+                        "Task |{}| from Fallback |{}| not found",
+                        task_name,
+                        fallback.get_name()
+                    ),
+                }
+            }
 
-        if candidates.is_empty() {
-            return None;
+            if let Some(service) = self.find_server_in_tasks(tasks, permissions).await {
+                log_info!(
+                    7,
+                    "Fallback |{}| selected Service [{}]",
+                    fallback.get_name(),
+                    service.get_name().await
+                );
+                return Some(service);
+            }
         }
 
-        // gleiche Strategie pro Task anwenden
-        let mut selected = Vec::new();
+        None
+    }
 
-        for (service, strategy) in candidates {
-            match strategy {
-                JoinStrategy::Fullest => {
-                    selected.push(service);
-                }
+    pub async fn find_next_default_connect_server(
+        &self,
+        permissions: &[String],
+    ) -> Option<ServiceProcessRef> {
+        let tasks = {
+            let tm = self.task_manager.read().await;
+            tm.filter_tasks(|t| t.default_connect() && !t.is_maintenance())
+                .await
+        };
 
-                JoinStrategy::Emptiest => {
-                    selected.push(service);
-                }
+        self.find_server_in_tasks(tasks, permissions).await
+    }
 
-                JoinStrategy::Random => {
-                    selected.push(service);
-                }
+    /// Select a Service from every Task with the JoinStrategy of the Task,
+    /// then take the fullest of them to keep Players together.
+    /// Tasks with a `join_permission` the Player doesn't have are skipped.
+    async fn find_server_in_tasks(
+        &self,
+        tasks: Vec<TaskRef>,
+        permissions: &[String],
+    ) -> Option<ServiceProcessRef> {
+        let mut best: Option<(ServiceProcessRef, u32, TaskRef)> = None;
 
-                JoinStrategy::RoundRobin => {
-                    selected.push(service);
-                }
+        for task_ref in tasks {
+            let join_permission = task_ref.read().await.get_join_permission().to_string();
+            if !join_permission.is_empty() && !permissions.contains(&join_permission) {
+                continue;
             }
-        }
-
-        // finale Auswahl
-        //
-        // mehrere Lobby-Typen einführen.
 
-        let mut best: Option<(ServiceProcessRef, u32)> = None;
-
-        for service in selected {
-            let players = {
-                let service = service.read().await;
-                service.get_service().get_current_players()
+            let service = match self.peek_next_free_server_by_task(&task_ref).await {
+                Some(service) => service,
+                None => continue,
             };
 
+            let players = service.read().await.get_current_players();
+
             match &best {
-                Some((_, best_players)) if *best_players >= players => {}
+                Some((_, best_players, _)) if *best_players >= players => {}
                 _ => {
-                    best = Some((service, players));
+                    best = Some((service, players, task_ref));
                 }
             }
         }
 
-        best.map(|(service, _)| service)
+        // only the Task the Player joins moves on, the others offer the same Service next time
+        let (service, _, task_ref) = best?;
+        self.commit_selection(&task_ref, &service).await;
+        Some(service)
     }
 
     pub async fn find_next_free_server_by_task(
         &self,
         task_ref: &TaskRef,
     ) -> Option<ServiceProcessRef> {
+        let service = self.peek_next_free_server_by_task(task_ref).await?;
+        self.commit_selection(task_ref, &service).await;
+        Some(service)
+    }
+
+    /// Like `find_next_free_server_by_task`, but the RoundRobin cursor is not moved
+    async fn peek_next_free_server_by_task(&self, task_ref: &TaskRef) -> Option<ServiceProcessRef> {
         let task = task_ref.read().await;
 
+        // Services of a Task in maintenance get no new Players
+        if task.is_maintenance() {
+            return None;
+        }
+
         let task_name = task.get_name();
         let max_players = task.get_max_players();
         let full_percent = task.get_full_percent();
@@ -497,7 +545,24 @@ impl ServiceManager {
             })
             .await;
 
-        self.select_service(services, strategy).await
+        self.select_service(&task_name, services, strategy).await
+    }
+
+    /// Move the RoundRobin cursor of the Task to the Service the Player joins
+    async fn commit_selection(&self, task_ref: &TaskRef, service: &ServiceProcessRef) {
+        let (task_name, strategy) = {
+            let task = task_ref.read().await;
+            (task.get_name(), task.get_join_strategy().clone())
+        };
+        if strategy != JoinStrategy::RoundRobin {
+            return;
+        }
+
+        let name = service.get_name().await;
+        self.round_robin_cursors
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(task_name, name);
     }
 
     fn get_db(&self) -> &DatabaseManager {
@@ -506,6 +571,7 @@ impl ServiceManager {
 
     async fn select_service(
         &self,
+        task_name: &str,
         services: Vec<ServiceProcessRef>,
         strategy: JoinStrategy,
     ) -> Option<ServiceProcessRef> {
@@ -563,20 +629,38 @@ impl ServiceManager {
             }
 
             JoinStrategy::RoundRobin => {
-                // TODO:
-                // später mit Counter pro Task
-                //
-                // Aktuell:
-                // erster verfügbarer
+                // sorted by name, so the order is stable while Services are added or removed
+                let mut candidates = Vec::new();
+                for service in services {
+                    let name = service.get_name().await;
+                    candidates.push((name, service));
+                }
+                candidates.sort_by(|a, b| a.0.cmp(&b.0));
+
+                // the cursor is the name of the last joined Service, `commit_selection` moves it
+                let cursors = self
+                    .round_robin_cursors
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner());
+
+                let index = match cursors.get(task_name) {
+                    Some(last) => candidates
+                        .iter()
+                        .position(|(name, _)| name > last)
+                        .unwrap_or(0),
+                    None => 0,
+                };
 
-                services.into_iter().next()
+                candidates
+                    .into_iter()
+                    .nth(index)
+                    .map(|(_, service)| service)
             }
         }
     }
 }
 
-fn get_all_from_file() -> Vec<ServiceProcessRef> {
-    let config = CloudConfig::get();
+fn get_all_from_file(config: &CloudConfig) -> Vec<ServiceProcessRef> {
     let mut list = Vec::new();
     list.extend(get_services_from_path(
         &config
@@ -598,12 +682,15 @@ fn get_services_from_path(path: &PathBuf) -> Vec<ServiceProcessRef> {
         .into_iter()
         .filter_map(|folder| {
             let p = path.join(&folder);
-            get_from_path(&p).map(|service| ServiceProcessRef::new(service, p))
+            get_from_path(&p).map(|file| {
+                let (service, secret) = file.into_parts();
+                ServiceProcessRef::with_secret(service, p, secret)
+            })
         })
         .collect()
 }
 
-fn get_from_path(path: &Path) -> Option<Service> {
+fn get_from_path(path: &Path) -> Option<ServiceFile> {
     let p = path.join(".minecloud").join("service_config.json");
     read_to_string(p)
         .ok()
@@ -617,7 +704,7 @@ impl ServiceManagerRef {
         task_manager: TaskManagerRef,
         software_config: SoftwareConfigRef,
     ) -> CloudResult<Self> {
-        let local_services = get_all_from_file();
+        let local_services = get_all_from_file(&cloud_config);
         TableServices::delete_others(db.as_ref(), &local_services, cloud_config.as_ref()).await?;
 
         let mut services: HashMap<EntityId, ServiceProcessRef> = HashMap::new();
@@ -633,6 +720,7 @@ impl ServiceManagerRef {
             config: cloud_config,
             task_manager,
             software_config,
+            round_robin_cursors: Mutex::new(HashMap::new()),
         }))))
     }
 
@@ -646,6 +734,190 @@ impl ServiceManagerRef {
     pub async fn get_service_ref_from_id(&self, id: &EntityId) -> CloudResult<ServiceProcessRef> {
         self.0.read().await.get_from_id(id)
     }
+
+    // A Proxy can take up to PROXY_REQUEST_TIMEOUT to answer, the lock of the ServiceManager
+    // is only held to find the Proxies, never while waiting for them.
+    pub async fn register_on_proxy(&self, service: &Service) -> CloudResult<()> {
+        if service.is_proxy() {
+            return Ok(());
+        }
+
+        let service_info_value = serde_json::to_value(ServiceInfoResponse::new(service))
+            .map_err(|e| error!(CantSerializeServiceInfo, e))?;
+        let msg = OutgoingMessage::ok(None, OutgoingMessageType::AddServer, service_info_value);
+
+        let proxies = self
+            .read()
+            .await
+            .filter_services(|s| s.is_running() && s.is_proxy())
+            .await;
+        let results = join_all(
+            proxies
+                .iter()
+                .map(|proxy| proxy.request(&msg, PROXY_REQUEST_TIMEOUT)),
+        )
+        .await;
+
+        let mut failed = Vec::new();
+        for (proxy, result) in proxies.iter().zip(results) {
+            let proxy_name = proxy.get_name().await;
+            match result {
+                Ok(_) => log_info!(
+                    4,
+                    "Connected [{}] to Proxy [{}]",
+                    service.get_name(),
+                    proxy_name
+                ),
+                Err(e) => {
+                    log_warning!(
+                        2,
+                        "Can't register [{}] to Proxy [{}]: {}",
+                        service.get_name(),
+                        proxy_name,
+                        e
+                    );
+                    failed.push(proxy_name);
+                }
+            }
+        }
+
+        if !failed.is_empty() {
+            return Err(error!(
+                CantRegisterOnProxy,
+                format!("{} -> {}", service.get_name(), failed.join(", "))
+            ));
+        }
+        Ok(())
+    }
+
+    /// Ask the Proxy the Player is connected to to move the Player to the target Service
+    pub async fn connect_player_to_server(
+        &self,
+        proxy: &ServiceProcessRef,
+        player: &OnlinePlayer,
+        target: &ServiceProcessRef,
+    ) -> CloudResult<()> {
+        let action = PlayerActionMessage::new(
+            PlayerAction::SwitchServer,
+            target.get_id().await,
+            target.get_name().await,
+            player.get_uuid(),
+            player.get_name().to_string(),
+        );
+        let data =
+            serde_json::to_value(&action).map_err(|e| error!(CantSerializeOutgoingMsg, e))?;
+        let msg = OutgoingMessage::ok(None, OutgoingMessageType::ConnectPlayerToServer, data);
+
+        proxy.request(&msg, PROXY_REQUEST_TIMEOUT).await?;
+        Ok(())
+    }
+
+    pub async fn kick_player(
+        &self,
+        proxy: &ServiceProcessRef,
+        player: &OnlinePlayer,
+        reason: &str,
+    ) -> CloudResult<()> {
+        let kick = PlayerKickMessage::new(player.get_uuid(), player.get_name(), reason);
+        let data = serde_json::to_value(&kick).map_err(|e| error!(CantSerializeOutgoingMsg, e))?;
+        let msg = OutgoingMessage::ok(None, OutgoingMessageType::KickPlayer, data);
+
+        proxy.request(&msg, PROXY_REQUEST_TIMEOUT).await?;
+        Ok(())
+    }
+
+    pub async fn message_player(
+        &self,
+        proxy: &ServiceProcessRef,
+        player: &OnlinePlayer,
+        message: &str,
+    ) -> CloudResult<()> {
+        let chat = PlayerChatMessage::new(player.get_uuid(), player.get_name(), message);
+        let data = serde_json::to_value(&chat).map_err(|e| error!(CantSerializeOutgoingMsg, e))?;
+        let msg = OutgoingMessage::ok(None, OutgoingMessageType::MessagePlayer, data);
+
+        proxy.request(&msg, PROXY_REQUEST_TIMEOUT).await?;
+        Ok(())
+    }
+
+    pub async fn mute_player(
+        &self,
+        proxy: &ServiceProcessRef,
+        mute: &PlayerMuteMessage,
+    ) -> CloudResult<()> {
+        let data = serde_json::to_value(mute).map_err(|e| error!(CantSerializeOutgoingMsg, e))?;
+        let msg = OutgoingMessage::ok(None, OutgoingMessageType::MutePlayer, data);
+
+        proxy.request(&msg, PROXY_REQUEST_TIMEOUT).await?;
+        Ok(())
+    }
+
+    /// Send the message to all running Proxies, returns the names of the Proxies which failed
+    pub async fn broadcast(
+        &self,
+        message: &str,
+        permission: Option<&str>,
+    ) -> CloudResult<Vec<String>> {
+        let broadcast = BroadcastMessage::new(message, permission);
+        let data =
+            serde_json::to_value(&broadcast).map_err(|e| error!(CantSerializeOutgoingMsg, e))?;
+        let msg = OutgoingMessage::ok(None, OutgoingMessageType::Broadcast, data);
+        Ok(self.request_all_proxies(&msg, "broadcast").await)
+    }
+
+    /// Returns the names of the Proxies which did not get the new state
+    pub async fn push_maintenance(&self, state: &MaintenanceMessage) -> CloudResult<Vec<String>> {
+        let data = serde_json::to_value(state).map_err(|e| error!(CantSerializeOutgoingMsg, e))?;
+        let msg = OutgoingMessage::ok(None, OutgoingMessageType::Maintenance, data);
+        Ok(self.request_all_proxies(&msg, "push maintenance").await)
+    }
+
+    /// Sends the message to every running Proxy and returns the names of those that failed
+    async fn request_all_proxies(&self, msg: &OutgoingMessage, action: &str) -> Vec<String> {
+        let proxies = self
+            .read()
+            .await
+            .filter_services(|s| s.is_running() && s.is_proxy())
+            .await;
+        let results = join_all(
+            proxies
+                .iter()
+                .map(|proxy| proxy.request(msg, PROXY_REQUEST_TIMEOUT)),
+        )
+        .await;
+
+        let mut failed = Vec::new();
+        for (proxy, result) in proxies.iter().zip(results) {
+            if let Err(e) = result {
+                let proxy_name = proxy.get_name().await;
+                log_warning!(2, "Can't {} on Proxy [{}]: {}", action, proxy_name, e);
+                failed.push(proxy_name);
+            }
+        }
+        failed
+    }
+
+    pub async fn unregister_from_proxy(&self, service: &Service) -> CloudResult<()> {
+        if service.is_proxy() {
+            return Ok(());
+        }
+
+        let service_info_value = serde_json::to_value(ServiceInfoResponse::new(service))
+            .map_err(|e| error!(CantSerializeServiceInfo, e))?;
+        let msg = OutgoingMessage::ok(None, OutgoingMessageType::RemoveServer, service_info_value);
+
+        let action = format!("unregister [{}]", service.get_name());
+        let failed = self.request_all_proxies(&msg, &action).await;
+        if !failed.is_empty() {
+            return Err(error!(
+                CantUnregisterFromProxy,
+                format!("{} -> {}", service.get_name(), failed.join(", "))
+            ));
+        }
+
+        log_info!(4, "Disconnected [{}] from all Proxies", service.get_name());
+        Ok(())
+    }
 }
 
 impl Clone for ServiceManagerRef {
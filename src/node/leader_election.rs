@@ -0,0 +1,25 @@
+/// Chooses one Node per Task which makes the scaling decisions.
+///
+/// Every Node computes the same result from the same set of alive Nodes (rendezvous hashing),
+/// so no extra messages are needed. If the leader stops sending heartbeats it is removed
+/// from the registry and the next Node takes over.
+pub struct LeaderElection;
+
+impl LeaderElection {
+    pub fn elect<'a>(task_name: &str, nodes: &[&'a str]) -> Option<&'a str> {
+        nodes
+            .iter()
+            .copied()
+            .max_by_key(|node| (Self::weight(task_name, node), *node))
+    }
+
+    /// FNV-1a, stable across Nodes and Rust versions
+    fn weight(task_name: &str, node: &str) -> u64 {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in task_name.bytes().chain([b'/']).chain(node.bytes()) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        hash
+    }
+}
@@ -1 +1,3 @@
+pub mod leader_election;
+pub mod resource_monitor;
 pub mod scheduler;
@@ -0,0 +1,113 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+use sysinfo::{Disks, Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tokio::sync::RwLock;
+
+use crate::types::{NodeResources, ResourceUsage, ServiceProcessRef};
+
+/// Samples the host and the processes of the local Services with sysinfo.
+///
+/// sysinfo calculates the CPU usage from the difference to the last refresh,
+/// so the first sample of a process always shows 0%.
+pub struct ResourceMonitor {
+    system: Mutex<System>,
+    disks: Mutex<Disks>,
+    /// the disk holding this path is reported as disk of the Node
+    disk_path: PathBuf,
+    node: RwLock<Option<NodeResources>>,
+}
+
+impl ResourceMonitor {
+    pub fn new(disk_path: PathBuf) -> ResourceMonitor {
+        ResourceMonitor {
+            system: Mutex::new(System::new()),
+            disks: Mutex::new(Disks::new_with_refreshed_list()),
+            disk_path: disk_path.canonicalize().unwrap_or(disk_path),
+            node: RwLock::new(None),
+        }
+    }
+
+    /// Store a new sample on every Service, Services without process get `None`
+    pub async fn sample_services(&self, service_refs: &[ServiceProcessRef]) {
+        let mut pids = Vec::new();
+        for service_ref in service_refs {
+            let pid = service_ref.read().await.get_pid().map(Pid::from_u32);
+            pids.push((service_ref.clone(), pid));
+        }
+
+        let usages: Vec<(ServiceProcessRef, Option<ResourceUsage>)> = {
+            let mut system = self.system.lock().unwrap_or_else(|e| e.into_inner());
+            let refresh: Vec<Pid> = pids.iter().filter_map(|(_, pid)| *pid).collect();
+            system.refresh_processes_specifics(
+                ProcessesToUpdate::Some(&refresh),
+                true,
+                ProcessRefreshKind::nothing()
+                    .with_cpu()
+                    .with_memory()
+                    .with_disk_usage(),
+            );
+
+            pids.into_iter()
+                .map(|(service_ref, pid)| {
+                    let usage = pid.and_then(|pid| system.process(pid)).map(|process| {
+                        let disk = process.disk_usage();
+                        ResourceUsage::new(
+                            process.cpu_usage(),
+                            process.memory(),
+                            disk.read_bytes,
+                            disk.written_bytes,
+                        )
+                    });
+                    (service_ref, usage)
+                })
+                .collect()
+        };
+
+        for (service_ref, usage) in usages {
+            service_ref.write().await.set_resource_usage(usage);
+        }
+    }
+
+    /// Sample the host, `services_ram` is the RAM in MB the local Services use
+    pub async fn sample_node(&self, services_ram: u64) -> NodeResources {
+        let (cpu_percent, used_memory, total_memory) = {
+            let mut system = self.system.lock().unwrap_or_else(|e| e.into_inner());
+            system.refresh_cpu_usage();
+            system.refresh_memory();
+            (
+                system.global_cpu_usage(),
+                system.used_memory(),
+                system.total_memory(),
+            )
+        };
+
+        let (disk_available, disk_total) = {
+            let mut disks = self.disks.lock().unwrap_or_else(|e| e.into_inner());
+            disks.refresh(true);
+            // the disk with the longest mount point containing the path
+            disks
+                .list()
+                .iter()
+                .filter(|d| self.disk_path.starts_with(d.mount_point()))
+                .max_by_key(|d| d.mount_point().as_os_str().len())
+                .map(|d| (d.available_space(), d.total_space()))
+                .unwrap_or((0, 0))
+        };
+
+        let resources = NodeResources::new(
+            cpu_percent,
+            used_memory,
+            total_memory,
+            disk_available,
+            disk_total,
+            services_ram,
+        );
+        *self.node.write().await = Some(resources.clone());
+        resources
+    }
+
+    /// Last sample of the host, `None` before the first sample
+    pub async fn get_node_resources(&self) -> Option<NodeResources> {
+        self.node.read().await.clone()
+    }
+}
@@ -1,21 +1,39 @@
+use chrono::Utc;
 use database_manager::DatabaseManager;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, watch};
+use tokio::task::JoinHandle;
 use tokio::time;
-use tokio::time::Instant;
+use tokio::time::{Instant, MissedTickBehavior};
 
 use crate::config::{CloudConfig, SoftwareConfigRef};
 use crate::manager::{NodeManager, TaskManagerRef};
+use crate::types::{Service, Task};
+use crate::utils::error::CloudErrorKind;
+use crate::utils::metrics::METRICS;
 use crate::{log_error, log_info};
 
+/// What the Scheduler does with a Task once `min_service_count` is reached
+#[derive(Debug)]
+enum ScalingDecision<'a> {
+    ScaleUp,
+    /// a Service is needed, but `max_service_count` is reached
+    MaxServicesReached,
+    /// stop the idle Service
+    ScaleDown(&'a Service),
+    Keep,
+}
+
 pub struct Scheduler {
     db: Arc<DatabaseManager>,
     config: Arc<CloudConfig>,
     software_config: SoftwareConfigRef,
     node_manager: Arc<NodeManager>,
     task_manager: TaskManagerRef,
-    last_scale_action: Arc<RwLock<Option<Instant>>>,
+    /// last scale up / scale down per Task name
+    last_scale_action: RwLock<HashMap<String, Instant>>,
 }
 
 impl Scheduler {
@@ -32,18 +50,45 @@ impl Scheduler {
             software_config,
             node_manager,
             task_manager,
-            last_scale_action: Arc::new(RwLock::new(None)),
+            last_scale_action: RwLock::new(HashMap::new()),
         }
     }
 
-    pub async fn run(&mut self) {
-        let mut interval = time::interval(Duration::from_secs(10));
+    /// Start the Scheduler loop as background Task.
+    ///
+    /// Every tick runs in its own Task, so a panic in one tick only gets logged and the
+    /// next tick starts again. The loop ends as soon as `shutdown` is set to `true`,
+    /// a running tick is always finished before.
+    pub fn start(
+        self: Arc<Self>,
+        interval: Duration,
+        mut shutdown: watch::Receiver<bool>,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = time::interval(interval);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = shutdown.changed() => {}
+                }
 
-        loop {
-            interval.tick().await;
-            log_info!(9, "Scheduler Checking...");
-            self.check_service().await;
-        }
+                if *shutdown.borrow() {
+                    break;
+                }
+
+                log_info!(9, "Scheduler Checking...");
+                let scheduler = self.clone();
+                if let Err(e) = tokio::spawn(async move { scheduler.check_service().await }).await {
+                    if e.is_panic() {
+                        log_error!("Scheduler tick panicked, restart with next tick: {}", e);
+                    }
+                }
+            }
+
+            log_info!(3, "Scheduler stopped");
+        })
     }
 
     pub async fn check_service(&self) {
@@ -53,9 +98,9 @@ impl Scheduler {
         };
 
         for task_ref in tasks {
-            let task = task_ref.read().await;
+            let task = task_ref.read().await.clone();
 
-            if !self.node_manager.is_responsible_for_task(&task).await {
+            if !self.node_manager.is_leader_for_task(&task).await {
                 continue;
             }
 
@@ -64,11 +109,16 @@ impl Scheduler {
                 .node_manager
                 .get_all_services_from_task(&task_name)
                 .await;
-            let start_count = services.iter().filter(|s| s.is_start()).count() as u64;
+            // a Service waiting for its restart comes back by itself, it must not be replaced
+            let start_count = services
+                .iter()
+                .filter(|s| s.is_start() || s.is_restart_pending())
+                .count() as u64;
             let stop_count = services.iter().filter(|s| s.is_stop()).count() as u64;
             let failed_count = services.iter().filter(|s| s.is_failed()).count() as u64;
 
             log_info!(
+                9,
                 "Task: {} Services: | Start: {} | Stop: {} | Failed: {}",
                 task_name,
                 start_count,
@@ -80,159 +130,275 @@ impl Scheduler {
             for _ in 0..missing {
                 log_info!("---------------------------------------------------------------");
                 log_info!("Service would be created from Task: [{}]", task_name);
+                if !self.start_service(&task).await {
+                    break;
+                }
+            }
 
-                match self.node_manager.start_service_from_task(&task).await {
-                    Ok(_) => {
-                        log_info!(2, "Server successfully started for Task [{}]", task_name);
-                    }
-                    Err(e) => {
-                        log_error!(
-                            1,
-                            "Service CANT start for Task: [{}]\nError: {}",
-                            task_name,
-                            e
-                        );
-                    }
+            if missing > 0 {
+                continue;
+            }
+
+            self.check_player_scaling_by_task(&task, &services).await;
+        }
+    }
+
+    async fn check_player_scaling_by_task(&self, task: &Task, services: &[Service]) {
+        if self.is_in_cooldown(task).await {
+            return;
+        }
+
+        match Self::decide_scaling(task, services) {
+            ScalingDecision::ScaleUp => {
+                log_info!(
+                    "[Scaling] Starting new Service for Task [{}]",
+                    task.get_name()
+                );
+                if self.start_service(task).await {
+                    self.set_last_scale_action(task, "up").await;
                 }
             }
+            ScalingDecision::MaxServicesReached => {
+                log_info!(
+                    7,
+                    "[Scaling] Task: {} reached max_service_count {}",
+                    task.get_name(),
+                    task.get_max_service_count()
+                );
+            }
+            ScalingDecision::ScaleDown(service) => {
+                log_info!(
+                    "[Scaling] Stopping idle Service [{}] from Task [{}]",
+                    service.get_name(),
+                    task.get_name()
+                );
+                self.node_manager
+                    .stop_service(service.get_id().clone(), "Auto scaling down")
+                    .await;
+                self.set_last_scale_action(task, "down").await;
+            }
+            ScalingDecision::Keep => {}
         }
     }
 
-    /*pub async fn check_player_scaling_all(&self) -> CloudResult<()> {
-        let tasks = {
-            let tm = self.task_manager.read().await;
-            tm.get_all_task()
-        };
+    /// Scale up when fewer than `min_available_services` non-full Services exist,
+    /// a Service lagging below `min_tps` counts as full,
+    /// scale down Services which are idle below `empty_percent` for `idle_timeout_seconds`.
+    fn decide_scaling<'a>(task: &Task, services: &'a [Service]) -> ScalingDecision<'a> {
+        let started: Vec<&Service> = services.iter().filter(|s| s.is_start()).collect();
+        let full_limit = task.get_max_players() * task.get_full_percent() / 100;
+        let empty_limit = task.get_max_players() * task.get_empty_percent() / 100;
+
+        // starting Services count as available, otherwise we would start a new one every tick
+        let available = started
+            .iter()
+            .filter(|s| s.get_current_players() < full_limit && !s.is_lagging(task.get_min_tps()))
+            .count() as u32;
+
+        log_info!(
+            9,
+            "[Scaling] Task: {} | Started: {} | Available: {} / {}",
+            task.get_name(),
+            started.len(),
+            available,
+            task.get_min_available_services()
+        );
+
+        if available < task.get_min_available_services() {
+            return if Self::can_scale_up(task, started.len()) {
+                ScalingDecision::ScaleUp
+            } else {
+                ScalingDecision::MaxServicesReached
+            };
+        }
 
-        for task_ref in tasks {
-            let task = task_ref.read().await;
+        if available > task.get_min_available_services()
+            && started.len() as u64 > task.get_min_service_count()
+        {
+            let idle_timeout = Duration::from_secs(task.get_idle_timeout_seconds() as u64);
 
-            if !self.node_manager.is_responsible_for_task(&task).await {
-                continue;
+            let idle_service = started.iter().find(|s| {
+                s.is_running()
+                    && s.get_current_players() <= empty_limit
+                    && Self::is_idle_longer_than(s, idle_timeout)
+            });
+
+            if let Some(service) = idle_service {
+                return ScalingDecision::ScaleDown(*service);
             }
+        }
+        ScalingDecision::Keep
+    }
 
-            // self.check_player_scaling_by_task(&task).await;
+    /// Returns `false` if no Service could be started
+    async fn start_service(&self, task: &Task) -> bool {
+        match self.node_manager.start_service_from_task(task).await {
+            Ok(_) => {
+                log_info!(
+                    2,
+                    "Server successfully started for Task [{}]",
+                    task.get_name()
+                );
+                true
+            }
+            Err(e) if matches!(e.kind, CloudErrorKind::InsufficientCapacity) => {
+                log_error!(
+                    1,
+                    "Insufficient capacity for Task [{}]: {}",
+                    task.get_name(),
+                    e.source_message.unwrap_or_default()
+                );
+                false
+            }
+            Err(e) => {
+                log_error!(
+                    1,
+                    "Service CANT start for Task: [{}]\nError: {}",
+                    task.get_name(),
+                    e
+                );
+                false
+            }
         }
-        Ok(())
-    }*/
-}
-/*
+    }
 
+    fn can_scale_up(task: &Task, started: usize) -> bool {
+        task.get_max_service_count() < 0 || (started as i64) < task.get_max_service_count() as i64
+    }
 
-async fn check_player_scaling_by_task(&self, task: &Task) {
-    {
-        let last = self.last_scale_action.read().await;
-        if let Some(last_action) = *last {
-            if last_action.elapsed() < Duration::from_secs(5) {
-                return;
+    fn is_idle_longer_than(service: &Service, timeout: Duration) -> bool {
+        match service.get_idle_since() {
+            Some(idle_since) => {
+                let idle = Utc::now().naive_utc() - idle_since;
+                idle.num_seconds() >= timeout.as_secs() as i64
             }
+            None => false,
         }
     }
 
-    let running_services = self.node_manager.get_online_all_from_task(&task.get_name()).await;
+    async fn is_in_cooldown(&self, task: &Task) -> bool {
+        let cooldown = Duration::from_secs(task.get_scale_cooldown_seconds() as u64);
+        match self.last_scale_action.read().await.get(&task.get_name()) {
+            Some(last_action) => last_action.elapsed() < cooldown,
+            None => false,
+        }
+    }
 
-    if running_services.is_empty() {
-        return;
+    /// `direction` is `up` or `down`
+    async fn set_last_scale_action(&self, task: &Task, direction: &str) {
+        METRICS.scaling_action(&task.get_name(), direction);
+        self.last_scale_action
+            .write()
+            .await
+            .insert(task.get_name(), Instant::now());
     }
+}
 
-    let total_players = match TablePlayerSessions::count_players_from_task(
-        self.get_db(),
-        &task.get_name(),
-    )
-        .await
-    {
-        Ok(count) => count,
-        Err(e) => {
-            log_error!("[Scheduler] {}", e.to_string());
-            return;
-        }
-    };
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
 
-    let max_possible_players = running_services.len() as u32 * task.get_max_players();
+    use super::*;
+    use crate::types::{ServiceStatus, SoftwareLink, SoftwareType};
 
-    if max_possible_players == 0 {
-        return;
+    /// 20 max players, full from 17, empty up to 1, 2 available Services
+    fn task() -> Task {
+        // the logger reads the config.json of a Node, tests have none
+        crate::utils::log::logger::Logger::init_log_level(0);
+        let software = SoftwareLink::new(SoftwareType::Backend, "paper".into(), "1.21".into());
+        Task::new("Lobby".to_string(), software, 1024)
     }
 
-    let usage_percent = (total_players * 100) / max_possible_players as u64;
+    fn service(task: &Task, status: ServiceStatus, players: u32) -> Service {
+        let config = Arc::new(CloudConfig::get());
+        let mut service = Service::new(Uuid::new_v4(), "Lobby-1".to_string(), task, &config);
+        service.set_status(status);
+        service.set_current_player(players);
+        if players == 0 {
+            service.start_idle_timer();
+        }
+        service
+    }
 
-    log_info!(
-        3,
-        "[Scaling] Task: {} | Players: {} | Usage: {}%",
-        task.get_name(),
-        total_players,
-        usage_percent
-    );
+    #[test]
+    fn scales_up_when_too_few_services_are_available() {
+        let task = task();
+        let services = vec![
+            service(&task, ServiceStatus::Running, 20),
+            service(&task, ServiceStatus::Running, 5),
+        ];
 
-    if usage_percent >= task.get_percent_of_players_for_a_new_service_by_instance() as u64 {
-        self.scaling_up(task).await;
-    } else {
-        self.scaling_down(&running_services, task, usage_percent).await;
+        let decision = Scheduler::decide_scaling(&task, &services);
+        assert!(matches!(decision, ScalingDecision::ScaleUp));
     }
-}
 
-async fn scaling_up(&self, task: &Task) {
-    let running_services = self.node_manager.get_online_all_from_task(&task.get_name()).await;
+    #[test]
+    fn starting_services_count_as_available() {
+        let task = task();
+        let services = vec![
+            service(&task, ServiceStatus::Starting, 0),
+            service(&task, ServiceStatus::Starting, 0),
+        ];
 
-    if running_services.len() as u64 >= task.get_max_service_count() as u64 {
-        return;
+        let decision = Scheduler::decide_scaling(&task, &services);
+        assert!(matches!(decision, ScalingDecision::Keep));
     }
 
-    log_info!("[Scaling] Starting new service for {}", task.get_name());
+    #[test]
+    fn does_not_scale_up_above_max_service_count() {
+        let mut task = task();
+        task.set_max_service_count(2);
+        let services = vec![
+            service(&task, ServiceStatus::Running, 20),
+            service(&task, ServiceStatus::Running, 5),
+        ];
+
+        let decision = Scheduler::decide_scaling(&task, &services);
+        assert!(matches!(decision, ScalingDecision::MaxServicesReached));
+    }
 
-    // NodeManager kümmert sich um alles
-    match self.node_manager.start_service(task).await {
-        Ok(_) => {
-            *self.last_scale_action.write().await = Some(Instant::now());
-        }
-        Err(e) => {
-            log_error!("Scaling start failed: {}", e);
+    #[test]
+    fn scales_down_a_service_idle_longer_than_the_idle_timeout() {
+        let mut task = task();
+        task.set_idle_timeout_seconds(0);
+        let services = vec![
+            service(&task, ServiceStatus::Running, 5),
+            service(&task, ServiceStatus::Running, 5),
+            service(&task, ServiceStatus::Running, 0),
+        ];
+
+        match Scheduler::decide_scaling(&task, &services) {
+            ScalingDecision::ScaleDown(idle) => assert_eq!(idle.get_id(), services[2].get_id()),
+            decision => panic!("expected ScaleDown, got {:?}", decision),
         }
     }
-}
 
-async fn scaling_down(
-    &self,
-    running_services: &[crate::types::ServiceProcessRef],
-    task: &Task,
-    usage_percent: u64,
-) {
-    if usage_percent
-        < task.get_percent_of_players_to_check_should_auto_stop_the_service() as u64
-        && running_services.len() as u64 > task.get_min_service_count()
-    {
-        // Service mit 0 Spielern finden der lange idle ist
-        for service_ref in running_services {
-            let s = service_ref.read().await;
-            let service = s.get_service();
-
-            if service.get_current_players() == 0 {
-                if let Some(idle_since) = service.get_idle_since() {
-                    let idle_duration = chrono::Utc::now().naive_utc() - idle_since;
-                    if idle_duration.num_seconds() as u64
-                        >= task.get_auto_stop_time_by_unused_service_in_seconds()
-                    {
-                        drop(s); // Lock freigeben vor stop
-                        let id = service_ref.get_id().await;
-
-                        match self.node_manager.stop_service(&id, "Auto scaling down").await {
-                            Ok(_) => {
-                                log_info!("[Scaling] Stopped idle service {}", service.get_name());
-                                *self.last_scale_action.write().await = Some(Instant::now());
-                                break;
-                            }
-                            Err(e) => {
-                                log_error!("Scaling down failed: {}", e);
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    #[test]
+    fn keeps_an_idle_service_until_the_idle_timeout() {
+        let mut task = task();
+        task.set_idle_timeout_seconds(60);
+        let services = vec![
+            service(&task, ServiceStatus::Running, 5),
+            service(&task, ServiceStatus::Running, 5),
+            service(&task, ServiceStatus::Running, 0),
+        ];
+
+        let decision = Scheduler::decide_scaling(&task, &services);
+        assert!(matches!(decision, ScalingDecision::Keep));
     }
-}
 
-fn get_db(&self) -> &DatabaseManager {
-    self.db.as_ref()
+    #[test]
+    fn does_not_scale_down_below_min_service_count() {
+        let mut task = task();
+        task.set_idle_timeout_seconds(0);
+        task.set_min_service_count(3);
+        let services = vec![
+            service(&task, ServiceStatus::Running, 0),
+            service(&task, ServiceStatus::Running, 0),
+            service(&task, ServiceStatus::Running, 0),
+        ];
+
+        let decision = Scheduler::decide_scaling(&task, &services);
+        assert!(matches!(decision, ScalingDecision::Keep));
+    }
 }
-} */
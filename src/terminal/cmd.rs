@@ -6,11 +6,16 @@ use tokio::sync::RwLock;
 
 use crate::cloud::Cloud;
 use crate::log_error;
+use crate::terminal::command::cmd_broadcast::CmdBroadcast;
 use crate::terminal::command::cmd_help::CmdHelp;
-use crate::terminal::command::cmd_me::CmdMe;
+use crate::terminal::command::cmd_maintenance::CmdMaintenance;
+use crate::terminal::command::cmd_player::CmdPlayer;
+use crate::terminal::command::cmd_punishment::{CmdBan, CmdMute};
 use crate::terminal::command::cmd_service::CmdService;
+use crate::terminal::command::cmd_token::CmdToken;
 //use crate::terminal::command::cmd_task::CmdTask;
 use crate::terminal::command::cmd_template::CmdTemplate;
+use crate::terminal::command::cmd_whitelist::CmdWhitelist;
 use crate::terminal::command_manager::CommandManager;
 
 pub struct Cmd {
@@ -75,10 +80,13 @@ impl Cmd {
             "task" => todo!(), //CmdTask::execute(cloud, args).await,
             "service" => CmdService::execute(cloud, args).await,
             "template" => CmdTemplate::execute(cloud, args).await,
-            "me" => match CmdMe::execute(cloud, args).await {
-                Ok(_) => Ok(()),
-                Err(e) => Ok(()),
-            },
+            "token" => CmdToken::execute(cloud, args).await,
+            "player" => CmdPlayer::execute(cloud, args).await,
+            "broadcast" => CmdBroadcast::execute(cloud, args).await,
+            "ban" => CmdBan::execute(cloud, args).await,
+            "mute" => CmdMute::execute(cloud, args).await,
+            "whitelist" => CmdWhitelist::execute(cloud, args).await,
+            "maintenance" => CmdMaintenance::execute(cloud, args).await,
             "reload" => {
                 let scheduler = {
                     let cloud_guard = cloud.read().await;
@@ -108,7 +116,7 @@ fn flush_buffer() {
     }
 }
 
-fn read_from_line() -> String {
+pub(crate) fn read_from_line() -> String {
     let mut input = String::new();
     match io::stdin().read_line(&mut input) {
         Ok(_) => input,
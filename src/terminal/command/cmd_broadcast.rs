@@ -0,0 +1,41 @@
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::cloud::Cloud;
+use crate::terminal::command_manager::CommandManager;
+use crate::{log_info, log_warning};
+
+pub struct CmdBroadcast;
+
+impl CommandManager for CmdBroadcast {
+    // command: broadcast <message>
+    async fn execute(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+        let message = args.get(1..).unwrap_or_default().join(" ");
+        if message.is_empty() {
+            return Err(Error::new(
+                ErrorKind::Other,
+                "bitte gebe eine Nachricht an".to_string(),
+            ));
+        }
+
+        let player_manager = cloud.read().await.get_player_manager();
+        let failed = player_manager
+            .read()
+            .await
+            .broadcast(&message, None)
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+
+        if failed.is_empty() {
+            log_info!("Broadcast an alle Proxies gesendet");
+        } else {
+            log_warning!("Broadcast an {} fehlgeschlagen", failed.join(", "));
+        }
+        Ok(())
+    }
+
+    fn tab_complete(_args: Vec<&str>) -> Vec<String> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,94 @@
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::cloud::Cloud;
+use crate::terminal::command_manager::{CommandManager, complete_subcommand};
+use crate::{log_info, log_warning};
+
+pub struct CmdMaintenance;
+
+impl CommandManager for CmdMaintenance {
+    async fn execute(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+        let arg1 = match args.get(1) {
+            Some(arg1) => *arg1,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::Other,
+                    "bitte gebe ein argument an -> on / off / status / task".to_string(),
+                ));
+            }
+        };
+
+        match arg1 {
+            "on" => set_enabled(cloud, true).await,
+            "off" => set_enabled(cloud, false).await,
+            "status" => status(cloud).await,
+            "task" => task(cloud, args).await,
+            _ => Err(Error::new(
+                ErrorKind::Other,
+                "bitte gebe ein gültiges argument an -> on / off / status / task".to_string(),
+            )),
+        }
+    }
+
+    fn tab_complete(args: Vec<&str>) -> Vec<String> {
+        complete_subcommand(&args, &["on", "off", "status", "task"])
+    }
+}
+
+// command: maintenance on|off
+async fn set_enabled(cloud: Arc<RwLock<Cloud>>, enabled: bool) -> Result<(), Error> {
+    let maintenance_manager = cloud.read().await.get_maintenance_manager();
+    let failed = maintenance_manager
+        .set_enabled(enabled)
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+    warn_failed(&failed);
+    Ok(())
+}
+
+// command: maintenance status
+async fn status(cloud: Arc<RwLock<Cloud>>) -> Result<(), Error> {
+    let maintenance_manager = cloud.read().await.get_maintenance_manager();
+    let state = maintenance_manager.get_state().await;
+
+    log_info!(
+        "Wartungsmodus: {}",
+        if maintenance_manager.is_enabled() {
+            "an"
+        } else {
+            "aus"
+        }
+    );
+    log_info!("Tasks in Wartung: {:?}", state.get_tasks());
+    Ok(())
+}
+
+// command: maintenance task <name> on|off
+async fn task(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+    let (name, enabled) = match (args.get(2), args.get(3)) {
+        (Some(name), Some(&"on")) => (*name, true),
+        (Some(name), Some(&"off")) => (*name, false),
+        _ => {
+            return Err(Error::new(
+                ErrorKind::Other,
+                "maintenance task <name> on|off".to_string(),
+            ));
+        }
+    };
+
+    let maintenance_manager = cloud.read().await.get_maintenance_manager();
+    let failed = maintenance_manager
+        .set_task_maintenance(name, enabled)
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+    warn_failed(&failed);
+    Ok(())
+}
+
+fn warn_failed(failed: &[String]) {
+    if !failed.is_empty() {
+        log_warning!("Proxies ohne neuen Wartungsstatus: {}", failed.join(", "));
+    }
+}
@@ -0,0 +1,274 @@
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::cloud::Cloud;
+use crate::log_info;
+use crate::terminal::command_manager::{CommandManager, complete_subcommand};
+use crate::types::{OnlinePlayer, Player, format_duration};
+
+const HISTORY_DEFAULT_LIMIT: usize = 10;
+const STATS_DEFAULT_DAYS: u32 = 7;
+
+pub struct CmdPlayer;
+
+impl CommandManager for CmdPlayer {
+    async fn execute(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+        let arg1 = match args.get(1) {
+            Some(arg1) => *arg1,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::Other,
+                    "bitte gebe ein argument an -> list / info / history / stats / send / kick / message".to_string(),
+                ));
+            }
+        };
+
+        match arg1 {
+            "list" => list(cloud, args).await,
+            "info" => info(cloud, args).await,
+            "history" => history(cloud, args).await,
+            "stats" => stats(cloud, args).await,
+            "send" => send(cloud, args).await,
+            "kick" => kick(cloud, args).await,
+            "message" => message(cloud, args).await,
+            _ => Err(Error::new(
+                ErrorKind::Other,
+                "bitte gebe ein gültiges argument an -> list / info / history / stats / send / kick / message".to_string(),
+            )),
+        }
+    }
+
+    fn tab_complete(args: Vec<&str>) -> Vec<String> {
+        complete_subcommand(
+            &args,
+            &[
+                "list", "info", "history", "stats", "send", "kick", "message",
+            ],
+        )
+    }
+}
+
+pub(crate) async fn get_player(
+    cloud: &Arc<RwLock<Cloud>>,
+    name: Option<&&str>,
+) -> Result<Player, Error> {
+    let name = match name {
+        Some(name) => *name,
+        None => {
+            return Err(Error::new(
+                ErrorKind::Other,
+                "bitte gebe einen Spieler namen an".to_string(),
+            ));
+        }
+    };
+
+    let player_manager = cloud.read().await.get_player_manager();
+    let player = player_manager
+        .read()
+        .await
+        .find_player_by_name(name)
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+    player.ok_or(Error::new(
+        ErrorKind::Other,
+        format!("Spieler |{}| nicht gefunden", name),
+    ))
+}
+
+fn get_arg<'a>(args: &[&'a str], index: usize, name: &str) -> Result<&'a str, Error> {
+    args.get(index).copied().ok_or(Error::new(
+        ErrorKind::Other,
+        format!("bitte gebe {} an", name),
+    ))
+}
+
+fn parse_number<T: std::str::FromStr>(arg: Option<&&str>, default: T) -> Result<T, Error> {
+    match arg {
+        Some(arg) => arg.parse::<T>().map_err(|_| {
+            Error::new(
+                ErrorKind::Other,
+                format!("|{}| ist keine gültige Anzahl", arg),
+            )
+        }),
+        None => Ok(default),
+    }
+}
+
+// command: player list [service|task]
+async fn list(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+    let node_manager = cloud.read().await.get_node_manager();
+    let all = node_manager.get_network_players().await;
+    let online_count = all.len();
+
+    let players: Vec<OnlinePlayer> = match args.get(2) {
+        Some(filter) => all
+            .into_iter()
+            .filter(|p| p.is_on_service_name(filter) || p.is_on_task(filter))
+            .collect(),
+        None => all,
+    };
+
+    log_info!(
+        "Online Players: {} ({} gesamt)",
+        players.len(),
+        online_count
+    );
+    log_info!("Name | Proxy | Server");
+    for player in players {
+        log_info!("{}", player);
+    }
+    Ok(())
+}
+
+// command: player info <name>
+async fn info(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+    let player = get_player(&cloud, args.get(2)).await?;
+    let node_manager = cloud.read().await.get_node_manager();
+    let online = node_manager
+        .find_network_player(|p| p.get_uuid() == player.get_uuid())
+        .await;
+
+    log_info!("------------>Player Info<------------");
+    log_info!("Name: {}", player.get_name());
+    log_info!("UUID: {}", player.get_uuid());
+    match online {
+        Some(online) => {
+            log_info!("Online since: {}", online.get_online_since());
+            if let Some(proxy) = online.get_proxy() {
+                log_info!("Proxy: {}", proxy.get_name());
+            }
+            if let Some(service) = online.get_service() {
+                log_info!(
+                    "Server: {} (Task: {})",
+                    service.get_name(),
+                    service.get_task()
+                );
+            }
+        }
+        None => log_info!("Offline"),
+    }
+    log_info!("-------------------------------------");
+    Ok(())
+}
+
+// command: player history <name> [limit]
+async fn history(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+    let player = get_player(&cloud, args.get(2)).await?;
+    let limit = parse_number(args.get(3), HISTORY_DEFAULT_LIMIT)?;
+
+    let player_manager = cloud.read().await.get_player_manager();
+    let history = player_manager
+        .read()
+        .await
+        .get_player_history(&player)
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+
+    log_info!("------------>Player History<------------");
+    log_info!("Name: {}", player.get_name());
+    log_info!("Sessions: {}", history.get_sessions().len());
+    log_info!(
+        "Playtime: {}",
+        format_duration(history.get_total_playtime())
+    );
+    for session in history.get_sessions().iter().take(limit) {
+        log_info!("{}", session);
+    }
+    log_info!("----------------------------------------");
+    Ok(())
+}
+
+// command: player stats [days]
+async fn stats(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+    let days = parse_number(args.get(2), STATS_DEFAULT_DAYS)?;
+
+    let player_manager = cloud.read().await.get_player_manager();
+    let statistics = player_manager
+        .read()
+        .await
+        .get_player_statistics(days)
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+
+    log_info!("------------>Player Stats ({} days)<------------", days);
+    match statistics.get_average_session_length() {
+        Some(length) => log_info!("Average Session: {}", format_duration(length)),
+        None => log_info!("Average Session: -"),
+    }
+
+    log_info!("Unique Players | Day | Players");
+    for unique in statistics.get_unique_players() {
+        log_info!("{}", unique);
+    }
+
+    log_info!("Peak Players | Day | Task | Players");
+    for peak in statistics.get_peak_players() {
+        log_info!("{}", peak);
+    }
+    log_info!("------------------------------------------------");
+    Ok(())
+}
+
+// command: player send <name> <service|task>
+async fn send(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+    let name = get_arg(&args, 2, "einen Spieler namen")?;
+    let target = get_arg(&args, 3, "einen Service oder Task")?;
+
+    let player_manager = cloud.read().await.get_player_manager();
+    let service_name = player_manager
+        .read()
+        .await
+        .send_player(name, target)
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+
+    log_info!("Spieler {} wird zu {} gesendet", name, service_name);
+    Ok(())
+}
+
+// command: player kick <name> <reason>
+async fn kick(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+    let name = get_arg(&args, 2, "einen Spieler namen")?;
+    let reason = args.get(3..).unwrap_or_default().join(" ");
+    if reason.is_empty() {
+        return Err(Error::new(
+            ErrorKind::Other,
+            "bitte gebe einen Grund an".to_string(),
+        ));
+    }
+
+    let player_manager = cloud.read().await.get_player_manager();
+    player_manager
+        .read()
+        .await
+        .kick_player(name, &reason)
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+
+    log_info!("Spieler {} wurde gekickt: {}", name, reason);
+    Ok(())
+}
+
+// command: player message <name> <message>
+async fn message(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+    let name = get_arg(&args, 2, "einen Spieler namen")?;
+    let message = args.get(3..).unwrap_or_default().join(" ");
+    if message.is_empty() {
+        return Err(Error::new(
+            ErrorKind::Other,
+            "bitte gebe eine Nachricht an".to_string(),
+        ));
+    }
+
+    let player_manager = cloud.read().await.get_player_manager();
+    player_manager
+        .read()
+        .await
+        .message_player(name, &message)
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+
+    log_info!("Nachricht an {} gesendet", name);
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::cloud::Cloud;
+use crate::log_info;
+use crate::terminal::command::cmd_player::get_player;
+use crate::terminal::command_manager::{CommandManager, complete_subcommand};
+use crate::types::{PunishmentType, parse_punishment_duration};
+
+const ISSUER: &str = "console";
+const SUBCOMMANDS: &[&str] = &["add", "remove", "list", "history"];
+
+pub struct CmdBan;
+
+impl CommandManager for CmdBan {
+    async fn execute(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+        execute(cloud, args, PunishmentType::Ban).await
+    }
+
+    fn tab_complete(args: Vec<&str>) -> Vec<String> {
+        complete_subcommand(&args, SUBCOMMANDS)
+    }
+}
+
+pub struct CmdMute;
+
+impl CommandManager for CmdMute {
+    async fn execute(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+        execute(cloud, args, PunishmentType::Mute).await
+    }
+
+    fn tab_complete(args: Vec<&str>) -> Vec<String> {
+        complete_subcommand(&args, SUBCOMMANDS)
+    }
+}
+
+async fn execute(
+    cloud: Arc<RwLock<Cloud>>,
+    args: Vec<&str>,
+    kind: PunishmentType,
+) -> Result<(), Error> {
+    let arg1 = match args.get(1) {
+        Some(arg1) => *arg1,
+        None => {
+            return Err(Error::new(
+                ErrorKind::Other,
+                "bitte gebe ein argument an -> add / remove / list / history".to_string(),
+            ));
+        }
+    };
+
+    match arg1 {
+        "add" => add(cloud, args, kind).await,
+        "remove" => remove(cloud, args, kind).await,
+        "list" => list(cloud, kind).await,
+        "history" => history(cloud, args).await,
+        _ => Err(Error::new(
+            ErrorKind::Other,
+            "bitte gebe ein gültiges argument an -> add / remove / list / history".to_string(),
+        )),
+    }
+}
+
+// command: ban|mute add <name> [30m|12h|7d|2w] <reason>
+async fn add(
+    cloud: Arc<RwLock<Cloud>>,
+    args: Vec<&str>,
+    kind: PunishmentType,
+) -> Result<(), Error> {
+    let player = get_player(&cloud, args.get(2)).await?;
+
+    // a duration before the reason makes it temporary
+    let duration = args.get(3).and_then(|arg| parse_punishment_duration(arg));
+    let reason_start = if duration.is_some() { 4 } else { 3 };
+    let reason = args.get(reason_start..).unwrap_or_default().join(" ");
+    if reason.is_empty() {
+        return Err(Error::new(
+            ErrorKind::Other,
+            format!("{} add <name> [30m|12h|7d|2w] <reason>", kind),
+        ));
+    }
+
+    let player_manager = cloud.read().await.get_player_manager();
+    let punishment = player_manager
+        .read()
+        .await
+        .punish_player(kind, &player, &reason, ISSUER, duration)
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+
+    log_info!("{}", punishment);
+    Ok(())
+}
+
+// command: ban|mute remove <name>
+async fn remove(
+    cloud: Arc<RwLock<Cloud>>,
+    args: Vec<&str>,
+    kind: PunishmentType,
+) -> Result<(), Error> {
+    let player = get_player(&cloud, args.get(2)).await?;
+
+    let player_manager = cloud.read().await.get_player_manager();
+    let removed = player_manager
+        .read()
+        .await
+        .pardon_player(kind, &player)
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+
+    if removed {
+        log_info!("{} von {} aufgehoben", kind, player.get_name());
+    } else {
+        log_info!("{} hat keinen aktiven {}", player.get_name(), kind);
+    }
+    Ok(())
+}
+
+// command: ban|mute list
+async fn list(cloud: Arc<RwLock<Cloud>>, kind: PunishmentType) -> Result<(), Error> {
+    let moderation_manager = cloud.read().await.get_moderation_manager();
+    let punishments = moderation_manager
+        .get_active_punishments(kind)
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+
+    log_info!("Active {}s: {}", kind, punishments.len());
+    for punishment in punishments {
+        log_info!("{}", punishment);
+    }
+    Ok(())
+}
+
+// command: ban|mute history <name>
+async fn history(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+    let player = get_player(&cloud, args.get(2)).await?;
+
+    let moderation_manager = cloud.read().await.get_moderation_manager();
+    let punishments = moderation_manager
+        .get_punishments_of_player(&player.get_uuid())
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+
+    log_info!(
+        "Bans und Mutes von {}: {}",
+        player.get_name(),
+        punishments.len()
+    );
+    for punishment in punishments {
+        log_info!("{}", punishment);
+    }
+    Ok(())
+}
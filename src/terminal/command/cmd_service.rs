@@ -1,11 +1,19 @@
 use std::io::{Error, ErrorKind};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
+use tokio::time::sleep;
 
 use crate::cloud::Cloud;
-use crate::log_info;
+use crate::terminal::cmd::read_from_line;
 use crate::terminal::command_manager::CommandManager;
-use crate::types::Service;
+use crate::types::{Service, ServiceEventFilter, ServiceProcessRef};
+use crate::{log_info, log_warning};
+
+const CONSOLE_LEAVE: &str = "leave";
+const CONSOLE_TAIL_BYTES: u64 = 4096;
+const CONSOLE_REFRESH: Duration = Duration::from_millis(250);
+const EVENTS_DEFAULT_LIMIT: usize = 20;
 
 pub struct CmdService;
 
@@ -24,9 +32,14 @@ impl CommandManager for CmdService {
         match arg1 {
             "list" => list(cloud.clone(), args).await,
             "reload" => reload(cloud.clone()).await,
+            "console" => console(cloud.clone(), args).await,
+            "cmd" => command(cloud.clone(), args).await,
+            "info" => info(cloud.clone(), args).await,
+            "events" => events(cloud.clone(), args).await,
             _ => Err(Error::new(
                 ErrorKind::Other,
-                "bitte gebe ein gültiges argument an -> list /  an".to_string(),
+                "bitte gebe ein gültiges argument an -> list / console / cmd / info / events an"
+                    .to_string(),
             )),
         }
     }
@@ -35,6 +48,161 @@ impl CommandManager for CmdService {
     }
 }
 
+async fn get_service_ref(
+    cloud: &Arc<RwLock<Cloud>>,
+    name: Option<&&str>,
+) -> Result<ServiceProcessRef, Error> {
+    let name = match name {
+        Some(name) => *name,
+        None => {
+            return Err(Error::new(
+                ErrorKind::Other,
+                "bitte gebe einen Service namen an".to_string(),
+            ));
+        }
+    };
+
+    let service_manager = cloud.read().await.get_node_manager().get_service_manager();
+    let service_ref = service_manager.read().await.find_from_name(name).await;
+    service_ref.ok_or(Error::new(
+        ErrorKind::Other,
+        format!("Service |{}| nicht gefunden", name),
+    ))
+}
+
+// command: service console <name>
+async fn console(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+    let service_ref = get_service_ref(&cloud, args.get(2)).await?;
+    let name = service_ref.get_name().await;
+    let mut offset = service_ref
+        .read()
+        .await
+        .get_console_tail_offset(CONSOLE_TAIL_BYTES);
+
+    log_info!(
+        "Attached to console of |{}|, type '{}' to detach",
+        name,
+        CONSOLE_LEAVE
+    );
+
+    let follower_ref = service_ref.clone();
+    let follower = tokio::spawn(async move {
+        loop {
+            let chunk = { follower_ref.read().await.read_console(offset) };
+            if let Ok(chunk) = chunk {
+                for line in chunk.get_lines() {
+                    println!("{}", line);
+                }
+                offset = chunk.get_offset();
+            }
+            sleep(CONSOLE_REFRESH).await;
+        }
+    });
+
+    loop {
+        let input = read_from_line();
+        let input = input.trim();
+
+        if input == CONSOLE_LEAVE {
+            break;
+        }
+        if input.is_empty() {
+            continue;
+        }
+
+        if let Err(e) = service_ref.send_command(input).await {
+            log_warning!("Cant send command to |{}|: {}", name, e);
+        }
+    }
+
+    follower.abort();
+    log_info!("Detached from console of |{}|", name);
+    Ok(())
+}
+
+// command: service info <name>
+async fn info(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+    let service_ref = get_service_ref(&cloud, args.get(2)).await?;
+    let service = service_ref.read().await.get_service().clone();
+
+    log_info!("------------>Service Info<------------");
+    log_info!("Name: {}", service.get_name());
+    log_info!("Task: {}", service.get_task_name());
+    log_info!("Status: {}", service.get_status());
+    log_info!("Players: {}", service.get_current_players());
+    match service.get_resources() {
+        Some(resources) => log_info!("{}", resources),
+        None => log_info!("Resources: not sampled yet"),
+    }
+    if let Some(tps) = service.get_metrics().get_average_tps() {
+        log_info!("TPS (1 min): {:.2}", tps);
+    }
+
+    let monitor = cloud.read().await.get_node_manager().get_resource_monitor();
+    if let Some(resources) = monitor.get_node_resources().await {
+        log_info!("Node: {}", resources);
+    }
+    log_info!("--------------------------------------");
+    Ok(())
+}
+
+// command: service events <name> [limit]
+async fn events(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+    let service_ref = get_service_ref(&cloud, args.get(2)).await?;
+    let limit = match args.get(3) {
+        Some(limit) => limit.parse::<usize>().map_err(|_| {
+            Error::new(
+                ErrorKind::Other,
+                format!("|{}| ist keine gültige Anzahl", limit),
+            )
+        })?,
+        None => EVENTS_DEFAULT_LIMIT,
+    };
+
+    let filter = ServiceEventFilter::new()
+        .service(service_ref.get_id().await)
+        .limit(limit);
+    let service_manager = cloud.read().await.get_node_manager().get_service_manager();
+    let events = service_manager
+        .read()
+        .await
+        .find_events(&filter)
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+
+    log_info!(
+        "Events of |{}| (newest first):",
+        service_ref.get_name().await
+    );
+    for event in events {
+        log_info!("{}", event);
+    }
+    Ok(())
+}
+
+// command: service cmd <name> <command...>
+async fn command(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+    let service_ref = get_service_ref(&cloud, args.get(2)).await?;
+
+    let command = args
+        .iter()
+        .skip(3)
+        .copied()
+        .collect::<Vec<&str>>()
+        .join(" ");
+    if command.is_empty() {
+        return Err(Error::new(
+            ErrorKind::Other,
+            "bitte gebe einen command an".to_string(),
+        ));
+    }
+
+    service_ref
+        .send_command(&command)
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))
+}
+
 async fn reload(_cloud: Arc<RwLock<Cloud>>) -> Result<(), Error> {
     /*
     let services = cloud.lock().await.get_all().get_all().await;
@@ -0,0 +1,124 @@
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::cloud::Cloud;
+use crate::log_info;
+use crate::terminal::command_manager::{CommandManager, complete_subcommand};
+use crate::types::ApiScope;
+
+pub struct CmdToken;
+
+impl CommandManager for CmdToken {
+    async fn execute(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+        let arg1 = match args.get(1) {
+            Some(arg1) => *arg1,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::Other,
+                    "bitte gebe ein argument an -> create / revoke / list".to_string(),
+                ));
+            }
+        };
+
+        match arg1 {
+            "create" => create(cloud, args).await,
+            "revoke" => revoke(cloud, args).await,
+            "list" => list(cloud).await,
+            _ => Err(Error::new(
+                ErrorKind::Other,
+                "bitte gebe ein gültiges argument an -> create / revoke / list".to_string(),
+            )),
+        }
+    }
+
+    fn tab_complete(args: Vec<&str>) -> Vec<String> {
+        complete_subcommand(&args, &["create", "revoke", "list"])
+    }
+}
+
+// command: token create <name> <scope,scope...>
+async fn create(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+    let (name, scopes) = match (args.get(2), args.get(3)) {
+        (Some(name), Some(scopes)) => (*name, *scopes),
+        _ => {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!(
+                    "token create <name> <scopes> -> scopes: {}",
+                    ApiScope::join(&ApiScope::all())
+                ),
+            ));
+        }
+    };
+
+    let mut parsed = Vec::new();
+    for scope in scopes.split(',') {
+        match ApiScope::parse(scope) {
+            Some(scope) => parsed.push(scope),
+            None => {
+                return Err(Error::new(
+                    ErrorKind::Other,
+                    format!("Unbekannter scope |{}|", scope),
+                ));
+            }
+        }
+    }
+
+    let token_manager = cloud.read().await.get_api_token_manager();
+    let token = token_manager
+        .create_token(name, &parsed)
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+
+    log_info!("Token |{}| erstellt, er wird nur einmal angezeigt:", name);
+    log_info!("{}", token);
+    Ok(())
+}
+
+// command: token revoke <name>
+async fn revoke(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+    let name = match args.get(2) {
+        Some(name) => *name,
+        None => {
+            return Err(Error::new(
+                ErrorKind::Other,
+                "bitte gebe einen Token namen an".to_string(),
+            ));
+        }
+    };
+
+    let token_manager = cloud.read().await.get_api_token_manager();
+    let revoked = token_manager
+        .revoke_token(name)
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+
+    if !revoked {
+        return Err(Error::new(
+            ErrorKind::Other,
+            format!("Token |{}| nicht gefunden", name),
+        ));
+    }
+    log_info!("Token |{}| wurde widerrufen", name);
+    Ok(())
+}
+
+async fn list(cloud: Arc<RwLock<Cloud>>) -> Result<(), Error> {
+    let token_manager = cloud.read().await.get_api_token_manager();
+    let tokens = token_manager
+        .get_tokens()
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+
+    log_info!("Dies sind alle aktiven Tokens:");
+    log_info!("Name | Scopes");
+    for token in tokens {
+        log_info!(
+            "{} | {}",
+            token.get_name(),
+            ApiScope::join(token.get_scopes())
+        );
+    }
+    Ok(())
+}
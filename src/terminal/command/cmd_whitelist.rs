@@ -0,0 +1,104 @@
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::cloud::Cloud;
+use crate::log_info;
+use crate::terminal::command::cmd_player::get_player;
+use crate::terminal::command_manager::{CommandManager, complete_subcommand};
+
+const ISSUER: &str = "console";
+
+pub struct CmdWhitelist;
+
+impl CommandManager for CmdWhitelist {
+    async fn execute(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+        let arg1 = match args.get(1) {
+            Some(arg1) => *arg1,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::Other,
+                    "bitte gebe ein argument an -> add / remove / list / on / off".to_string(),
+                ));
+            }
+        };
+
+        match arg1 {
+            "add" => add(cloud, args).await,
+            "remove" => remove(cloud, args).await,
+            "list" => list(cloud).await,
+            "on" => set_enabled(cloud, true).await,
+            "off" => set_enabled(cloud, false).await,
+            _ => Err(Error::new(
+                ErrorKind::Other,
+                "bitte gebe ein gültiges argument an -> add / remove / list / on / off".to_string(),
+            )),
+        }
+    }
+
+    fn tab_complete(args: Vec<&str>) -> Vec<String> {
+        complete_subcommand(&args, &["add", "remove", "list", "on", "off"])
+    }
+}
+
+// command: whitelist add <name>
+async fn add(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+    let player = get_player(&cloud, args.get(2)).await?;
+
+    let moderation_manager = cloud.read().await.get_moderation_manager();
+    moderation_manager
+        .add_to_whitelist(&player, ISSUER)
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+
+    log_info!("{} ist jetzt auf der Whitelist", player.get_name());
+    Ok(())
+}
+
+// command: whitelist remove <name>
+async fn remove(cloud: Arc<RwLock<Cloud>>, args: Vec<&str>) -> Result<(), Error> {
+    let player = get_player(&cloud, args.get(2)).await?;
+
+    let moderation_manager = cloud.read().await.get_moderation_manager();
+    let removed = moderation_manager
+        .remove_from_whitelist(&player)
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+
+    if removed {
+        log_info!("{} wurde von der Whitelist entfernt", player.get_name());
+    } else {
+        log_info!("{} ist nicht auf der Whitelist", player.get_name());
+    }
+    Ok(())
+}
+
+// command: whitelist list
+async fn list(cloud: Arc<RwLock<Cloud>>) -> Result<(), Error> {
+    let moderation_manager = cloud.read().await.get_moderation_manager();
+    let entries = moderation_manager
+        .get_whitelist()
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+
+    log_info!(
+        "Whitelist ({}): {}",
+        if moderation_manager.is_whitelist_enabled() {
+            "an"
+        } else {
+            "aus"
+        },
+        entries.len()
+    );
+    for entry in entries {
+        log_info!("{}", entry);
+    }
+    Ok(())
+}
+
+// command: whitelist on|off
+async fn set_enabled(cloud: Arc<RwLock<Cloud>>, enabled: bool) -> Result<(), Error> {
+    let moderation_manager = cloud.read().await.get_moderation_manager();
+    moderation_manager.set_whitelist_enabled(enabled);
+    Ok(())
+}
@@ -11,3 +11,16 @@ pub trait CommandManager {
     ) -> impl std::future::Future<Output = Result<(), Error>>;
     fn tab_complete(args: Vec<&str>) -> Vec<String>;
 }
+
+/// The subcommands which start with the typed second argument
+pub fn complete_subcommand(args: &[&str], subcommands: &[&str]) -> Vec<String> {
+    if args.len() > 2 {
+        return Vec::new();
+    }
+    let typed = args.get(1).copied().unwrap_or_default();
+    subcommands
+        .iter()
+        .filter(|s| s.starts_with(typed))
+        .map(|s| s.to_string())
+        .collect()
+}
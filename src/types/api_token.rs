@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// What an API Token is allowed to do
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ApiScope {
+    /// read everything
+    #[serde(rename = "read_only")]
+    ReadOnly,
+
+    /// start / stop Services, console and commands
+    #[serde(rename = "service_control")]
+    ServiceControl,
+
+    /// create / update / delete Tasks
+    #[serde(rename = "task_admin")]
+    TaskAdmin,
+
+    /// change Players
+    #[serde(rename = "player_admin")]
+    PlayerAdmin,
+}
+
+impl ApiScope {
+    pub fn all() -> [ApiScope; 4] {
+        [
+            ApiScope::ReadOnly,
+            ApiScope::ServiceControl,
+            ApiScope::TaskAdmin,
+            ApiScope::PlayerAdmin,
+        ]
+    }
+
+    pub fn parse(value: &str) -> Option<ApiScope> {
+        ApiScope::all()
+            .into_iter()
+            .find(|s| s.to_string() == value.trim())
+    }
+
+    /// unknown scopes are ignored
+    pub fn parse_list(value: &str) -> Vec<ApiScope> {
+        value.split(',').filter_map(ApiScope::parse).collect()
+    }
+
+    pub fn join(scopes: &[ApiScope]) -> String {
+        scopes
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+}
+
+impl fmt::Display for ApiScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match self {
+            ApiScope::ReadOnly => "read_only",
+            ApiScope::ServiceControl => "service_control",
+            ApiScope::TaskAdmin => "task_admin",
+            ApiScope::PlayerAdmin => "player_admin",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+/// A Token for the REST API, the secret itself is never stored
+#[derive(Serialize, Clone, Debug)]
+pub struct ApiToken {
+    id: u64,
+    name: String,
+    scopes: Vec<ApiScope>,
+}
+
+impl ApiToken {
+    pub fn new(id: u64, name: String, scopes: Vec<ApiScope>) -> ApiToken {
+        ApiToken { id, name, scopes }
+    }
+
+    pub fn get_id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_scopes(&self) -> &Vec<ApiScope> {
+        &self.scopes
+    }
+
+    /// every scope includes `ReadOnly`
+    pub fn has_scope(&self, scope: ApiScope) -> bool {
+        (scope == ApiScope::ReadOnly && !self.scopes.is_empty()) || self.scopes.contains(&scope)
+    }
+}
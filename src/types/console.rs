@@ -0,0 +1,23 @@
+use serde::Serialize;
+
+/// Lines read from the stdout log of a Service
+#[derive(Serialize, Debug, Clone)]
+pub struct ConsoleChunk {
+    offset: u64,
+    lines: Vec<String>,
+}
+
+impl ConsoleChunk {
+    pub fn new(offset: u64, lines: Vec<String>) -> ConsoleChunk {
+        ConsoleChunk { offset, lines }
+    }
+
+    /// Offset for the next read
+    pub fn get_offset(&self) -> u64 {
+        self.offset
+    }
+
+    pub fn get_lines(&self) -> &Vec<String> {
+        &self.lines
+    }
+}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+/// A named group of Tasks a Player is sent to when joining the Network.
+///
+/// Groups are checked by `priority` (highest first), the first group the
+/// Player is allowed to join and which has a free Service is used.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Fallback {
+    /// Name of the group.
+    ///
+    /// Example: `"premium-lobby"`, `"lobby"`, `"limbo"`
+    name: String,
+
+    /// Groups with a higher priority are checked first.
+    priority: u32,
+
+    /// Permission required to be sent to this group.
+    ///
+    /// Empty string means no permission is required.
+    #[serde(default)]
+    permission: String,
+
+    /// Names of the Tasks in this group.
+    tasks: Vec<String>,
+}
+
+impl Fallback {
+    pub fn new(name: String, priority: u32, permission: String, tasks: Vec<String>) -> Fallback {
+        Fallback {
+            name,
+            priority,
+            permission,
+            tasks,
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_priority(&self) -> u32 {
+        self.priority
+    }
+
+    pub fn get_permission(&self) -> &str {
+        &self.permission
+    }
+
+    pub fn get_tasks(&self) -> &Vec<String> {
+        &self.tasks
+    }
+
+    pub fn is_allowed(&self, permissions: &[String]) -> bool {
+        self.permission.is_empty() || permissions.iter().any(|p| *p == self.permission)
+    }
+}
@@ -5,29 +5,53 @@ use std::path::Path;
 use strum_macros::EnumIter;
 use uuid::Uuid;
 
+pub use api_token::*;
+pub use console::*;
+pub use fallback::*;
 pub use group::*;
 pub use installer::*;
 pub use join_strategy::*;
 pub use node::*;
+pub use online_player::*;
+pub use placement_policy::*;
 pub use player::*;
+pub use player_stats::*;
 pub use process::*;
+pub use punishment::*;
+pub use resource_usage::*;
+pub use restart_policy::*;
 pub use service::*;
 pub use service_config::*;
+pub use service_crash::*;
+pub use service_event::*;
+pub use service_metrics::*;
 pub use software_link::*;
 pub use task::*;
 pub use template::*;
 
+mod api_token;
+mod console;
+mod fallback;
 mod group;
 mod installer;
 mod node;
+mod online_player;
+mod placement_policy;
 mod task;
 mod template;
 
 mod join_strategy;
 mod player;
+mod player_stats;
 mod process;
+mod punishment;
+mod resource_usage;
+mod restart_policy;
 mod service;
 mod service_config;
+mod service_crash;
+mod service_event;
+mod service_metrics;
 mod software_link;
 
 /// EntityId for Service
@@ -84,6 +108,10 @@ pub enum ServiceStatus {
     #[serde(rename = "running")]
     Running,
 
+    /// Running, but the plugin missed its last heartbeat
+    #[serde(rename = "degraded")]
+    Degraded,
+
     #[serde(rename = "stopping")]
     Stopping,
 
@@ -97,6 +125,7 @@ impl fmt::Display for ServiceStatus {
             ServiceStatus::Failed => "failed",
             ServiceStatus::Starting => "starting",
             ServiceStatus::Running => "running",
+            ServiceStatus::Degraded => "degraded",
             ServiceStatus::Stopping => "stopping",
             ServiceStatus::Stopped => "stopped",
         };
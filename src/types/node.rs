@@ -1,14 +1,92 @@
 use bx::network::address::Address;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::time::Instant;
 use uuid::Uuid;
 
+use crate::config::CloudConfig;
+use crate::types::NodeResources;
+
+/// A Node in the Cluster
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Node {
     name: String,
+
+    /// changes with every start of the Node
     uuid: Uuid,
+
+    /// address of the internal API of the Node
     host: Address,
+
+    /// RAM in MB the Node may use for Services
+    #[serde(default)]
+    max_ram: u64,
+
+    /// Last sample of the host, sent with every heartbeat
+    #[serde(default)]
+    resources: Option<NodeResources>,
+
+    #[serde(skip)]
+    last_seen: Option<Instant>,
 }
 
 impl Node {
-    fn new() {
-        print!("noide etst");
+    pub fn new(name: String, uuid: Uuid, host: Address, max_ram: u64) -> Node {
+        Node {
+            name,
+            uuid,
+            host,
+            max_ram,
+            resources: None,
+            last_seen: None,
+        }
+    }
+
+    pub fn from_config(config: &CloudConfig, uuid: Uuid) -> Node {
+        Node::new(
+            config.get_name(),
+            uuid,
+            config.get_node_host(),
+            config.get_max_ram(),
+        )
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_uuid(&self) -> &Uuid {
+        &self.uuid
+    }
+
+    pub fn get_host(&self) -> &Address {
+        &self.host
+    }
+
+    pub fn get_max_ram(&self) -> u64 {
+        self.max_ram
+    }
+
+    pub fn get_resources(&self) -> Option<&NodeResources> {
+        self.resources.as_ref()
+    }
+
+    pub fn set_resources(&mut self, resources: Option<NodeResources>) {
+        self.resources = resources;
+    }
+
+    pub fn get_last_seen(&self) -> Option<Instant> {
+        self.last_seen
+    }
+
+    pub fn touch(&mut self) {
+        self.last_seen = Some(Instant::now());
+    }
+
+    pub fn is_alive(&self, timeout: Duration) -> bool {
+        match self.last_seen {
+            Some(last_seen) => last_seen.elapsed() < timeout,
+            None => false,
+        }
     }
 }
@@ -0,0 +1,130 @@
+use chrono::{NaiveDateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+use crate::types::Service;
+
+/// A Service a Player is connected to
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PlayerLocation {
+    #[serde(rename = "id")]
+    id: Uuid,
+
+    #[serde(rename = "name")]
+    name: String,
+
+    #[serde(rename = "task")]
+    task: String,
+}
+
+impl PlayerLocation {
+    pub fn new(service: &Service) -> PlayerLocation {
+        PlayerLocation {
+            id: *service.get_id(),
+            name: service.get_name().to_string(),
+            task: service.get_task_name().to_string(),
+        }
+    }
+
+    pub fn get_id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_task(&self) -> &str {
+        &self.task
+    }
+}
+
+/// A Player connected to a Proxy of this Node, kept by the `PlayerManager`.
+/// The other Nodes send theirs over the Cluster API
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct OnlinePlayer {
+    #[serde(rename = "uuid")]
+    uuid: Uuid,
+
+    #[serde(rename = "name")]
+    name: String,
+
+    #[serde(rename = "proxy")]
+    proxy: Option<PlayerLocation>,
+
+    /// the backend Server, `None` while switching
+    #[serde(rename = "service")]
+    service: Option<PlayerLocation>,
+
+    /// format -> YYYY-MM-DD HH:MM:SS
+    #[serde(rename = "online_since")]
+    #[schemars(with = "String")]
+    online_since: NaiveDateTime,
+}
+
+impl OnlinePlayer {
+    pub fn new(uuid: Uuid, name: &str) -> OnlinePlayer {
+        OnlinePlayer {
+            uuid,
+            name: name.to_string(),
+            proxy: None,
+            service: None,
+            online_since: Utc::now().naive_utc(),
+        }
+    }
+
+    pub fn get_uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_proxy(&self) -> Option<&PlayerLocation> {
+        self.proxy.as_ref()
+    }
+
+    pub fn get_service(&self) -> Option<&PlayerLocation> {
+        self.service.as_ref()
+    }
+
+    pub fn get_online_since(&self) -> NaiveDateTime {
+        self.online_since
+    }
+
+    pub fn set_proxy(&mut self, proxy: PlayerLocation) {
+        self.proxy = Some(proxy);
+    }
+
+    pub fn set_service(&mut self, service: Option<PlayerLocation>) {
+        self.service = service;
+    }
+
+    /// `true` if the Player is on the Service, as Proxy or as backend Server
+    pub fn is_on_service(&self, id: &Uuid) -> bool {
+        self.proxy.as_ref().is_some_and(|p| p.id == *id)
+            || self.service.as_ref().is_some_and(|s| s.id == *id)
+    }
+
+    /// like `is_on_service`, by the name of the Service
+    pub fn is_on_service_name(&self, name: &str) -> bool {
+        self.proxy.as_ref().is_some_and(|p| p.name == name)
+            || self.service.as_ref().is_some_and(|s| s.name == name)
+    }
+
+    pub fn is_on_task(&self, task: &str) -> bool {
+        self.proxy.as_ref().is_some_and(|p| p.task == task)
+            || self.service.as_ref().is_some_and(|s| s.task == task)
+    }
+}
+
+impl fmt::Display for OnlinePlayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let proxy = self.proxy.as_ref().map(|p| p.get_name()).unwrap_or("-");
+        let service = self.service.as_ref().map(|s| s.get_name()).unwrap_or("-");
+        write!(f, "{} | {} | {}", self.name, proxy, service)
+    }
+}
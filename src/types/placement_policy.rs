@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// How the Node for a new Service is chosen
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub enum PlacementPolicy {
+    /// Node with the most free RAM
+    #[default]
+    #[serde(rename = "least_loaded")]
+    LeastLoaded,
+
+    /// Node with the least free RAM that still fits the Service
+    #[serde(rename = "bin_packing")]
+    BinPacking,
+
+    /// Node with the fewest Services of the Task, then the most free RAM
+    #[serde(rename = "spread_by_task")]
+    SpreadByTask,
+}
+
+/// RAM usage of one Node while searching a place for a new Service
+#[derive(Clone, Debug)]
+pub struct NodeLoad {
+    name: String,
+    max_ram: u64,
+    used_ram: u64,
+    task_services: usize,
+    /// RAM in MB still free on the host, `None` if the Node was not sampled yet
+    host_free_ram: Option<u64>,
+}
+
+impl NodeLoad {
+    pub fn new(name: &str, max_ram: u64) -> NodeLoad {
+        NodeLoad {
+            name: name.to_string(),
+            max_ram,
+            used_ram: 0,
+            task_services: 0,
+            host_free_ram: None,
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_max_ram(&self) -> u64 {
+        self.max_ram
+    }
+
+    pub fn get_used_ram(&self) -> u64 {
+        self.used_ram
+    }
+
+    pub fn get_free_ram(&self) -> u64 {
+        self.max_ram.saturating_sub(self.used_ram)
+    }
+
+    pub fn add_service(&mut self, ram: u64, same_task: bool) {
+        self.add_ram(ram);
+        if same_task {
+            self.add_task_service();
+        }
+    }
+
+    pub fn add_ram(&mut self, ram: u64) {
+        self.used_ram += ram;
+    }
+
+    pub fn add_task_service(&mut self) {
+        self.task_services += 1;
+    }
+
+    pub fn set_host_free_ram(&mut self, ram: u64) {
+        self.host_free_ram = Some(ram);
+    }
+
+    pub fn can_fit(&self, ram: u64) -> bool {
+        self.used_ram + ram <= self.max_ram && self.host_free_ram.is_none_or(|free| ram <= free)
+    }
+}
+
+impl PlacementPolicy {
+    /// Select the Node for a Service which needs `required_ram`, `None` if no Node can fit it
+    pub fn select<'a>(&self, nodes: &'a [NodeLoad], required_ram: u64) -> Option<&'a NodeLoad> {
+        let mut candidates: Vec<&NodeLoad> =
+            nodes.iter().filter(|n| n.can_fit(required_ram)).collect();
+        // stable result for equal Nodes
+        candidates.sort_by(|a, b| a.name.cmp(&b.name));
+
+        match self {
+            PlacementPolicy::LeastLoaded => candidates
+                .into_iter()
+                .min_by_key(|n| std::cmp::Reverse(n.get_free_ram())),
+            PlacementPolicy::BinPacking => candidates.into_iter().min_by_key(|n| n.get_free_ram()),
+            PlacementPolicy::SpreadByTask => candidates
+                .into_iter()
+                .min_by_key(|n| (n.task_services, std::cmp::Reverse(n.get_free_ram()))),
+        }
+    }
+}
+
+impl fmt::Display for PlacementPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match self {
+            PlacementPolicy::LeastLoaded => "least_loaded",
+            PlacementPolicy::BinPacking => "bin_packing",
+            PlacementPolicy::SpreadByTask => "spread_by_task",
+        };
+        write!(f, "{}", value)
+    }
+}
@@ -0,0 +1,320 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+use crate::types::PlayerAction;
+
+/// A row of `t_player_events` with the columns the analytics need
+#[derive(Clone, Debug)]
+pub struct PlayerEventRecord {
+    id: u64,
+    player_id: u64,
+    session_id: Option<u64>,
+    task: String,
+    action: String,
+    /// unix seconds
+    timestamp: i64,
+}
+
+impl PlayerEventRecord {
+    pub fn new(
+        id: u64,
+        player_id: u64,
+        session_id: Option<u64>,
+        task: String,
+        action: String,
+        timestamp: i64,
+    ) -> PlayerEventRecord {
+        PlayerEventRecord {
+            id,
+            player_id,
+            session_id,
+            task,
+            action,
+            timestamp,
+        }
+    }
+
+    pub fn get_task(&self) -> &str {
+        &self.task
+    }
+
+    fn is_join(&self) -> bool {
+        self.action == PlayerAction::Join.to_string()
+    }
+
+    fn is_leave(&self) -> bool {
+        self.action == PlayerAction::Leave.to_string()
+    }
+
+    fn day(&self) -> NaiveDate {
+        to_datetime(self.timestamp).date()
+    }
+}
+
+/// One visit of a Player, from the proxy join to the proxy leave
+#[derive(Serialize, Clone, Debug)]
+pub struct PlayerSessionHistory {
+    session_id: u64,
+    started_at: NaiveDateTime,
+    /// `None` while the Player is online
+    ended_at: Option<NaiveDateTime>,
+    /// seconds
+    duration: i64,
+    /// Tasks visited in this session, in order
+    tasks: Vec<String>,
+}
+
+impl PlayerSessionHistory {
+    pub fn is_open(&self) -> bool {
+        self.ended_at.is_none()
+    }
+}
+
+impl fmt::Display for PlayerSessionHistory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ended_at = match self.ended_at {
+            Some(ended_at) => ended_at.to_string(),
+            None => String::from("online"),
+        };
+        write!(
+            f,
+            "#{} {} - {} ({}) | {}",
+            self.session_id,
+            self.started_at,
+            ended_at,
+            format_duration(self.duration),
+            self.tasks.join(" -> ")
+        )
+    }
+}
+
+/// Session history and playtime of one Player
+#[derive(Serialize, Clone, Debug)]
+pub struct PlayerHistory {
+    sessions: Vec<PlayerSessionHistory>,
+    /// seconds
+    total_playtime: i64,
+}
+
+impl PlayerHistory {
+    pub fn new(sessions: Vec<PlayerSessionHistory>) -> PlayerHistory {
+        let total_playtime = sessions.iter().map(|s| s.duration).sum();
+        PlayerHistory {
+            sessions,
+            total_playtime,
+        }
+    }
+
+    /// newest first
+    pub fn get_sessions(&self) -> &Vec<PlayerSessionHistory> {
+        &self.sessions
+    }
+
+    pub fn get_total_playtime(&self) -> i64 {
+        self.total_playtime
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TaskPeak {
+    day: NaiveDate,
+    task: String,
+    peak: u32,
+}
+
+impl fmt::Display for TaskPeak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} | {} | {}", self.day, self.task, self.peak)
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DailyUniquePlayers {
+    day: NaiveDate,
+    players: u64,
+}
+
+impl fmt::Display for DailyUniquePlayers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} | {}", self.day, self.players)
+    }
+}
+
+/// Network wide numbers since a point in time
+#[derive(Serialize, Clone, Debug)]
+pub struct PlayerStatistics {
+    peak_players: Vec<TaskPeak>,
+    unique_players: Vec<DailyUniquePlayers>,
+    /// seconds, `None` without finished sessions
+    average_session_length: Option<i64>,
+}
+
+impl PlayerStatistics {
+    pub fn get_peak_players(&self) -> &Vec<TaskPeak> {
+        &self.peak_players
+    }
+
+    pub fn get_unique_players(&self) -> &Vec<DailyUniquePlayers> {
+        &self.unique_players
+    }
+
+    pub fn get_average_session_length(&self) -> Option<i64> {
+        self.average_session_length
+    }
+}
+
+/// Rebuilds sessions and counts from the Player events.
+///
+/// The events are the only history, `t_player_sessions` only holds the sessions
+/// of the Players which are online right now.
+pub struct PlayerAnalytics;
+
+impl PlayerAnalytics {
+    /// Sessions of the events, newest first.
+    ///
+    /// A session ends with its last `Leave`. A session without one is only open
+    /// if it is in `online_sessions`, otherwise the Node lost it (crash) and it
+    /// ends with its last event.
+    pub fn sessions(
+        events: &[PlayerEventRecord],
+        online_sessions: &BTreeSet<u64>,
+        now: i64,
+    ) -> Vec<PlayerSessionHistory> {
+        let mut by_session: BTreeMap<u64, Vec<&PlayerEventRecord>> = BTreeMap::new();
+        for event in events {
+            if let Some(session_id) = event.session_id {
+                by_session.entry(session_id).or_default().push(event);
+            }
+        }
+
+        let mut sessions = Vec::new();
+        for (session_id, mut events) in by_session {
+            events.sort_by_key(|e| (e.timestamp, e.id));
+            let (first, last) = match (events.first(), events.last()) {
+                (Some(first), Some(last)) => (*first, *last),
+                _ => continue,
+            };
+
+            let mut tasks: Vec<String> = Vec::new();
+            for event in events.iter().filter(|e| e.is_join()) {
+                if tasks.last() != Some(&event.task) {
+                    tasks.push(event.task.clone());
+                }
+            }
+
+            let is_open = !last.is_leave() && online_sessions.contains(&session_id);
+            let end = if is_open { now } else { last.timestamp };
+
+            sessions.push(PlayerSessionHistory {
+                session_id,
+                started_at: to_datetime(first.timestamp),
+                ended_at: (!is_open).then(|| to_datetime(last.timestamp)),
+                duration: end.saturating_sub(first.timestamp).max(0),
+                tasks,
+            });
+        }
+
+        sessions.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        sessions
+    }
+
+    pub fn statistics(
+        events: &[PlayerEventRecord],
+        online_sessions: &BTreeSet<u64>,
+        since: i64,
+        now: i64,
+    ) -> PlayerStatistics {
+        let finished: Vec<i64> = Self::sessions(events, online_sessions, now)
+            .into_iter()
+            .filter(|s| !s.is_open() && s.started_at >= to_datetime(since))
+            .map(|s| s.duration)
+            .collect();
+
+        PlayerStatistics {
+            peak_players: Self::peak_players_per_task(events, since),
+            unique_players: Self::unique_players_per_day(events, since),
+            average_session_length: if finished.is_empty() {
+                None
+            } else {
+                Some(finished.iter().sum::<i64>() / finished.len() as i64)
+            },
+        }
+    }
+
+    /// Highest number of Players on the Services of a Task at the same time, per day.
+    ///
+    /// All given events are replayed, so Players who joined before `since` are counted too.
+    pub fn peak_players_per_task(events: &[PlayerEventRecord], since: i64) -> Vec<TaskPeak> {
+        let mut sorted: Vec<&PlayerEventRecord> = events.iter().collect();
+        sorted.sort_by_key(|e| (e.timestamp, e.id));
+
+        let mut current: HashMap<&str, u32> = HashMap::new();
+        let mut peaks: BTreeMap<(NaiveDate, &str), u32> = BTreeMap::new();
+
+        for event in sorted {
+            let count = current.entry(event.task.as_str()).or_insert(0);
+            if event.is_join() {
+                *count += 1;
+            } else if event.is_leave() {
+                // events from before the first recorded join can't go below zero
+                *count = count.saturating_sub(1);
+            }
+
+            if event.timestamp < since {
+                continue;
+            }
+            let peak = peaks.entry((event.day(), event.task.as_str())).or_insert(0);
+            *peak = (*peak).max(*count);
+        }
+
+        peaks
+            .into_iter()
+            .map(|((day, task), peak)| TaskPeak {
+                day,
+                task: task.to_string(),
+                peak,
+            })
+            .collect()
+    }
+
+    pub fn unique_players_per_day(
+        events: &[PlayerEventRecord],
+        since: i64,
+    ) -> Vec<DailyUniquePlayers> {
+        let mut players: BTreeMap<NaiveDate, BTreeSet<u64>> = BTreeMap::new();
+        for event in events.iter().filter(|e| e.timestamp >= since) {
+            players
+                .entry(event.day())
+                .or_default()
+                .insert(event.player_id);
+        }
+
+        players
+            .into_iter()
+            .map(|(day, players)| DailyUniquePlayers {
+                day,
+                players: players.len() as u64,
+            })
+            .collect()
+    }
+}
+
+/// `1h 5m 3s`
+pub fn format_duration(seconds: i64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+    if hours > 0 {
+        format!("{}h {}m {}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+fn to_datetime(timestamp: i64) -> NaiveDateTime {
+    DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .naive_utc()
+}
@@ -1,7 +1,13 @@
-use crate::api::internal::{OutgoingMessage, OutgoingMessageType};
+use crate::api::internal::{
+    CAPABILITY_REQUEST_RESPONSE, MIN_PROTOCOL_VERSION, OutgoingMessage, OutgoingMessageType,
+    PluginHeartbeat,
+};
 use crate::config::Software;
 use crate::types::service::Service;
-use crate::types::{EntityId, ServiceConfig, ServiceStatus};
+use crate::types::{
+    ConsoleChunk, EntityId, MetricsWindow, ResourceUsage, ServiceConfig, ServiceCrash,
+    ServiceMetrics, ServiceStatus,
+};
 use crate::utils::error::*;
 use crate::utils::utils::Utils;
 use crate::{error, log_error, log_info, log_warning};
@@ -10,28 +16,63 @@ use bx::network::address::Address;
 use bx::network::url::{Url, UrlSchema};
 use chrono::NaiveDateTime;
 use delegate::delegate;
-use serde_json::json;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::fs;
-use std::fs::File;
-use std::io::Write;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::PathBuf;
-use std::process::Stdio;
+use std::process::{ExitStatus, Stdio};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::io;
+use tokio::io::AsyncWriteExt;
 use tokio::process::{Child, ChildStdin, Command};
-use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
-use tokio::time::{Instant, sleep};
+use tokio::sync::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard, oneshot};
+use tokio::time::{Instant, sleep, timeout};
 use uuid::Uuid;
 
+const CRASH_STDERR_TAIL_LINES: usize = 20;
+
 pub struct ServiceProcess {
     service: Service,
     path: PathBuf,
     shutdown_initiated_by_cloud: bool,
     process: Option<Child>,
     session: Option<Session>,
-    stdin: Option<ChildStdin>,
+    /// shared, so a command is written without the lock of the process
+    stdin: Option<Arc<Mutex<ChildStdin>>>,
+    restart_count: u32,
+    crashes: Vec<ServiceCrash>,
+    /// the plugin has to send this secret in the Auth message
+    secret: String,
+    /// id of the attached connection
+    session_id: Option<Uuid>,
+    /// requests sent over the session which wait for a response
+    pending_requests: HashMap<Uuid, oneshot::Sender<CloudResult<Value>>>,
+    /// negotiated in the Auth handshake
+    protocol_version: u32,
+    capabilities: Vec<String>,
+    /// last answer to a ping, or the Auth of the plugin
+    last_heartbeat: Option<Instant>,
+    plugin_heartbeat: Option<PluginHeartbeat>,
+}
+
+/// Content of `.minecloud/service_config.json`, read by the plugin
+#[derive(Serialize, Deserialize)]
+pub struct ServiceFile {
+    #[serde(flatten)]
+    service: Service,
+
+    #[serde(default)]
+    secret: String,
+}
+
+impl ServiceFile {
+    pub fn into_parts(self) -> (Service, String) {
+        (self.service, self.secret)
+    }
 }
 
 pub struct ServiceProcessRef(Arc<RwLock<ServiceProcess>>);
@@ -71,8 +112,9 @@ impl ServiceProcess {
             .spawn()
             .map_err(|e| error!(CantStartServer, e))?;
 
-        self.stdin = child.stdin.take();
+        self.stdin = child.stdin.take().map(|stdin| Arc::new(Mutex::new(stdin)));
         self.process = Some(child);
+        self.shutdown_initiated_by_cloud = false;
 
         Ok(())
     }
@@ -94,6 +136,14 @@ impl ServiceProcess {
         }
     }
 
+    /// Kill the process without taking it, the watcher of the Service handles the exit
+    pub fn start_kill(&mut self) -> io::Result<()> {
+        match &mut self.process {
+            Some(child) => child.start_kill(),
+            None => Ok(()),
+        }
+    }
+
     pub async fn kill(&mut self) -> io::Result<()> {
         if let Some(mut child) = self.process.take() {
             child.kill().await?;
@@ -102,21 +152,200 @@ impl ServiceProcess {
         Ok(())
     }
 
-    pub fn attach_session(&mut self, session: Session) {
+    /// Read all complete lines from `server_stdout.log` starting at `offset`.
+    ///
+    /// Returns the lines and the offset for the next call. If the file was recreated
+    /// (Service restarted) it is read from the beginning again.
+    pub fn read_console(&self, offset: u64) -> CloudResult<ConsoleChunk> {
+        let mut file =
+            File::open(self.get_path_stdout_file()).map_err(|e| error!(CantReadSTDOUTFile, e))?;
+        let len = file
+            .metadata()
+            .map_err(|e| error!(CantReadSTDOUTFile, e))?
+            .len();
+
+        let offset = if offset > len { 0 } else { offset };
+        file.seek(SeekFrom::Start(offset))
+            .map_err(|e| error!(CantReadSTDOUTFile, e))?;
+
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)
+            .map_err(|e| error!(CantReadSTDOUTFile, e))?;
+
+        // only return complete lines, the rest is read with the next call
+        let complete = match buf.iter().rposition(|b| *b == b'\n') {
+            Some(pos) => pos + 1,
+            None => 0,
+        };
+
+        let lines = String::from_utf8_lossy(&buf[..complete])
+            .lines()
+            .map(String::from)
+            .collect();
+
+        Ok(ConsoleChunk::new(offset + complete as u64, lines))
+    }
+
+    /// Offset to start reading the console with the last `bytes` of the stdout log
+    pub fn get_console_tail_offset(&self, bytes: u64) -> u64 {
+        match fs::metadata(self.get_path_stdout_file()) {
+            Ok(meta) => meta.len().saturating_sub(bytes),
+            Err(_) => 0,
+        }
+    }
+
+    pub fn get_pid(&self) -> Option<u32> {
+        self.process.as_ref().and_then(|child| child.id())
+    }
+
+    pub fn has_process(&self) -> bool {
+        self.process.is_some()
+    }
+
+    /// Check without blocking if the process has exited.
+    /// On exit the process, stdin and session are released.
+    pub fn try_wait_exit(&mut self) -> io::Result<Option<ExitStatus>> {
+        let status = match self.process.as_mut() {
+            Some(child) => child.try_wait()?,
+            None => return Ok(None),
+        };
+
+        if status.is_some() {
+            self.process = None;
+            self.stdin = None;
+            self.session = None;
+        }
+        Ok(status)
+    }
+
+    /// Records a crash with the tail of `server_stderr.log` and appends it to the crash report file
+    pub fn record_crash(&mut self, exit_code: Option<i32>) -> ServiceCrash {
+        let crash = ServiceCrash::new(exit_code, self.read_stderr_tail(CRASH_STDERR_TAIL_LINES));
+
+        match OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.get_path_crash_report_file())
+        {
+            Ok(mut file) => {
+                if let Err(e) = write!(file, "{}", crash) {
+                    log_warning!(
+                        3,
+                        "Cant write crash report for [{}]: {}",
+                        self.get_name(),
+                        e
+                    );
+                }
+            }
+            Err(e) => log_warning!(3, "Cant open crash report for [{}]: {}", self.get_name(), e),
+        }
+
+        self.crashes.push(crash.clone());
+        crash
+    }
+
+    pub fn get_crashes(&self) -> &Vec<ServiceCrash> {
+        &self.crashes
+    }
+
+    pub fn get_restart_count(&self) -> u32 {
+        self.restart_count
+    }
+
+    pub fn increment_restart_count(&mut self) -> u32 {
+        self.restart_count += 1;
+        self.restart_count
+    }
+
+    pub fn reset_restart_count(&mut self) {
+        self.restart_count = 0;
+    }
+
+    fn read_stderr_tail(&self, lines: usize) -> Vec<String> {
+        match fs::read_to_string(self.get_path_stderr_file()) {
+            Ok(content) => {
+                let mut tail: Vec<String> = content
+                    .lines()
+                    .rev()
+                    .take(lines)
+                    .map(String::from)
+                    .collect();
+                tail.reverse();
+                tail
+            }
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Returns the id of the connection, needed to detach it again
+    pub fn attach_session(
+        &mut self,
+        session: Session,
+        protocol_version: u32,
+        capabilities: Vec<String>,
+    ) -> Uuid {
+        let session_id = Uuid::new_v4();
         self.session = Some(session);
+        self.session_id = Some(session_id);
+        self.protocol_version = protocol_version;
+        self.capabilities = capabilities;
+        self.last_heartbeat = Some(Instant::now());
+        session_id
+    }
+
+    /// Only detach if no newer connection was attached in the meantime
+    pub fn detach_session(&mut self, session_id: &Uuid) {
+        if self.session_id.as_ref() == Some(session_id) {
+            self.session = None;
+            self.session_id = None;
+            // dropping the senders fails all waiting requests
+            self.pending_requests.clear();
+        }
+    }
+
+    pub fn record_heartbeat(&mut self, heartbeat: PluginHeartbeat) {
+        self.last_heartbeat = Some(Instant::now());
+        self.plugin_heartbeat = Some(heartbeat);
+    }
+
+    /// Time since the plugin answered the last time, `None` if it never connected
+    pub fn get_time_since_heartbeat(&self) -> Option<Duration> {
+        self.last_heartbeat.map(|t| t.elapsed())
     }
 
-    pub fn detach_session(&mut self) {
-        self.session = None;
+    pub fn get_plugin_heartbeat(&self) -> Option<&PluginHeartbeat> {
+        self.plugin_heartbeat.as_ref()
+    }
+
+    pub fn get_protocol_version(&self) -> u32 {
+        self.protocol_version
+    }
+
+    /// Capabilities the plugin sent in the Auth handshake
+    pub fn has_capability(&self, capability: &str) -> bool {
+        self.capabilities.iter().any(|c| c == capability)
+    }
+
+    pub fn verify_secret(&self, secret: &str) -> bool {
+        Utils::constant_time_eq(&self.secret, secret)
     }
 
     pub async fn send(&mut self, msg: &OutgoingMessage) -> bool {
         if let Some(session) = &mut self.session {
+            let msg = msg.clone().for_protocol(self.protocol_version);
             return session.text(msg.to_string()).await.is_ok();
         }
         false
     }
 
+    /// Called by the WS handler when a `response` or `error` message arrives
+    pub fn resolve_request(&mut self, request_id: &Uuid, result: CloudResult<Value>) -> bool {
+        match self.pending_requests.remove(request_id) {
+            Some(sender) => sender.send(result).is_ok(),
+            None => false,
+        }
+    }
+
     pub fn has_session(&self) -> bool {
         self.session.is_some()
     }
@@ -189,6 +418,9 @@ impl ServiceProcess {
         self.get_path_with_service_config()
             .join("server_stderr.log")
     }
+    pub fn get_path_crash_report_file(&self) -> PathBuf {
+        self.get_path_with_service_config().join("crash_report.log")
+    }
 
     pub fn set_status(&mut self, status: ServiceStatus) {
         self.service.set_status(status);
@@ -201,7 +433,11 @@ impl ServiceProcess {
             log_error!("Can't create service config dir");
             return;
         }
-        if let Ok(serialized) = serde_json::to_string_pretty(self.get_service()) {
+        let file = ServiceFile {
+            service: self.get_service().clone(),
+            secret: self.secret.clone(),
+        };
+        if let Ok(serialized) = serde_json::to_string_pretty(&file) {
             if let Ok(mut file) = File::create(self.get_path_with_service_file()) {
                 file.write_all(serialized.as_bytes())
                     .expect("Error saving service config");
@@ -230,24 +466,42 @@ impl ServiceProcess {
             pub fn get_cloud_listener(&self) -> &Address;
             pub fn get_task_name(&self) -> &str;
             pub fn get_config(&self) -> &ServiceConfig;
+            pub fn get_metrics(&self) -> &MetricsWindow;
+            pub fn get_resources(&self) -> Option<&ResourceUsage>;
             pub fn is_proxy(&self) -> bool;
             pub fn is_backend_server(&self) -> bool;
             pub fn is_start(&self) -> bool;
             pub fn is_running(&self) -> bool;
+            pub fn is_degraded(&self) -> bool;
             pub fn is_stop(&self) -> bool;
+            pub fn is_restart_pending(&self) -> bool;
             pub fn is_local_node(&self, node_name: &str) -> bool;
 
             pub fn set_server_listener(&mut self, address: Address);
             pub fn set_plugin_listener(&mut self, address: Address);
             pub fn set_cloud_listener(&mut self, address: Address);
             pub fn set_current_player(&mut self, count: u32);
+            pub fn set_restart_pending(&mut self, pending: bool);
+            pub fn add_metrics(&mut self, metrics: ServiceMetrics);
+            pub fn set_resource_usage(&mut self, usage: Option<ResourceUsage>);
             pub fn start_idle_timer(&mut self);
+            pub fn stop_idle_timer(&mut self);
         }
     }
 }
 
 impl ServiceProcessRef {
     pub fn new(service: Service, path: PathBuf) -> Self {
+        let secret = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        Self::with_secret(service, path, secret)
+    }
+
+    /// a Service loaded from its folder keeps the secret the plugin already knows
+    pub fn with_secret(service: Service, path: PathBuf, secret: String) -> Self {
+        if secret.is_empty() {
+            return Self::new(service, path);
+        }
+
         Self(Arc::new(RwLock::new(ServiceProcess {
             service,
             path,
@@ -255,6 +509,15 @@ impl ServiceProcessRef {
             process: None,
             session: None,
             stdin: None,
+            restart_count: 0,
+            crashes: Vec::new(),
+            secret,
+            session_id: None,
+            pending_requests: HashMap::new(),
+            protocol_version: MIN_PROTOCOL_VERSION,
+            capabilities: Vec::new(),
+            last_heartbeat: None,
+            plugin_heartbeat: None,
         })))
     }
 
@@ -274,6 +537,80 @@ impl ServiceProcessRef {
     pub async fn get_name(&self) -> String {
         self.0.read().await.get_name().to_string()
     }
+
+    /// Send a command line to the server console and write it to `server_stdin.log`.
+    ///
+    /// The lock of the process is only held to get the stdin, a slow console
+    /// does not block the Service.
+    pub async fn send_command(&self, command: &str) -> CloudResult<()> {
+        let (stdin, stdin_file, name) = {
+            let sp = self.0.read().await;
+            let stdin = sp.stdin.clone().ok_or(error!(ServiceHasNoProcess))?;
+            (stdin, sp.get_path_stdin_file(), sp.get_name().to_string())
+        };
+
+        // keep the lock while logging, so the log has the order of the console
+        let mut stdin = stdin.lock().await;
+        stdin
+            .write_all(format!("{}\n", command).as_bytes())
+            .await
+            .map_err(|e| error!(CantWriteSTDIN, e))?;
+        stdin.flush().await.map_err(|e| error!(CantWriteSTDIN, e))?;
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(stdin_file)
+            .map_err(|e| error!(CantWriteFile, e))?;
+        writeln!(file, "[{}] {}", Utils::get_datetime_now(), command)
+            .map_err(|e| error!(CantWriteFile, e))?;
+
+        log_info!(6, "[{}] Console << {}", name, command);
+        Ok(())
+    }
+
+    /// Send the message and wait for the matching response of the Service.
+    ///
+    /// The lock is only held while sending, so the WS handler can resolve the
+    /// response in the meantime. Plugins without `request_response` never answer,
+    /// they only get the message and the result is `null`.
+    pub async fn request(&self, msg: &OutgoingMessage, wait: Duration) -> CloudResult<Value> {
+        let request_id = Uuid::new_v4();
+        let mut msg = msg.clone();
+        msg.set_request_id(Some(request_id));
+
+        let (tx, rx) = oneshot::channel();
+        {
+            let mut sp = self.0.write().await;
+            if !sp.has_session() {
+                return Err(error!(ServiceHasNoSession, sp.get_name()));
+            }
+            if !sp.has_capability(CAPABILITY_REQUEST_RESPONSE) {
+                if !sp.send(&msg).await {
+                    return Err(error!(CantSendWSAnswer, sp.get_name()));
+                }
+                return Ok(Value::Null);
+            }
+            sp.pending_requests.insert(request_id, tx);
+            if !sp.send(&msg).await {
+                sp.pending_requests.remove(&request_id);
+                return Err(error!(CantSendWSAnswer, sp.get_name()));
+            }
+        }
+
+        match timeout(wait, rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(error!(RequestFailed, "Connection closed")),
+            Err(_) => {
+                let mut sp = self.0.write().await;
+                sp.pending_requests.remove(&request_id);
+                Err(error!(
+                    RequestTimeout,
+                    format!("{} ({:?})", sp.get_name(), msg.get_msg_type())
+                ))
+            }
+        }
+    }
     pub async fn is_start(&self) -> bool {
         self.0.read().await.is_start()
     }
@@ -0,0 +1,247 @@
+use chrono::{DateTime, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+/// Permission which lets a Player join while the whitelist is enabled
+pub const WHITELIST_BYPASS_PERMISSION: &str = "cloud.whitelist.bypass";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PunishmentType {
+    /// the Player can not join the Network
+    #[serde(rename = "ban")]
+    Ban,
+
+    /// the Player can not write in the chat, enforced by the Proxy
+    #[serde(rename = "mute")]
+    Mute,
+}
+
+impl PunishmentType {
+    pub fn parse(value: &str) -> Option<PunishmentType> {
+        match value.trim().to_lowercase().as_str() {
+            "ban" => Some(PunishmentType::Ban),
+            "mute" => Some(PunishmentType::Mute),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PunishmentType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match self {
+            PunishmentType::Ban => "ban",
+            PunishmentType::Mute => "mute",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+/// A ban or mute of a Player, stored in `t_player_punishments`
+#[derive(Serialize, Clone, Debug)]
+pub struct Punishment {
+    id: u64,
+    kind: PunishmentType,
+    player_uuid: Uuid,
+    player_name: String,
+    reason: String,
+    /// who issued it, a Token name or `console`
+    issuer: String,
+    created_at: NaiveDateTime,
+    /// `None` for permanent punishments
+    expires_at: Option<NaiveDateTime>,
+    revoked: bool,
+}
+
+impl Punishment {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: u64,
+        kind: PunishmentType,
+        player_uuid: Uuid,
+        player_name: String,
+        reason: String,
+        issuer: String,
+        created_at: i64,
+        expires_at: Option<i64>,
+        revoked: bool,
+    ) -> Punishment {
+        Punishment {
+            id,
+            kind,
+            player_uuid,
+            player_name,
+            reason,
+            issuer,
+            created_at: to_datetime(created_at),
+            expires_at: expires_at.map(to_datetime),
+            revoked,
+        }
+    }
+
+    pub fn get_kind(&self) -> PunishmentType {
+        self.kind
+    }
+
+    pub fn get_reason(&self) -> &str {
+        &self.reason
+    }
+
+    pub fn get_expires_at(&self) -> Option<NaiveDateTime> {
+        self.expires_at
+    }
+
+    /// Not revoked and not expired at `now` (unix seconds)
+    pub fn is_active(&self, now: i64) -> bool {
+        !self.revoked
+            && self
+                .expires_at
+                .is_none_or(|expires_at| now < expires_at.and_utc().timestamp())
+    }
+
+    /// Shown to the Player when the Proxy denies the join
+    pub fn get_kick_message(&self) -> String {
+        let until = match self.expires_at {
+            Some(expires_at) => expires_at.to_string(),
+            None => String::from("permanent"),
+        };
+        format!(
+            "Du bist vom Netzwerk gebannt\nGrund: {}\nBis: {}",
+            self.reason, until
+        )
+    }
+}
+
+impl fmt::Display for Punishment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let until = match self.expires_at {
+            Some(expires_at) => expires_at.to_string(),
+            None => String::from("permanent"),
+        };
+        write!(
+            f,
+            "#{} {} {} | {} | von {} | {} - {}",
+            self.id, self.kind, self.player_name, self.reason, self.issuer, self.created_at, until
+        )?;
+        if self.revoked {
+            write!(f, " (aufgehoben)")?;
+        }
+        Ok(())
+    }
+}
+
+/// A Player on the network whitelist
+#[derive(Serialize, Clone, Debug)]
+pub struct WhitelistEntry {
+    player_uuid: Uuid,
+    player_name: String,
+    issuer: String,
+    created_at: NaiveDateTime,
+}
+
+impl WhitelistEntry {
+    pub fn new(
+        player_uuid: Uuid,
+        player_name: String,
+        issuer: String,
+        created_at: i64,
+    ) -> WhitelistEntry {
+        WhitelistEntry {
+            player_uuid,
+            player_name,
+            issuer,
+            created_at: to_datetime(created_at),
+        }
+    }
+}
+
+impl fmt::Display for WhitelistEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}) | von {} | {}",
+            self.player_name, self.player_uuid, self.issuer, self.created_at
+        )
+    }
+}
+
+/// Seconds of a duration like `30m`, `12h`, `7d` or `2w`
+pub fn parse_punishment_duration(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let unit = value.chars().last()?;
+    let amount = value[..value.len() - unit.len_utf8()]
+        .parse::<i64>()
+        .ok()
+        .filter(|amount| *amount > 0)?;
+    let factor = match unit {
+        's' => 1,
+        'm' => 60,
+        'h' => 3600,
+        'd' => 86400,
+        'w' => 604800,
+        _ => return None,
+    };
+    amount.checked_mul(factor)
+}
+
+fn to_datetime(timestamp: i64) -> NaiveDateTime {
+    DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .naive_utc()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn ban(expires_at: Option<i64>, revoked: bool) -> Punishment {
+        Punishment::new(
+            1,
+            PunishmentType::Ban,
+            Uuid::nil(),
+            String::from("Steve"),
+            String::from("Hacking"),
+            String::from("console"),
+            NOW - 3600,
+            expires_at,
+            revoked,
+        )
+    }
+
+    #[test]
+    fn temporary_ban_is_active_until_it_expires() {
+        assert!(ban(Some(NOW + 60), false).is_active(NOW));
+        assert!(ban(Some(NOW + 1), false).is_active(NOW));
+    }
+
+    #[test]
+    fn expired_ban_is_not_active() {
+        assert!(!ban(Some(NOW), false).is_active(NOW));
+        assert!(!ban(Some(NOW - 60), false).is_active(NOW));
+    }
+
+    #[test]
+    fn permanent_ban_never_expires() {
+        assert!(ban(None, false).is_active(NOW));
+        assert!(ban(None, false).is_active(i64::MAX));
+    }
+
+    #[test]
+    fn revoked_ban_is_not_active() {
+        assert!(!ban(None, true).is_active(NOW));
+        assert!(!ban(Some(NOW + 60), true).is_active(NOW));
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_punishment_duration("30m"), Some(1800));
+        assert_eq!(parse_punishment_duration("12h"), Some(43200));
+        assert_eq!(parse_punishment_duration("7d"), Some(604800));
+        assert_eq!(parse_punishment_duration("2w"), Some(1209600));
+        assert_eq!(parse_punishment_duration("0d"), None);
+        assert_eq!(parse_punishment_duration("Hacking"), None);
+        assert_eq!(parse_punishment_duration("5ä"), None);
+    }
+}
@@ -0,0 +1,165 @@
+use chrono::{NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const MB: u64 = 1024 * 1024;
+
+/// Resources used by the process of a Service, sampled by the `ResourceMonitor`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ResourceUsage {
+    /// 100% is one full CPU core
+    cpu_percent: f32,
+
+    /// resident memory in bytes
+    memory: u64,
+
+    /// bytes read from disk since the last sample
+    disk_read: u64,
+
+    /// bytes written to disk since the last sample
+    disk_written: u64,
+
+    sampled_at: NaiveDateTime,
+}
+
+impl ResourceUsage {
+    pub fn new(cpu_percent: f32, memory: u64, disk_read: u64, disk_written: u64) -> ResourceUsage {
+        ResourceUsage {
+            cpu_percent,
+            memory,
+            disk_read,
+            disk_written,
+            sampled_at: Utc::now().naive_utc(),
+        }
+    }
+
+    pub fn get_cpu_percent(&self) -> f32 {
+        self.cpu_percent
+    }
+
+    pub fn get_memory(&self) -> u64 {
+        self.memory
+    }
+
+    pub fn get_memory_mb(&self) -> u64 {
+        self.memory / MB
+    }
+
+    pub fn get_disk_read(&self) -> u64 {
+        self.disk_read
+    }
+
+    pub fn get_disk_written(&self) -> u64 {
+        self.disk_written
+    }
+
+    pub fn get_sampled_at(&self) -> NaiveDateTime {
+        self.sampled_at
+    }
+}
+
+impl fmt::Display for ResourceUsage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "CPU: {:.1}% | RAM: {} MB | Disk read: {} KB | Disk written: {} KB",
+            self.cpu_percent,
+            self.get_memory_mb(),
+            self.disk_read / 1024,
+            self.disk_written / 1024
+        )
+    }
+}
+
+/// Resources of the host of a Node, sent to the other Nodes with the heartbeat
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NodeResources {
+    /// average over all cores
+    cpu_percent: f32,
+
+    /// bytes
+    used_memory: u64,
+
+    /// bytes
+    total_memory: u64,
+
+    /// bytes free on the disk of the service folder
+    disk_available: u64,
+
+    /// bytes
+    disk_total: u64,
+
+    /// RAM in MB reserved for the Services of this Node, see `NodeManager::get_local_used_ram`
+    services_ram: u64,
+
+    sampled_at: NaiveDateTime,
+}
+
+impl NodeResources {
+    pub fn new(
+        cpu_percent: f32,
+        used_memory: u64,
+        total_memory: u64,
+        disk_available: u64,
+        disk_total: u64,
+        services_ram: u64,
+    ) -> NodeResources {
+        NodeResources {
+            cpu_percent,
+            used_memory,
+            total_memory,
+            disk_available,
+            disk_total,
+            services_ram,
+            sampled_at: Utc::now().naive_utc(),
+        }
+    }
+
+    pub fn get_cpu_percent(&self) -> f32 {
+        self.cpu_percent
+    }
+
+    pub fn get_used_memory(&self) -> u64 {
+        self.used_memory
+    }
+
+    pub fn get_total_memory(&self) -> u64 {
+        self.total_memory
+    }
+
+    /// RAM in MB which is still free on the host
+    pub fn get_free_memory_mb(&self) -> u64 {
+        self.total_memory.saturating_sub(self.used_memory) / MB
+    }
+
+    pub fn get_disk_available(&self) -> u64 {
+        self.disk_available
+    }
+
+    pub fn get_disk_total(&self) -> u64 {
+        self.disk_total
+    }
+
+    pub fn get_services_ram(&self) -> u64 {
+        self.services_ram
+    }
+
+    pub fn get_sampled_at(&self) -> NaiveDateTime {
+        self.sampled_at
+    }
+}
+
+impl fmt::Display for NodeResources {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "CPU: {:.1}% | RAM: {}/{} MB | Disk: {}/{} GB free | Services: {} MB",
+            self.cpu_percent,
+            self.used_memory / MB,
+            self.total_memory / MB,
+            self.disk_available / (1024 * MB),
+            self.disk_total / (1024 * MB),
+            self.services_ram
+        )
+    }
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+/// Defines what happens when a Service exits without being stopped by the Cloud.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(tag = "mode")]
+pub enum RestartPolicy {
+    /// The Service stays `Failed` until it is started again.
+    #[default]
+    #[serde(rename = "never")]
+    Never,
+
+    /// The Service is restarted up to `max_retries` times.
+    ///
+    /// The wait time before each restart doubles, starting with `backoff_seconds`.
+    #[serde(rename = "on_failure")]
+    OnFailure {
+        max_retries: u32,
+        backoff_seconds: u64,
+    },
+}
+
+impl RestartPolicy {
+    /// Returns the wait time before the given restart attempt (starting by 1),
+    /// or `None` if the Service should not be restarted anymore.
+    pub fn get_backoff(&self, attempt: u32) -> Option<Duration> {
+        match self {
+            RestartPolicy::Never => None,
+            RestartPolicy::OnFailure {
+                max_retries,
+                backoff_seconds,
+            } => {
+                if attempt == 0 || attempt > *max_retries {
+                    return None;
+                }
+                let factor = 2u64.saturating_pow(attempt - 1);
+                Some(Duration::from_secs(backoff_seconds.saturating_mul(factor)))
+            }
+        }
+    }
+}
+
+impl fmt::Display for RestartPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RestartPolicy::Never => write!(f, "never"),
+            RestartPolicy::OnFailure {
+                max_retries,
+                backoff_seconds,
+            } => write!(f, "on_failure ({}x, {}s)", max_retries, backoff_seconds),
+        }
+    }
+}
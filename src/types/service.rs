@@ -5,7 +5,9 @@ use std::sync::Arc;
 
 use crate::config::CloudConfig;
 use crate::types::task::Task;
-use crate::types::{EntityId, ServiceConfig, ServiceStatus};
+use crate::types::{
+    EntityId, MetricsWindow, ResourceUsage, ServiceConfig, ServiceMetrics, ServiceStatus,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Service {
@@ -24,6 +26,19 @@ pub struct Service {
     task_name: String,
     default_connect: bool,
     join_permission: String,
+    /// Failed, but the Restart Policy starts it again after the backoff
+    #[serde(default)]
+    restart_pending: bool,
+    /// Performance samples of the plugin, reset on every start.
+    /// Only kept on the Node of the Service, see `GET /services/{id}/metrics`
+    #[serde(skip)]
+    metrics: MetricsWindow,
+    /// Average TPS of the samples, sent along so the leader of the Task can scale on lag
+    #[serde(default)]
+    average_tps: Option<f64>,
+    /// Last sample of the process, `None` without process
+    #[serde(default)]
+    resources: Option<ResourceUsage>,
 }
 
 impl Service {
@@ -43,7 +58,11 @@ impl Service {
             task_name: task.get_name(),
             default_connect: task.default_connect(),
             join_permission: task.get_join_permission().to_string(),
+            restart_pending: false,
             config: ServiceConfig::from(task),
+            metrics: MetricsWindow::default(),
+            average_tps: None,
+            resources: None,
         }
     }
 
@@ -58,9 +77,23 @@ impl Service {
         self.status
     }
     pub fn set_status(&mut self, status: ServiceStatus) {
+        if status == ServiceStatus::Starting {
+            self.metrics.clear();
+            self.average_tps = None;
+        }
+        if status != ServiceStatus::Failed {
+            self.restart_pending = false;
+        }
         self.status = status;
     }
 
+    pub fn is_restart_pending(&self) -> bool {
+        self.restart_pending
+    }
+    pub fn set_restart_pending(&mut self, pending: bool) {
+        self.restart_pending = pending;
+    }
+
     pub fn get_parent_node(&self) -> &str {
         &self.parent_node
     }
@@ -86,7 +119,12 @@ impl Service {
     }
 
     pub fn start_idle_timer(&mut self) {
-        self.idle_since = Some(Utc::now().naive_utc());
+        if self.idle_since.is_none() {
+            self.idle_since = Some(Utc::now().naive_utc());
+        }
+    }
+    pub fn stop_idle_timer(&mut self) {
+        self.idle_since = None;
     }
 
     pub fn get_server_listener(&self) -> &Address {
@@ -126,6 +164,30 @@ impl Service {
         &self.config
     }
 
+    pub fn get_metrics(&self) -> &MetricsWindow {
+        &self.metrics
+    }
+    pub fn add_metrics(&mut self, metrics: ServiceMetrics) {
+        self.metrics.push(metrics);
+        self.average_tps = self.metrics.get_average_tps();
+    }
+
+    pub fn get_average_tps(&self) -> Option<f64> {
+        self.average_tps
+    }
+
+    /// `true` if the average TPS of the last minute is below `min_tps`, `min_tps` 0 disables the check
+    pub fn is_lagging(&self, min_tps: f64) -> bool {
+        min_tps > 0.0 && self.average_tps.is_some_and(|tps| tps < min_tps)
+    }
+
+    pub fn get_resources(&self) -> Option<&ResourceUsage> {
+        self.resources.as_ref()
+    }
+    pub fn set_resource_usage(&mut self, usage: Option<ResourceUsage>) {
+        self.resources = usage;
+    }
+
     pub fn is_proxy(&self) -> bool {
         self.config.get_software().get_software_type().is_proxy()
     }
@@ -138,7 +200,10 @@ impl Service {
     }
 
     pub fn is_start(&self) -> bool {
-        self.status == ServiceStatus::Starting || self.status == ServiceStatus::Running
+        matches!(
+            self.status,
+            ServiceStatus::Starting | ServiceStatus::Running | ServiceStatus::Degraded
+        )
     }
 
     pub fn is_running(&self) -> bool {
@@ -152,6 +217,10 @@ impl Service {
         )
     }
 
+    pub fn is_degraded(&self) -> bool {
+        self.status == ServiceStatus::Degraded
+    }
+
     pub fn is_failed(&self) -> bool {
         self.status == ServiceStatus::Failed
     }
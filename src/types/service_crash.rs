@@ -0,0 +1,47 @@
+use chrono::{NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A unexpected exit of a Service process
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ServiceCrash {
+    crashed_at: NaiveDateTime,
+    exit_code: Option<i32>,
+    stderr_tail: Vec<String>,
+}
+
+impl ServiceCrash {
+    pub fn new(exit_code: Option<i32>, stderr_tail: Vec<String>) -> ServiceCrash {
+        ServiceCrash {
+            crashed_at: Utc::now().naive_utc(),
+            exit_code,
+            stderr_tail,
+        }
+    }
+
+    pub fn get_crashed_at(&self) -> NaiveDateTime {
+        self.crashed_at
+    }
+
+    pub fn get_exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
+
+    pub fn get_stderr_tail(&self) -> &Vec<String> {
+        &self.stderr_tail
+    }
+}
+
+impl fmt::Display for ServiceCrash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let exit_code = match self.exit_code {
+            Some(code) => code.to_string(),
+            None => String::from("killed by signal"),
+        };
+        writeln!(f, "[{}] Exit Code: {}", self.crashed_at, exit_code)?;
+        for line in &self.stderr_tail {
+            writeln!(f, "  {}", line)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,240 @@
+use chrono::{DateTime, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+use crate::types::ServiceStatus;
+
+/// What happened to a Service, stored in `t_service_events`
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ServiceEventType {
+    #[serde(rename = "created")]
+    Created,
+
+    #[serde(rename = "starting")]
+    Starting,
+
+    /// the plugin registered, the Service accepts Players
+    #[serde(rename = "online")]
+    Online,
+
+    #[serde(rename = "degraded")]
+    Degraded,
+
+    #[serde(rename = "stopping")]
+    Stopping,
+
+    #[serde(rename = "stopped")]
+    Stopped,
+
+    /// the process exited without being stopped by the Cloud
+    #[serde(rename = "crashed")]
+    Crashed,
+
+    /// started again by the Restart Policy
+    #[serde(rename = "restarted")]
+    Restarted,
+
+    /// could not be started again, the Service stays down
+    #[serde(rename = "failed")]
+    Failed,
+
+    #[serde(rename = "deleted")]
+    Deleted,
+}
+
+impl ServiceEventType {
+    pub fn all() -> [ServiceEventType; 10] {
+        [
+            ServiceEventType::Created,
+            ServiceEventType::Starting,
+            ServiceEventType::Online,
+            ServiceEventType::Degraded,
+            ServiceEventType::Stopping,
+            ServiceEventType::Stopped,
+            ServiceEventType::Crashed,
+            ServiceEventType::Restarted,
+            ServiceEventType::Failed,
+            ServiceEventType::Deleted,
+        ]
+    }
+
+    pub fn parse(value: &str) -> Option<ServiceEventType> {
+        ServiceEventType::all()
+            .into_iter()
+            .find(|t| t.to_string() == value.trim())
+    }
+
+    /// The event of a status change
+    pub fn from_status(status: ServiceStatus) -> ServiceEventType {
+        match status {
+            ServiceStatus::Starting => ServiceEventType::Starting,
+            ServiceStatus::Running => ServiceEventType::Online,
+            ServiceStatus::Degraded => ServiceEventType::Degraded,
+            ServiceStatus::Stopping => ServiceEventType::Stopping,
+            ServiceStatus::Stopped => ServiceEventType::Stopped,
+            ServiceStatus::Failed => ServiceEventType::Failed,
+        }
+    }
+}
+
+impl fmt::Display for ServiceEventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match self {
+            ServiceEventType::Created => "created",
+            ServiceEventType::Starting => "starting",
+            ServiceEventType::Online => "online",
+            ServiceEventType::Degraded => "degraded",
+            ServiceEventType::Stopping => "stopping",
+            ServiceEventType::Stopped => "stopped",
+            ServiceEventType::Crashed => "crashed",
+            ServiceEventType::Restarted => "restarted",
+            ServiceEventType::Failed => "failed",
+            ServiceEventType::Deleted => "deleted",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+/// A stored lifecycle event of a Service
+#[derive(Serialize, Clone, Debug)]
+pub struct ServiceEvent {
+    id: u64,
+    service_uuid: Uuid,
+    service_name: String,
+    task: String,
+    node: String,
+    event_type: ServiceEventType,
+    message: Option<String>,
+    occurred_at: NaiveDateTime,
+}
+
+impl ServiceEvent {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: u64,
+        service_uuid: Uuid,
+        service_name: String,
+        task: String,
+        node: String,
+        event_type: ServiceEventType,
+        message: Option<String>,
+        timestamp: i64,
+    ) -> ServiceEvent {
+        ServiceEvent {
+            id,
+            service_uuid,
+            service_name,
+            task,
+            node,
+            event_type,
+            message,
+            occurred_at: DateTime::from_timestamp(timestamp, 0)
+                .unwrap_or_default()
+                .naive_utc(),
+        }
+    }
+
+    pub fn get_id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn get_service_uuid(&self) -> &Uuid {
+        &self.service_uuid
+    }
+
+    pub fn get_service_name(&self) -> &str {
+        &self.service_name
+    }
+
+    pub fn get_task(&self) -> &str {
+        &self.task
+    }
+
+    pub fn get_node(&self) -> &str {
+        &self.node
+    }
+
+    pub fn get_event_type(&self) -> ServiceEventType {
+        self.event_type
+    }
+
+    pub fn get_message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    pub fn get_occurred_at(&self) -> NaiveDateTime {
+        self.occurred_at
+    }
+}
+
+impl fmt::Display for ServiceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] {} {} (Node: {})",
+            self.occurred_at, self.service_name, self.event_type, self.node
+        )?;
+        if let Some(message) = &self.message {
+            write!(f, ": {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+/// Which events to load, every `None` matches all
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct ServiceEventFilter {
+    #[serde(default)]
+    service: Option<Uuid>,
+
+    #[serde(default)]
+    task: Option<String>,
+
+    /// unix seconds, inclusive
+    #[serde(default)]
+    from: Option<i64>,
+
+    /// unix seconds, inclusive
+    #[serde(default)]
+    to: Option<i64>,
+
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+impl ServiceEventFilter {
+    pub fn new() -> ServiceEventFilter {
+        ServiceEventFilter::default()
+    }
+
+    pub fn service(mut self, service: Uuid) -> ServiceEventFilter {
+        self.service = Some(service);
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> ServiceEventFilter {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn get_service(&self) -> Option<&Uuid> {
+        self.service.as_ref()
+    }
+
+    pub fn get_task(&self) -> Option<&str> {
+        self.task.as_deref()
+    }
+
+    pub fn get_limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    pub fn get_from(&self) -> Option<i64> {
+        self.from
+    }
+
+    pub fn get_to(&self) -> Option<i64> {
+        self.to
+    }
+}
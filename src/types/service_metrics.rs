@@ -0,0 +1,130 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Samples older than this are dropped from the window
+const METRICS_WINDOW_SECONDS: i64 = 60;
+
+/// Upper bound, so a plugin sending too often cannot fill the memory
+const METRICS_WINDOW_MAX_SAMPLES: usize = 120;
+
+/// Performance sample sent by the plugin with the `metrics` message
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+pub struct ServiceMetrics {
+    #[serde(default)]
+    #[serde(rename = "tps")]
+    tps: f64,
+
+    /// milliseconds per tick
+    #[serde(default)]
+    #[serde(rename = "mspt")]
+    mspt: f64,
+
+    /// bytes
+    #[serde(default)]
+    #[serde(rename = "heap_used")]
+    heap_used: u64,
+
+    /// bytes
+    #[serde(default)]
+    #[serde(rename = "heap_max")]
+    heap_max: u64,
+
+    #[serde(default)]
+    #[serde(rename = "loaded_chunks")]
+    loaded_chunks: u32,
+
+    #[serde(default)]
+    #[serde(rename = "entities")]
+    entities: u32,
+
+    /// set by the Node on receive, the value of the plugin is ignored
+    #[serde(default = "now")]
+    #[serde(rename = "recorded_at")]
+    #[schemars(skip)]
+    recorded_at: NaiveDateTime,
+}
+
+impl ServiceMetrics {
+    pub fn get_tps(&self) -> f64 {
+        self.tps
+    }
+
+    pub fn get_mspt(&self) -> f64 {
+        self.mspt
+    }
+
+    pub fn get_heap_used(&self) -> u64 {
+        self.heap_used
+    }
+
+    pub fn get_heap_max(&self) -> u64 {
+        self.heap_max
+    }
+
+    pub fn get_loaded_chunks(&self) -> u32 {
+        self.loaded_chunks
+    }
+
+    pub fn get_entities(&self) -> u32 {
+        self.entities
+    }
+
+    pub fn get_recorded_at(&self) -> NaiveDateTime {
+        self.recorded_at
+    }
+}
+
+/// The samples of the last minute, oldest first
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(transparent)]
+pub struct MetricsWindow {
+    samples: VecDeque<ServiceMetrics>,
+}
+
+impl MetricsWindow {
+    pub fn push(&mut self, mut metrics: ServiceMetrics) {
+        metrics.recorded_at = now();
+        self.samples.push_back(metrics);
+
+        let oldest = now() - Duration::seconds(METRICS_WINDOW_SECONDS);
+        while let Some(sample) = self.samples.front() {
+            if sample.recorded_at >= oldest && self.samples.len() <= METRICS_WINDOW_MAX_SAMPLES {
+                break;
+            }
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    pub fn get_samples(&self) -> &VecDeque<ServiceMetrics> {
+        &self.samples
+    }
+
+    pub fn get_latest(&self) -> Option<&ServiceMetrics> {
+        self.samples.back()
+    }
+
+    pub fn get_average_tps(&self) -> Option<f64> {
+        self.average(|m| m.tps)
+    }
+
+    pub fn get_average_mspt(&self) -> Option<f64> {
+        self.average(|m| m.mspt)
+    }
+
+    fn average(&self, value: impl Fn(&ServiceMetrics) -> f64) -> Option<f64> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        Some(self.samples.iter().map(value).sum::<f64>() / self.samples.len() as f64)
+    }
+}
+
+fn now() -> NaiveDateTime {
+    Utc::now().naive_utc()
+}
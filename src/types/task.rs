@@ -7,6 +7,7 @@ use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 use crate::types::installer::Installer;
 use crate::types::join_strategy::JoinStrategy;
+use crate::types::restart_policy::RestartPolicy;
 use crate::types::software_link::SoftwareLink;
 use crate::types::template::Template;
 
@@ -119,6 +120,31 @@ pub struct Task {
     /// Prevents continuous service creation and removal.
     scale_cooldown_seconds: u32,
 
+    /// Time a service must stay at or below `empty_percent` before it is stopped.
+    ///
+    /// Default is `60`.
+    #[serde(default = "default_idle_timeout_seconds")]
+    idle_timeout_seconds: u32,
+
+    /// Defines if and how often a crashed service is restarted.
+    ///
+    /// Default is `never`.
+    #[serde(default)]
+    restart_policy: RestartPolicy,
+
+    /// Average TPS of the last minute below which a service counts as full.
+    ///
+    /// Lets the scheduler start a new service when the existing ones lag.
+    /// `0` disables the check.
+    #[serde(default)]
+    min_tps: f64,
+
+    /// Defines whether this task is in maintenance.
+    ///
+    /// Its services keep running but get no new players.
+    #[serde(default)]
+    maintenance: bool,
+
     /// Deprecated: Percentage used to detect unused services.
     ///
     /// Use `empty_percent` instead.
@@ -177,6 +203,10 @@ impl Task {
             empty_percent: 5,
             min_available_services: 2,
             scale_cooldown_seconds: 30,
+            idle_timeout_seconds: default_idle_timeout_seconds(),
+            restart_policy: RestartPolicy::Never,
+            min_tps: 0.0,
+            maintenance: false,
             groups: Vec::new(),
             installer: Installer::InstallAll,
             templates: vec![template],
@@ -345,6 +375,36 @@ impl Task {
         self.scale_cooldown_seconds = value;
     }
 
+    pub fn get_idle_timeout_seconds(&self) -> u32 {
+        self.idle_timeout_seconds
+    }
+
+    pub fn set_idle_timeout_seconds(&mut self, value: u32) {
+        self.idle_timeout_seconds = value;
+    }
+
+    pub fn get_restart_policy(&self) -> &RestartPolicy {
+        &self.restart_policy
+    }
+
+    pub fn set_restart_policy(&mut self, restart_policy: RestartPolicy) {
+        self.restart_policy = restart_policy;
+    }
+
+    pub fn get_min_tps(&self) -> f64 {
+        self.min_tps
+    }
+    pub fn set_min_tps(&mut self, value: f64) {
+        self.min_tps = value.max(0.0);
+    }
+
+    pub fn is_maintenance(&self) -> bool {
+        self.maintenance
+    }
+    pub fn set_maintenance(&mut self, value: bool) {
+        self.maintenance = value;
+    }
+
     #[deprecated]
     pub fn get_percent_of_players_to_check_should_auto_stop_the_service(&self) -> u32 {
         self.percent_of_players_to_check_should_auto_stop_the_service
@@ -482,3 +542,7 @@ impl Clone for TaskRef {
         Self(self.0.clone())
     }
 }
+
+fn default_idle_timeout_seconds() -> u32 {
+    60
+}
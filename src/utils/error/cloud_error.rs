@@ -116,14 +116,51 @@ where
 
 impl actix_web::ResponseError for CloudError {
     fn status_code(&self) -> StatusCode {
-        StatusCode::INTERNAL_SERVER_ERROR
+        match self.kind {
+            CloudErrorKind::InvalidRequest
+            | CloudErrorKind::DeserializationError
+            | CloudErrorKind::CantParseToValue => StatusCode::BAD_REQUEST,
+
+            CloudErrorKind::TaskNotFound
+            | CloudErrorKind::CantFindTaskFromName
+            | CloudErrorKind::CantFindGroupFromName
+            | CloudErrorKind::TemplateNotFound
+            | CloudErrorKind::GroupTemplateNotFound
+            | CloudErrorKind::ServiceNotFound
+            | CloudErrorKind::CantFindServiceFromUUID
+            | CloudErrorKind::PlayerNotFound
+            | CloudErrorKind::PlayerNotOnline
+            | CloudErrorKind::PunishmentNotFound
+            | CloudErrorKind::NodeNotFound
+            | CloudErrorKind::CantFindSoftware => StatusCode::NOT_FOUND,
+
+            CloudErrorKind::TaskAlreadyExists | CloudErrorKind::ServiceHasNoProcess => {
+                StatusCode::CONFLICT
+            }
+
+            CloudErrorKind::ClusterUnauthorized | CloudErrorKind::ApiUnauthorized => {
+                StatusCode::UNAUTHORIZED
+            }
+            CloudErrorKind::ApiForbidden => StatusCode::FORBIDDEN,
+
+            CloudErrorKind::InsufficientCapacity | CloudErrorKind::CantReachNode => {
+                StatusCode::SERVICE_UNAVAILABLE
+            }
+
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
     }
 
     fn error_response(&self) -> HttpResponse {
-        log_error!(1, "[API] {}", self.to_string());
+        let status = self.status_code();
+        if status.is_server_error() {
+            log_error!(1, "[API] {}", self.to_string());
+        }
 
-        HttpResponse::build(self.status_code()).json(serde_json::json!({
-            "Error": self.to_string()
+        HttpResponse::build(status).json(serde_json::json!({
+            "code": self.kind.code(),
+            "error": format!("{:?}", self.kind),
+            "message": self.source_message.as_deref().unwrap_or(self.kind.message()),
         }))
     }
 }
@@ -23,6 +23,19 @@ pub enum CloudErrorKind {
     /// 13.xxx api internal
     CantSerializeOutgoingMsg,
     CantSendWSAnswer,
+    ServiceHasNoSession,
+    RequestTimeout,
+    RequestFailed,
+    CantRegisterOnProxy,
+    CantUnregisterFromProxy,
+
+    /// 14.xxx api external
+    InvalidRequest,
+    PlayerNotFound,
+    ApiUnauthorized,
+    ApiForbidden,
+    PlayerNotOnline,
+    PunishmentNotFound,
 
     /// 2x.xxx CloudSystem
     CantFetchSoftwareIndex,
@@ -62,6 +75,9 @@ pub enum CloudErrorKind {
 
     CantCreateSTDOUTFile,
     CantCreateSTDERRFile,
+    CantReadSTDOUTFile,
+    CantWriteSTDIN,
+    ServiceHasNoProcess,
 
     CantStartServer,
     CantSendShutdownRequest,
@@ -89,6 +105,14 @@ pub enum CloudErrorKind {
 
     CantBindAddress,
     CantStartAPIServer,
+
+    /// 7x.xxx Node API
+    NodeNotFound,
+    CantReachNode,
+    ClusterRequestFailed,
+    ClusterUnauthorized,
+    InsufficientCapacity,
+    ClusterSecretMissing,
     /// 9.xxx
     /// Internal System
     IoError,
@@ -102,6 +126,21 @@ impl CloudErrorKind {
             // 10.xxx Directory
             CloudErrorKind::NextFreePortNotFound => 120001,
 
+            // 13.xxx api internal
+            CloudErrorKind::ServiceHasNoSession => 130001,
+            CloudErrorKind::RequestTimeout => 130002,
+            CloudErrorKind::RequestFailed => 130003,
+            CloudErrorKind::CantRegisterOnProxy => 130004,
+            CloudErrorKind::CantUnregisterFromProxy => 130005,
+
+            // 14.xxx api external
+            CloudErrorKind::InvalidRequest => 140001,
+            CloudErrorKind::PlayerNotFound => 140002,
+            CloudErrorKind::ApiUnauthorized => 140003,
+            CloudErrorKind::ApiForbidden => 140004,
+            CloudErrorKind::PlayerNotOnline => 140005,
+            CloudErrorKind::PunishmentNotFound => 140006,
+
             // 2x.xxx CloudSystem
 
             // 21.xxx Task
@@ -124,6 +163,12 @@ impl CloudErrorKind {
             CloudErrorKind::CantCreateDBRecord => 60005,
             //7x.xxx Node API
             CloudErrorKind::CantFindServiceFromUUID => 310001,
+            CloudErrorKind::NodeNotFound => 70001,
+            CloudErrorKind::CantReachNode => 70002,
+            CloudErrorKind::ClusterRequestFailed => 70003,
+            CloudErrorKind::ClusterUnauthorized => 70004,
+            CloudErrorKind::InsufficientCapacity => 70005,
+            CloudErrorKind::ClusterSecretMissing => 70006,
 
             // 9.xxx
             // Internal System
@@ -141,6 +186,23 @@ impl CloudErrorKind {
             CloudErrorKind::TaskNotFound => "Task nicht gefunden",
             CloudErrorKind::ServiceNotFound => "Service nicht gefunden",
             CloudErrorKind::TemplateNotFound => "Template nicht gefunden",
+            CloudErrorKind::NodeNotFound => "Node nicht gefunden",
+            CloudErrorKind::CantReachNode => "Node nicht erreichbar",
+            CloudErrorKind::InsufficientCapacity => "Nicht genug Kapazität im Cluster",
+            CloudErrorKind::ClusterSecretMissing => "Cluster Seeds ohne Cluster Secret",
+            CloudErrorKind::ServiceHasNoSession => "Service ist nicht verbunden",
+            CloudErrorKind::RequestTimeout => "Keine Antwort vom Service",
+            CloudErrorKind::RequestFailed => "Anfrage fehlgeschlagen",
+            CloudErrorKind::InvalidRequest => "Ungültige Anfrage",
+            CloudErrorKind::PlayerNotFound => "Spieler nicht gefunden",
+            CloudErrorKind::PlayerNotOnline => "Spieler ist nicht online",
+            CloudErrorKind::PunishmentNotFound => "Kein aktiver Eintrag für den Spieler",
+            CloudErrorKind::ApiUnauthorized => "Nicht angemeldet",
+            CloudErrorKind::ApiForbidden => "Keine Berechtigung",
+            CloudErrorKind::CantFindTaskFromName => "Task nicht gefunden",
+            CloudErrorKind::CantFindGroupFromName => "Gruppe nicht gefunden",
+            CloudErrorKind::TaskAlreadyExists => "Task existiert bereits",
+            CloudErrorKind::ServiceHasNoProcess => "Service läuft nicht",
             CloudErrorKind::IoError => "IO Fehler",
             CloudErrorKind::Internal => "Interner Fehler",
             _ => "NUll",
@@ -0,0 +1,224 @@
+use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Counters and timings recorded while the Node runs, exported by the `metrics` feature.
+///
+/// State like Services per Task is not stored here, the exporter reads it from the
+/// managers on every scrape.
+pub static METRICS: Lazy<CloudMetrics> = Lazy::new(CloudMetrics::new);
+
+/// Upper bounds in seconds
+const LATENCY_BUCKETS: &[f64] = &[0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0];
+const COPY_BUCKETS: &[f64] = &[0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+
+pub struct CloudMetrics {
+    /// (task, direction) -> count
+    scaling_actions: Mutex<BTreeMap<(String, String), u64>>,
+    /// task -> count
+    service_crashes: Mutex<BTreeMap<String, u64>>,
+    /// query -> timings
+    db_latency: Mutex<BTreeMap<String, Histogram>>,
+    /// task -> timings
+    template_copy: Mutex<BTreeMap<String, Histogram>>,
+}
+
+impl CloudMetrics {
+    fn new() -> CloudMetrics {
+        CloudMetrics {
+            scaling_actions: Mutex::new(BTreeMap::new()),
+            service_crashes: Mutex::new(BTreeMap::new()),
+            db_latency: Mutex::new(BTreeMap::new()),
+            template_copy: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// `direction` is `up` or `down`
+    pub fn scaling_action(&self, task: &str, direction: &str) {
+        let mut actions = lock(&self.scaling_actions);
+        *actions
+            .entry((task.to_string(), direction.to_string()))
+            .or_insert(0) += 1;
+    }
+
+    pub fn service_crashed(&self, task: &str) {
+        *lock(&self.service_crashes)
+            .entry(task.to_string())
+            .or_insert(0) += 1;
+    }
+
+    pub fn observe_db_latency(&self, query: &str, duration: Duration) {
+        lock(&self.db_latency)
+            .entry(query.to_string())
+            .or_insert_with(|| Histogram::new(LATENCY_BUCKETS))
+            .observe(duration);
+    }
+
+    pub fn observe_template_copy(&self, task: &str, duration: Duration) {
+        lock(&self.template_copy)
+            .entry(task.to_string())
+            .or_insert_with(|| Histogram::new(COPY_BUCKETS))
+            .observe(duration);
+    }
+
+    /// Append the recorded values in the Prometheus text format
+    pub fn write_prometheus(&self, out: &mut String) {
+        let _ = writeln!(
+            out,
+            "# HELP minecloud_scaling_actions_total Services started or stopped by the Scheduler"
+        );
+        let _ = writeln!(out, "# TYPE minecloud_scaling_actions_total counter");
+        for ((task, direction), count) in lock(&self.scaling_actions).iter() {
+            let _ = writeln!(
+                out,
+                "minecloud_scaling_actions_total{{task=\"{}\",direction=\"{}\"}} {}",
+                escape(task),
+                escape(direction),
+                count
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP minecloud_service_crashes_total Unexpected exits of Service processes"
+        );
+        let _ = writeln!(out, "# TYPE minecloud_service_crashes_total counter");
+        for (task, count) in lock(&self.service_crashes).iter() {
+            let _ = writeln!(
+                out,
+                "minecloud_service_crashes_total{{task=\"{}\"}} {}",
+                escape(task),
+                count
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP minecloud_db_latency_seconds Duration of database queries"
+        );
+        let _ = writeln!(out, "# TYPE minecloud_db_latency_seconds histogram");
+        for (query, histogram) in lock(&self.db_latency).iter() {
+            histogram.write_prometheus(
+                out,
+                "minecloud_db_latency_seconds",
+                &format!("query=\"{}\"", escape(query)),
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP minecloud_template_copy_seconds Duration of copying the templates of a Service"
+        );
+        let _ = writeln!(out, "# TYPE minecloud_template_copy_seconds histogram");
+        for (task, histogram) in lock(&self.template_copy).iter() {
+            histogram.write_prometheus(
+                out,
+                "minecloud_template_copy_seconds",
+                &format!("task=\"{}\"", escape(task)),
+            );
+        }
+    }
+}
+
+struct Histogram {
+    bounds: &'static [f64],
+    /// not cumulative, one more than `bounds` for `+Inf`
+    counts: Vec<u64>,
+    sum: f64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Histogram {
+        Histogram {
+            bounds,
+            counts: vec![0; bounds.len() + 1],
+            sum: 0.0,
+        }
+    }
+
+    fn observe(&mut self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        let index = self
+            .bounds
+            .iter()
+            .position(|bound| seconds <= *bound)
+            .unwrap_or(self.bounds.len());
+        self.counts[index] += 1;
+        self.sum += seconds;
+    }
+
+    fn write_prometheus(&self, out: &mut String, name: &str, labels: &str) {
+        let mut cumulative = 0;
+        for (bound, count) in self.bounds.iter().zip(&self.counts) {
+            cumulative += count;
+            let _ = writeln!(
+                out,
+                "{}_bucket{{{},le=\"{}\"}} {}",
+                name, labels, bound, cumulative
+            );
+        }
+        cumulative += self.counts[self.bounds.len()];
+        let _ = writeln!(
+            out,
+            "{}_bucket{{{},le=\"+Inf\"}} {}",
+            name, labels, cumulative
+        );
+        let _ = writeln!(out, "{}_sum{{{}}} {}", name, labels, self.sum);
+        let _ = writeln!(out, "{}_count{{{}}} {}", name, labels, cumulative);
+    }
+}
+
+/// Escape a label value for the Prometheus text format
+pub fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_buckets_are_cumulative_and_end_with_inf() {
+        let mut histogram = Histogram::new(&[0.1, 1.0]);
+        histogram.observe(Duration::from_millis(50));
+        histogram.observe(Duration::from_millis(500));
+        histogram.observe(Duration::from_millis(700));
+        histogram.observe(Duration::from_secs(3));
+
+        let mut out = String::new();
+        histogram.write_prometheus(&mut out, "test_seconds", "query=\"q\"");
+
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], "test_seconds_bucket{query=\"q\",le=\"0.1\"} 1");
+        assert_eq!(lines[1], "test_seconds_bucket{query=\"q\",le=\"1\"} 3");
+        assert_eq!(lines[2], "test_seconds_bucket{query=\"q\",le=\"+Inf\"} 4");
+        let sum: f64 = lines[3]
+            .strip_prefix("test_seconds_sum{query=\"q\"} ")
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((sum - 4.25).abs() < 1e-9);
+        assert_eq!(lines[4], "test_seconds_count{query=\"q\"} 4");
+        assert_eq!(lines.len(), 5);
+    }
+
+    #[test]
+    fn empty_histogram_exports_zero_counts() {
+        let histogram = Histogram::new(LATENCY_BUCKETS);
+        let mut out = String::new();
+        histogram.write_prometheus(&mut out, "test_seconds", "query=\"q\"");
+
+        assert_eq!(out.lines().count(), LATENCY_BUCKETS.len() + 3);
+        assert!(out.contains("test_seconds_bucket{query=\"q\",le=\"+Inf\"} 0"));
+        assert!(out.contains("test_seconds_count{query=\"q\"} 0"));
+    }
+}
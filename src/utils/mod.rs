@@ -1,3 +1,4 @@
 pub mod error;
 pub mod log;
+pub mod metrics;
 pub mod utils;
@@ -17,6 +17,15 @@ use crate::{error, log_error};
 pub struct Utils;
 
 impl Utils {
+    /// Compares every byte, so the time does not tell how much of a secret was right
+    pub fn constant_time_eq(a: &str, b: &str) -> bool {
+        a.len() == b.len()
+            && a.bytes()
+                .zip(b.bytes())
+                .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    }
+
     pub fn convert_to_json<T>(value: &T) -> Option<serde_json::Value>
     where
         T: ?Sized + Serialize,